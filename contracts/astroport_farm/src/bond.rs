@@ -1,13 +1,15 @@
-use astroport::asset::{Asset, token_asset};
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, token_asset};
+use astroport::pair::PoolResponse;
 use astroport::querier::query_token_balance;
-use cosmwasm_std::{attr, Addr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, Coin, Decimal};
+use cosmwasm_std::{attr, Addr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, Coin, Decimal, StdError, QuerierWrapper, Storage};
 
 use crate::error::ContractError;
-use crate::state::{ScalingOperation, CONFIG, REWARD, STATE, Config};
+use crate::state::{ScalingOperation, CONFIG, REWARD, STATE, Config, PAIR_PROXY, State};
 
 use cw20::{Expiration};
 
 use baz::adapters::asset::AssetEx;
+use baz::adapters::pair::Pair;
 use baz::astroport_farm::{RewardInfoResponse, RewardInfoResponseItem, CallbackMsg};
 use baz::helper::{ScalingUint128};
 
@@ -155,12 +157,15 @@ fn bond_internal(
         .may_load(deps.storage, &staker_addr)?
         .unwrap_or_default();
 
+    let fee_amount = amount * config.deposit_fee;
+    let bond_amount = amount.checked_sub(fee_amount)?;
+
     // convert amount to share & update
-    let bond_share = state.calc_bond_share(amount, lp_balance, ScalingOperation::Truncate);
+    let bond_share = state.calc_bond_share(bond_amount, lp_balance, ScalingOperation::Truncate);
     state.total_bond_share += bond_share;
 
     let deposit_amount = state.calc_bond_amount(
-        lp_balance + amount,
+        lp_balance + bond_amount,
         bond_share,
     );
 
@@ -169,16 +174,84 @@ fn bond_internal(
     reward_info.bond(bond_share, deposit_amount, env.block.time.seconds(), &pool_info)?;
 
     REWARD.save(deps.storage, &staker_addr, &reward_info)?;
+
+    let mut stake_amount = bond_amount;
+    if !fee_amount.is_zero() {
+        if config.fee_in_shares {
+            mint_fee_shares(
+                deps.storage,
+                &config,
+                &mut state,
+                lp_balance + bond_amount,
+                fee_amount,
+                env.block.time.seconds(),
+                &pool_info,
+            )?;
+            stake_amount = amount;
+        } else {
+            messages.extend(fee_transfer_msgs(
+                &config,
+                token_asset(config.liquidity_token.clone(), fee_amount),
+            )?);
+        }
+    }
+
     STATE.save(deps.storage, &state)?;
 
-    messages.push(config.staking_contract.deposit_msg(config.liquidity_token.to_string(), amount)?);
+    messages.push(config.staking_contract.deposit_msg(config.liquidity_token.to_string(), stake_amount)?);
     Ok(Response::new().add_messages(messages).add_attributes(vec![
         attr("action", "bond"),
         attr("amount", amount),
-        attr("bond_amount", amount),
+        attr("bond_amount", bond_amount),
+        attr("fee_amount", fee_amount),
     ]))
 }
 
+/// ## Description
+/// Returns the messages to transfer the fee asset to the fee recipients.
+pub fn fee_transfer_msgs(config: &Config, fee: Asset) -> StdResult<Vec<CosmosMsg>> {
+    config
+        .split_fee(fee.amount)?
+        .into_iter()
+        .filter(|(_, amount)| !amount.is_zero())
+        .map(|(recipient, amount)| fee.info.with_balance(amount).transfer_msg(&recipient))
+        .collect()
+}
+
+/// ## Description
+/// Mints bond shares worth **fee_amount** LP to the fee recipients.
+/// **lp_balance** is the LP amount held for the existing shares, excluding the fee.
+pub fn mint_fee_shares(
+    storage: &mut dyn Storage,
+    config: &Config,
+    state: &mut State,
+    lp_balance: Uint128,
+    fee_amount: Uint128,
+    time: u64,
+    pool_info: &PoolResponse,
+) -> StdResult<()> {
+    let mut lp_balance = lp_balance;
+    for (recipient, amount) in config.split_fee(fee_amount)? {
+        if amount.is_zero() {
+            continue;
+        }
+
+        let share = state.calc_bond_share(amount, lp_balance, ScalingOperation::Truncate);
+        state.total_bond_share += share;
+        lp_balance += amount;
+
+        let mut reward_info = REWARD.may_load(storage, &recipient)?.unwrap_or_default();
+        reward_info.ensure_deposit_costs(storage)?;
+        reward_info.bond(share, amount, time, pool_info)?;
+        REWARD.save(storage, &recipient, &reward_info)?;
+    }
+
+    Ok(())
+}
+
+/// The max spread to swap withdrawn assets, the output is protected by the minimum receive
+const MAX_SPREAD: u64 = 50;
+
 /// ## Description
 /// Unbond LP token of sender
 pub fn unbond(
@@ -187,24 +260,48 @@ pub fn unbond(
     info: MessageInfo,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
+    let staker_addr = info.sender;
+
+    let config = CONFIG.load(deps.storage)?;
+    let staking_token = config.liquidity_token.clone();
+
+    let (unbond_amount, fee_messages) = unbond_internal(deps, &env, &config, &staker_addr, amount)?;
+
+    Ok(Response::new()
+        .add_messages(fee_messages)
+        .add_messages(vec![
+            config.staking_contract.withdraw_msg(staking_token.to_string(), unbond_amount)?,
+            token_asset(staking_token, unbond_amount).transfer_msg(&staker_addr)?,
+        ])
+        .add_attributes(vec![
+            attr("action", "unbond"),
+            attr("staker_addr", staker_addr),
+            attr("amount", amount),
+        ]))
+}
+
+/// Internal unbond function used by unbond and unbond_assets.
+/// Returns the LP amount to withdraw for the staker after the withdraw and exit fees, and the messages to pay the fees.
+fn unbond_internal(
+    deps: DepsMut,
+    env: &Env,
+    config: &Config,
+    staker_addr: &Addr,
+    amount: Uint128,
+) -> Result<(Uint128, Vec<CosmosMsg>), ContractError> {
 
     if amount.is_zero() {
         return Err(ContractError::InvalidZeroAmount {});
     }
 
-    let staker_addr = info.sender;
-
-    let config = CONFIG.load(deps.storage)?;
-    let staking_token = config.liquidity_token;
-
     let lp_balance = config.staking_contract.query_deposit(
         &deps.querier,
-        &staking_token,
+        &config.liquidity_token,
         &env.contract.address,
     )?;
 
     let mut state = STATE.load(deps.storage)?;
-    let mut reward_info = REWARD.load(deps.storage, &staker_addr)?;
+    let mut reward_info = REWARD.load(deps.storage, staker_addr)?;
 
     let user_balance = reward_info.calc_user_balance(
         &state,
@@ -216,26 +313,333 @@ pub fn unbond(
         return Err(ContractError::UnbondExceedBalance {});
     }
 
+    let deposit_time = reward_info.deposit_time;
     let bond_share = reward_info.bond_share.multiply_ratio_and_ceil(amount, user_balance);
     state.total_bond_share = state.total_bond_share.checked_sub(bond_share)?;
     reward_info.unbond(bond_share)?;
+    REWARD.save(deps.storage, staker_addr, &reward_info)?;
+
+    // the exit fee stays in the vault for the remaining depositors, unless nobody is left
+    let withdraw_fee_amount = amount * config.withdraw_fee;
+    let exit_fee_amount = config.calc_exit_fee(amount, deposit_time, env.block.time.seconds());
+    let (fee_amount, depositor_fee_amount) = match &config.exit_fee {
+        Some(exit_fee) if exit_fee.to_depositors && !state.total_bond_share.is_zero() => {
+            (withdraw_fee_amount, exit_fee_amount)
+        }
+        _ => (withdraw_fee_amount + exit_fee_amount, Uint128::zero()),
+    };
+
+    let mut fee_messages: Vec<CosmosMsg> = vec![];
+    if !fee_amount.is_zero() {
+        if config.fee_in_shares {
+            let pool_info = config.pair.query_pool_info(&deps.querier)?;
+            mint_fee_shares(
+                deps.storage,
+                config,
+                &mut state,
+                lp_balance.checked_sub(amount)? + depositor_fee_amount,
+                fee_amount,
+                env.block.time.seconds(),
+                &pool_info,
+            )?;
+        } else {
+            fee_messages.push(
+                config.staking_contract.withdraw_msg(config.liquidity_token.to_string(), fee_amount)?,
+            );
+            fee_messages.extend(fee_transfer_msgs(
+                config,
+                token_asset(config.liquidity_token.clone(), fee_amount),
+            )?);
+        }
+    }
 
     // update state
     STATE.save(deps.storage, &state)?;
-    REWARD.save(deps.storage, &staker_addr, &reward_info)?;
 
+    Ok((amount.checked_sub(withdraw_fee_amount + exit_fee_amount)?, fee_messages))
+}
+
+/// ## Description
+/// Unbond LP token of sender and withdraw the underlying assets from the pair.
+/// When **target_asset** is set, all withdrawn assets are swapped into the target asset,
+/// and **minimum_receive** is required to bound the swaps.
+pub fn unbond_assets(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    target_asset: Option<AssetInfo>,
+    minimum_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    if target_asset.is_none() && minimum_receive.is_some() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "minimum_receive requires target_asset",
+        )));
+    }
+    if target_asset.is_some() && minimum_receive.is_none() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "target_asset requires minimum_receive",
+        )));
+    }
+
+    let staker_addr = info.sender;
+
+    let config = CONFIG.load(deps.storage)?;
+    if let Some(target_asset) = &target_asset {
+        target_asset.check(deps.api)?;
+        query_swap_route(&deps.querier, deps.storage, &config, target_asset)?;
+    }
+
+    let (unbond_amount, fee_messages) = unbond_internal(deps, &env, &config, &staker_addr, amount)?;
+
+    // withdraw liquidity after rewards are claimed, so they are not mistaken for withdrawn assets
     Ok(Response::new()
+        .add_messages(fee_messages)
         .add_messages(vec![
-            config.staking_contract.withdraw_msg(staking_token.to_string(), amount)?,
-            token_asset(staking_token, amount).transfer_msg(&staker_addr)?,
+            config.staking_contract.withdraw_msg(config.liquidity_token.to_string(), unbond_amount)?,
+            CallbackMsg::WithdrawLiquidity {
+                to: staker_addr.clone(),
+                amount: unbond_amount,
+                target_asset,
+                minimum_receive,
+            }
+            .into_cosmos_msg(&env.contract.address)?,
         ])
         .add_attributes(vec![
-            attr("action", "unbond"),
+            attr("action", "unbond_assets"),
             attr("staker_addr", staker_addr),
             attr("amount", amount),
         ]))
 }
 
+/// ## Description
+/// Returns the pair asset to swap withdrawn assets into and the pair proxy to swap it to **target_asset**.
+/// The pair proxy is not needed when **target_asset** is one of the pair assets.
+fn query_swap_route(
+    querier: &QuerierWrapper,
+    storage: &dyn Storage,
+    config: &Config,
+    target_asset: &AssetInfo,
+) -> StdResult<(AssetInfo, Option<Pair>)> {
+    let pair_info = config.pair.query_pair_info(querier)?;
+    if pair_info.asset_infos.contains(target_asset) {
+        return Ok((target_asset.clone(), None));
+    }
+
+    let pair_proxy = PAIR_PROXY
+        .may_load(storage, target_asset.to_string())?
+        .ok_or_else(|| StdError::generic_err(format!("No pair proxy for {}", target_asset)))?;
+    let pair_proxy_info = pair_proxy.query_pair_info(querier)?;
+    let ask_asset_info = pair_proxy_info
+        .asset_infos
+        .into_iter()
+        .find(|asset_info| pair_info.asset_infos.contains(asset_info))
+        .ok_or_else(|| StdError::generic_err("Invalid pair proxy"))?;
+
+    Ok((ask_asset_info, Some(pair_proxy)))
+}
+
+/// ## Description
+/// Burns LP token at the pair and schedules swapping and sending the withdrawn assets.
+pub fn withdraw_liquidity(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    to: Addr,
+    amount: Uint128,
+    target_asset: Option<AssetInfo>,
+    minimum_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let pair_info = config.pair.query_pair_info(&deps.querier)?;
+    let mut asset_infos = pair_info.asset_infos;
+    if let Some(target_asset) = &target_asset {
+        if !asset_infos.contains(target_asset) {
+            asset_infos.push(target_asset.clone());
+        }
+    }
+    let prev_balances = asset_infos
+        .into_iter()
+        .map(|asset_info| {
+            let balance = asset_info.query_pool(&deps.querier, &env.contract.address)?;
+            Ok(asset_info.with_balance(balance))
+        })
+        .collect::<StdResult<Vec<Asset>>>()?;
+
+    let mut messages: Vec<CosmosMsg> = vec![
+        config.pair.withdraw_liquidity_msg(config.liquidity_token.to_string(), amount)?,
+    ];
+
+    if let Some(target_asset) = &target_asset {
+        let (ask_asset_info, pair_proxy) =
+            query_swap_route(&deps.querier, deps.storage, &config, target_asset)?;
+        messages.push(
+            CallbackMsg::SwapAssets {
+                prev_balances: prev_balances.clone(),
+                target_asset: ask_asset_info,
+            }
+            .into_cosmos_msg(&env.contract.address)?,
+        );
+        if pair_proxy.is_some() {
+            messages.push(
+                CallbackMsg::SwapAssets {
+                    prev_balances: prev_balances.clone(),
+                    target_asset: target_asset.clone(),
+                }
+                .into_cosmos_msg(&env.contract.address)?,
+            );
+        }
+    }
+
+    messages.push(
+        CallbackMsg::SendAssets {
+            to,
+            prev_balances,
+            target_asset,
+            minimum_receive,
+        }
+        .into_cosmos_msg(&env.contract.address)?,
+    );
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "withdraw_liquidity"))
+}
+
+/// ## Description
+/// Swaps the assets received since **prev_balances** into **target_asset**,
+/// on the pair if both assets are in the pair, otherwise on the pair proxy of **target_asset**.
+pub fn swap_assets(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    prev_balances: Vec<Asset>,
+    target_asset: AssetInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let pair_info = config.pair.query_pair_info(&deps.querier)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for prev_balance in prev_balances {
+        if prev_balance.info == target_asset {
+            continue;
+        }
+        let balance = prev_balance.info.query_pool(&deps.querier, &env.contract.address)?;
+        let amount = balance.checked_sub(prev_balance.amount)?;
+        if amount.is_zero() {
+            continue;
+        }
+
+        let swap_asset = prev_balance.info.with_balance(amount);
+        let pair = if pair_info.asset_infos.contains(&swap_asset.info)
+            && pair_info.asset_infos.contains(&target_asset)
+        {
+            config.pair.clone()
+        } else {
+            PAIR_PROXY.load(deps.storage, target_asset.to_string())?
+        };
+        messages.push(pair.swap_msg(
+            &swap_asset,
+            Some(target_asset.clone()),
+            None,
+            Some(Decimal::percent(MAX_SPREAD)),
+            None,
+        )?);
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "swap_assets"))
+}
+
+/// ## Description
+/// Sends the assets received since **prev_balances** to **to**.
+/// Fails if the amount of **target_asset** received is less than **minimum_receive**.
+pub fn send_assets(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    to: Addr,
+    prev_balances: Vec<Asset>,
+    target_asset: Option<AssetInfo>,
+    minimum_receive: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for prev_balance in prev_balances {
+        let balance = prev_balance.info.query_pool(&deps.querier, &env.contract.address)?;
+        let amount = balance.checked_sub(prev_balance.amount)?;
+
+        if let (Some(target_asset), Some(minimum_receive)) = (&target_asset, minimum_receive) {
+            if prev_balance.info == *target_asset && amount < minimum_receive {
+                return Err(ContractError::AssertionMinimumReceive {
+                    minimum_receive,
+                    amount,
+                });
+            }
+        }
+
+        if !amount.is_zero() {
+            messages.push(prev_balance.info.with_balance(amount).transfer_msg(&to)?);
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "send_assets")
+        .add_attribute("to", to))
+}
+
+/// ## Description
+/// Returns the assets received from unbonding **amount** of LP with [`unbond_assets`].
+pub fn query_simulate_unbond_assets(
+    deps: Deps,
+    amount: Uint128,
+    target_asset: Option<AssetInfo>,
+) -> StdResult<Vec<Asset>> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = amount.checked_sub(amount * config.withdraw_fee)?;
+    let assets = config.pair.query_share(&deps.querier, amount)?;
+
+    let target_asset = match target_asset {
+        Some(target_asset) => target_asset,
+        None => return Ok(assets),
+    };
+
+    let (ask_asset_info, pair_proxy) =
+        query_swap_route(&deps.querier, deps.storage, &config, &target_asset)?;
+    let mut ask_amount = Uint128::zero();
+    for asset in assets {
+        if asset.amount.is_zero() {
+            continue;
+        }
+        if asset.info == ask_asset_info {
+            ask_amount += asset.amount;
+        } else {
+            ask_amount += config
+                .pair
+                .simulate(&deps.querier, &asset, Some(ask_asset_info.clone()))?
+                .return_amount;
+        }
+    }
+
+    let return_amount = match pair_proxy {
+        Some(pair_proxy) if !ask_amount.is_zero() => {
+            pair_proxy
+                .simulate(
+                    &deps.querier,
+                    &ask_asset_info.with_balance(ask_amount),
+                    Some(target_asset.clone()),
+                )?
+                .return_amount
+        }
+        Some(_) => Uint128::zero(),
+        None => ask_amount,
+    };
+
+    Ok(vec![target_asset.with_balance(return_amount)])
+}
+
 /// ## Description
 /// Returns reward info for the staker.
 pub fn query_reward_info(
@@ -259,7 +663,7 @@ fn read_reward_info(deps: Deps, env: Env, staker_addr: &Addr) -> StdResult<Rewar
         .unwrap_or_default();
     let state = STATE.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
-    let staking_token = config.liquidity_token;
+    let staking_token = config.liquidity_token.clone();
 
     let lp_balance = config.staking_contract.query_deposit(
         &deps.querier,
@@ -273,6 +677,8 @@ fn read_reward_info(deps: Deps, env: Env, staker_addr: &Addr) -> StdResult<Rewar
         env.block.time.seconds(),
     );
     let total_share = reward_info.bond_share + reward_info.transfer_share;
+    let exit_fee = bond_amount * config.withdraw_fee
+        + config.calc_exit_fee(bond_amount, reward_info.deposit_time, env.block.time.seconds());
     reward_info.ensure_deposit_costs(deps.storage)?;
     Ok(RewardInfoResponseItem {
         staking_token: staking_token.to_string(),
@@ -291,6 +697,7 @@ fn read_reward_info(deps: Deps, env: Env, staker_addr: &Addr) -> StdResult<Rewar
             reward_info.deposit_costs.iter()
                 .map(|it| it.multiply_ratio(reward_info.bond_share, total_share))
                 .collect()
-        }
+        },
+        exit_fee,
     })
 }
@@ -4,8 +4,9 @@ use astroport::{
 use cosmwasm_std::{attr, Attribute, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, Uint128, Decimal};
 
 use crate::{
+    bond::{fee_transfer_msgs, mint_fee_shares},
     error::ContractError,
-    state::CONFIG,
+    state::{CONFIG, STATE},
 };
 
 use cw20::{Expiration};
@@ -17,7 +18,8 @@ use baz::adapters::asset::AssetEx;
 use baz::astroport_farm::CallbackMsg;
 
 /// ## Description
-/// Performs compound by sending LP rewards to compound proxy and reinvest received LP token
+/// Performs compound by sending LP rewards to compound proxy and reinvest received LP token.
+/// When permissionless compound is enabled, anyone may compound once per minimum interval and receives the bounty.
 pub fn compound(
     deps: DepsMut,
     env: Env,
@@ -28,12 +30,32 @@ pub fn compound(
 
     let config = CONFIG.load(deps.storage)?;
 
-    // Only controller can call this function
-    if info.sender != config.controller {
-        return Err(ContractError::Unauthorized {});
+    let mut state = STATE.load(deps.storage)?;
+    let now = env.block.time.seconds();
+
+    // Only controller can call this function, unless permissionless compound is enabled
+    let is_controller = info.sender == config.controller;
+    if !is_controller {
+        if !config.permissionless_compound {
+            return Err(ContractError::Unauthorized {});
+        }
+        let next_compound = state.last_compound.saturating_add(config.min_compound_interval);
+        if now < next_compound {
+            return Err(ContractError::CompoundTooEarly { next_compound });
+        }
     }
 
-    let staking_token = config.liquidity_token;
+    state.last_compound = now;
+    STATE.save(deps.storage, &state)?;
+
+    // other callers cannot loosen the swap limits, the compound proxy applies its configured slippage tolerance
+    let (bounty, minimum_receive, slippage_tolerance) = if is_controller {
+        (Decimal::zero(), minimum_receive, slippage_tolerance)
+    } else {
+        (config.compound_bounty, None, None)
+    };
+
+    let staking_token = config.liquidity_token.clone();
 
     let pending_token = config.staking_contract.query_pending_token(
         &deps.querier,
@@ -47,7 +69,12 @@ pub fn compound(
         &env.contract.address,
     )?;
 
-    let total_fee = config.fee;
+    // when fees are paid in shares, the commission is taken from the compounded LP in the stake callback
+    let total_fee = if config.fee_in_shares {
+        Decimal::zero()
+    } else {
+        config.fee
+    };
 
     let mut messages: Vec<CosmosMsg> = vec![];
     let mut attributes: Vec<Attribute> = vec![];
@@ -61,7 +88,7 @@ pub fn compound(
     messages.push(claim_rewards);
 
     rewards.push(
-        token_asset(config.base_reward_token, pending_token.pending),
+        token_asset(config.base_reward_token.clone(), pending_token.pending),
     );
     if let Some(pending_on_proxy) = pending_token.pending_on_proxy {
         rewards.extend(pending_on_proxy);
@@ -72,11 +99,14 @@ pub fn compound(
         let reward_amount = asset.amount;
         if !reward_amount.is_zero() && !lp_balance.is_zero() {
             let commission_amount = reward_amount * total_fee;
-            let compound_amount = reward_amount.checked_sub(commission_amount)?;
+            let bounty_amount = reward_amount * bounty;
+            let compound_amount = reward_amount
+                .checked_sub(commission_amount)?
+                .checked_sub(bounty_amount)?;
             if !compound_amount.is_zero() {
                 let compound_asset = asset.info.with_balance(compound_amount);
                 if let AssetInfo::NativeToken { denom } = &asset.info {
-                    compound_funds.push(Coin { denom: denom.clone(), amount: asset.amount });
+                    compound_funds.push(Coin { denom: denom.clone(), amount: compound_amount });
                 } else {
                     let increase_allowance = compound_asset.increase_allowance_msg(
                         config.compound_proxy.0.to_string(),
@@ -89,13 +119,21 @@ pub fn compound(
 
             if !commission_amount.is_zero() {
                 let commission_asset = asset.info.with_balance(commission_amount);
-                let transfer_fee = commission_asset.transfer_msg(&config.fee_collector)?;
-                messages.push(transfer_fee);
+                messages.extend(fee_transfer_msgs(&config, commission_asset)?);
+            }
+
+            if !bounty_amount.is_zero() {
+                let bounty_asset = asset.info.with_balance(bounty_amount);
+                let transfer_bounty = bounty_asset.transfer_msg(&info.sender)?;
+                messages.push(transfer_bounty);
             }
 
             attributes.push(attr("token", asset.info.to_string()));
             attributes.push(attr("compound_amount", compound_amount));
             attributes.push(attr("commission_amount", commission_amount));
+            if !bounty_amount.is_zero() {
+                attributes.push(attr("bounty_amount", bounty_amount));
+            }
         }
     }
 
@@ -130,7 +168,7 @@ pub fn stake(
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
-    let staking_token = config.liquidity_token;
+    let staking_token = config.liquidity_token.clone();
 
     let balance = query_token_balance(&deps.querier, &staking_token, &env.contract.address)?;
     let amount = balance - prev_balance;
@@ -144,6 +182,27 @@ pub fn stake(
         }
     }
 
+    let fee_amount = amount * config.fee;
+    if config.fee_in_shares && !fee_amount.is_zero() {
+        let lp_balance = config.staking_contract.query_deposit(
+            &deps.querier,
+            &staking_token,
+            &env.contract.address,
+        )?;
+        let pool_info = config.pair.query_pool_info(&deps.querier)?;
+        let mut state = STATE.load(deps.storage)?;
+        mint_fee_shares(
+            deps.storage,
+            &config,
+            &mut state,
+            lp_balance + amount - fee_amount,
+            fee_amount,
+            env.block.time.seconds(),
+            &pool_info,
+        )?;
+        STATE.save(deps.storage, &state)?;
+    }
+
     Ok(Response::new()
         .add_message(
             config.staking_contract.deposit_msg(staking_token.to_string(), amount)?
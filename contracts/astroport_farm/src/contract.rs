@@ -1,10 +1,15 @@
+use astroport::asset::AssetInfo;
 use cosmwasm_std::{
     attr, entry_point, from_binary, to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo,
-    Response, StdError, StdResult, Uint128,
+    Order, Response, StdError, StdResult, Uint128,
 };
+use cw_storage_plus::Bound;
 
 use crate::{
-    bond::{bond, bond_assets, bond_to},
+    bond::{
+        bond, bond_assets, bond_to, query_simulate_unbond_assets, send_assets, swap_assets,
+        unbond_assets, withdraw_liquidity,
+    },
     compound::{compound, stake},
     error::ContractError,
     ownership::{claim_ownership, drop_ownership_proposal, propose_new_owner},
@@ -16,18 +21,23 @@ use baz::adapters::generator::Generator;
 use baz::adapters::pair::Pair;
 
 use crate::bond::{query_reward_info, unbond};
-use crate::state::{STATE};
+use crate::state::{PAIR_PROXY, STATE};
 use baz::astroport_farm::{
-    CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    CallbackMsg, Cw20HookMsg, ExecuteMsg, ExitFee, InstantiateMsg, MigrateMsg, QueryMsg,
 };
 use baz::compound_proxy::Compounder;
 use crate::cw20::{execute_burn, execute_burn_from, execute_decrease_allowance, execute_increase_allowance, execute_send, execute_send_from, execute_transfer, execute_transfer_from, query_all_accounts, query_all_allowances, query_allowance, query_balance, query_token_info};
 
+/// The maximum deposit and withdraw fee, in percent
+const MAX_DEPOSIT_WITHDRAW_FEE_PERCENT: u64 = 5;
+/// The maximum exit fee, in percent
+const MAX_EXIT_FEE_PERCENT: u64 = 10;
+
 /// ## Description
-/// Validates that decimal value is in the range 0 to 1
-fn validate_percentage(value: Decimal, field: &str) -> StdResult<()> {
-    if value > Decimal::one() {
-        Err(StdError::generic_err(field.to_string() + " must be 0 to 1"))
+/// Validates that decimal value is in the range 0 to max
+fn validate_percentage(value: Decimal, max: Decimal, field: &str) -> StdResult<()> {
+    if value > max {
+        Err(StdError::generic_err(format!("{} must be 0 to {}", field, max)))
     } else {
         Ok(())
     }
@@ -44,7 +54,7 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     msg.validate()?;
-    validate_percentage(msg.fee, "fee")?;
+    validate_percentage(msg.fee, Decimal::one(), "fee")?;
 
     CONFIG.save(
         deps.storage,
@@ -60,6 +70,14 @@ pub fn instantiate(
             pair: Pair(deps.api.addr_validate(&msg.pair)?),
             name: msg.name,
             symbol: msg.symbol,
+            permissionless_compound: false,
+            min_compound_interval: 0,
+            compound_bounty: Decimal::zero(),
+            fee_recipients: vec![],
+            deposit_fee: Decimal::zero(),
+            withdraw_fee: Decimal::zero(),
+            fee_in_shares: false,
+            exit_fee: None,
         },
     )?;
 
@@ -67,6 +85,7 @@ pub fn instantiate(
         deps.storage,
         &State {
             total_bond_share: Uint128::zero(),
+            last_compound: 0,
         },
     )?;
 
@@ -90,7 +109,31 @@ pub fn execute(
             fee,
             fee_collector,
         } => update_config(deps, info, compound_proxy, controller, fee, fee_collector),
+        ExecuteMsg::UpdateCompoundConfig {
+            permissionless,
+            min_interval,
+            bounty,
+        } => update_compound_config(deps, info, permissionless, min_interval, bounty),
+        ExecuteMsg::UpdateFeeConfig {
+            fee_recipients,
+            deposit_fee,
+            withdraw_fee,
+            fee_in_shares,
+        } => update_fee_config(
+            deps,
+            info,
+            fee_recipients,
+            deposit_fee,
+            withdraw_fee,
+            fee_in_shares,
+        ),
+        ExecuteMsg::UpdateExitFee { exit_fee } => update_exit_fee(deps, info, exit_fee),
         ExecuteMsg::Unbond { amount } => unbond(deps, env, info, amount),
+        ExecuteMsg::UnbondAssets {
+            amount,
+            target_asset,
+            minimum_receive,
+        } => unbond_assets(deps, env, info, amount, target_asset, minimum_receive),
         ExecuteMsg::BondAssets {
             assets,
             minimum_receive,
@@ -109,6 +152,9 @@ pub fn execute(
             minimum_receive,
             slippage_tolerance,
         } => compound(deps, env, info, minimum_receive, slippage_tolerance),
+        ExecuteMsg::UpdatePairProxies { add, remove } => {
+            update_pair_proxies(deps, info, add, remove)
+        }
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config: Config = CONFIG.load(deps.storage)?;
 
@@ -205,7 +251,8 @@ pub fn update_config(
     }
 
     if let Some(fee) = fee {
-        validate_percentage(fee, "fee")?;
+        validate_percentage(fee, Decimal::one(), "fee")?;
+        validate_percentage(fee + config.compound_bounty, Decimal::one(), "fee + bounty")?;
         config.fee = fee;
     }
 
@@ -218,6 +265,150 @@ pub fn update_config(
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
 }
 
+/// ## Description
+/// Updates the permissionless compound settings. Returns a [`ContractError`] on failure or the [`CONFIG`] data will be updated.
+pub fn update_compound_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    permissionless: Option<bool>,
+    min_interval: Option<u64>,
+    bounty: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(permissionless) = permissionless {
+        config.permissionless_compound = permissionless;
+    }
+
+    if let Some(min_interval) = min_interval {
+        config.min_compound_interval = min_interval;
+    }
+
+    if let Some(bounty) = bounty {
+        validate_percentage(bounty, Decimal::one(), "bounty")?;
+        validate_percentage(config.fee + bounty, Decimal::one(), "fee + bounty")?;
+        config.compound_bounty = bounty;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_compound_config")]))
+}
+
+/// ## Description
+/// Updates the fee recipients and the deposit and withdraw fees. Returns a [`ContractError`] on failure or the [`CONFIG`] data will be updated.
+pub fn update_fee_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_recipients: Option<Vec<(String, u64)>>,
+    deposit_fee: Option<Decimal>,
+    withdraw_fee: Option<Decimal>,
+    fee_in_shares: Option<bool>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(fee_recipients) = fee_recipients {
+        config.fee_recipients = fee_recipients
+            .into_iter()
+            .map(|(recipient, weight)| {
+                if weight == 0 {
+                    return Err(StdError::generic_err("fee recipient weight must be positive"));
+                }
+                Ok((deps.api.addr_validate(&recipient)?, weight))
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        config.total_fee_weight()?;
+    }
+
+    let max_fee = Decimal::percent(MAX_DEPOSIT_WITHDRAW_FEE_PERCENT);
+    if let Some(deposit_fee) = deposit_fee {
+        validate_percentage(deposit_fee, max_fee, "deposit_fee")?;
+        config.deposit_fee = deposit_fee;
+    }
+
+    if let Some(withdraw_fee) = withdraw_fee {
+        validate_percentage(withdraw_fee, max_fee, "withdraw_fee")?;
+        config.withdraw_fee = withdraw_fee;
+    }
+
+    if let Some(fee_in_shares) = fee_in_shares {
+        config.fee_in_shares = fee_in_shares;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_fee_config")]))
+}
+
+/// ## Description
+/// Sets or disables the exit fee. Returns a [`ContractError`] on failure or the [`CONFIG`] data will be updated.
+pub fn update_exit_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    exit_fee: Option<ExitFee>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(exit_fee) = &exit_fee {
+        validate_percentage(
+            exit_fee.max_fee,
+            Decimal::percent(MAX_EXIT_FEE_PERCENT),
+            "exit fee",
+        )?;
+        if exit_fee.period == 0 {
+            return Err(StdError::generic_err("exit fee period must be positive").into());
+        }
+    }
+
+    config.exit_fee = exit_fee;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_exit_fee")]))
+}
+
+/// ## Description
+/// Adds or removes pair proxies used to swap withdrawn assets. Returns a [`ContractError`] on failure.
+pub fn update_pair_proxies(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<(AssetInfo, String)>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(remove) = remove {
+        for asset_info in remove {
+            PAIR_PROXY.remove(deps.storage, asset_info.to_string());
+        }
+    }
+
+    if let Some(add) = add {
+        for (asset_info, pair_proxy) in add {
+            asset_info.check(deps.api)?;
+            let pair_proxy_addr = deps.api.addr_validate(&pair_proxy)?;
+            PAIR_PROXY.save(deps.storage, asset_info.to_string(), &Pair(pair_proxy_addr))?;
+        }
+    }
+
+    Ok(Response::new().add_attribute("action", "update_pair_proxies"))
+}
+
 /// # Description
 /// Handle the callbacks describes in the [`CallbackMsg`]. Returns an [`ContractError`] on failure, otherwise returns the [`Response`]
 pub fn handle_callback(
@@ -240,6 +431,22 @@ pub fn handle_callback(
             prev_balance,
             minimum_receive,
         } => bond_to(deps, env, info, to, prev_balance, minimum_receive),
+        CallbackMsg::WithdrawLiquidity {
+            to,
+            amount,
+            target_asset,
+            minimum_receive,
+        } => withdraw_liquidity(deps, env, info, to, amount, target_asset, minimum_receive),
+        CallbackMsg::SwapAssets {
+            prev_balances,
+            target_asset,
+        } => swap_assets(deps, env, info, prev_balances, target_asset),
+        CallbackMsg::SendAssets {
+            to,
+            prev_balances,
+            target_asset,
+            minimum_receive,
+        } => send_assets(deps, env, info, to, prev_balances, target_asset, minimum_receive),
     }
 }
 
@@ -253,6 +460,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             to_binary(&query_reward_info(deps, env, staker_addr)?)
         }
         QueryMsg::State {} => to_binary(&query_state(deps)?),
+        QueryMsg::SimulateUnbondAssets {
+            amount,
+            target_asset,
+        } => to_binary(&query_simulate_unbond_assets(deps, amount, target_asset)?),
+        QueryMsg::PairProxies { start_after, limit } => {
+            to_binary(&query_pair_proxies(deps, start_after, limit)?)
+        }
 
         // cw20
         QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
@@ -280,6 +494,26 @@ fn query_state(deps: Deps) -> StdResult<State> {
     Ok(state)
 }
 
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns the list of target asset and the pair proxy used to swap into it.
+fn query_pair_proxies(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Pair)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    PAIR_PROXY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
 /// ## Description
 /// Used for contract migration. Returns a default object of type [`Response`].
 #[cfg_attr(not(feature = "library"), entry_point)]
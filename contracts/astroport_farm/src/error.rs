@@ -34,6 +34,9 @@ pub enum ContractError {
 
     #[error("Duplicated asset")]
     DuplicatedAsset {},
+
+    #[error("Compound is not available until {next_compound}")]
+    CompoundTooEarly { next_compound: u64 },
 }
 
 impl From<OverflowError> for ContractError {
@@ -4,9 +4,10 @@ use cosmwasm_std::testing::{MockApi, MockStorage};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use astroport::asset::{native_asset, token_asset};
+use astroport::asset::{native_asset, token_asset, Asset, AssetInfo, PairInfo};
+use astroport::factory::PairType;
 use astroport::generator::{PendingTokenResponse};
-use astroport::pair::PoolResponse;
+use astroport::pair::{PoolResponse, SimulationResponse};
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let custom_querier: WasmMockQuerier = WasmMockQuerier::new();
@@ -21,6 +22,9 @@ pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
 
 const ASTRO_TOKEN: &str = "astro";
 const REWARD_TOKEN: &str = "reward";
+const LP_TOKEN: &str = "lp_token";
+const IBC_TOKEN: &str = "ibc/stablecoin";
+const PAIR_PROXY: &str = "pair_proxy";
 
 pub struct WasmMockQuerier {
     balances: HashMap<(String, String), Uint128>,
@@ -112,6 +116,38 @@ impl WasmMockQuerier {
                         native_asset("denom2".to_string(), Uint128::from(1_000_000u128)),
                     ]
                 })
+            },
+            MockQueryMsg::Pair {} => {
+                let (asset_infos, pair_type) = if contract_addr == PAIR_PROXY {
+                    (vec![native_info("denom2"), native_info(IBC_TOKEN)], PairType::Custom("pair_proxy".to_string()))
+                } else {
+                    (vec![native_info("denom1"), native_info("denom2")], PairType::Xyk {})
+                };
+                to_binary(&PairInfo {
+                    asset_infos,
+                    contract_addr: Addr::unchecked(contract_addr),
+                    liquidity_token: Addr::unchecked(LP_TOKEN),
+                    pair_type,
+                })
+            },
+            MockQueryMsg::Share {
+                amount,
+            } => {
+                to_binary(&vec![
+                    native_asset("denom1".to_string(), amount),
+                    native_asset("denom2".to_string(), amount),
+                ])
+            },
+            MockQueryMsg::Simulation {
+                offer_asset,
+                ..
+            } => {
+                // 1% commission
+                to_binary(&SimulationResponse {
+                    return_amount: offer_asset.amount.multiply_ratio(99u128, 100u128),
+                    spread_amount: Uint128::zero(),
+                    commission_amount: offer_asset.amount.multiply_ratio(1u128, 100u128),
+                })
             }
         }
     }
@@ -132,6 +168,20 @@ enum MockQueryMsg {
         user: String
     },
     Pool {},
+    Pair {},
+    Share {
+        amount: Uint128,
+    },
+    Simulation {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+    },
+}
+
+fn native_info(denom: &str) -> AssetInfo {
+    AssetInfo::NativeToken {
+        denom: denom.to_string(),
+    }
 }
 
 impl Querier for WasmMockQuerier {
@@ -2,11 +2,12 @@ use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Decimal, StdResult, Storage, Uint128};
+use cosmwasm_std::{Addr, Decimal, StdError, StdResult, Storage, Uint128};
 use cw20::AllowanceResponse;
 use astroport::pair::PoolResponse;
 use baz::adapters::generator::Generator;
 use baz::adapters::pair::Pair;
+use baz::astroport_farm::ExitFee;
 use baz::compound_proxy::Compounder;
 use baz::helper::{compute_deposit_time, ScalingUint128};
 
@@ -31,6 +32,59 @@ pub struct Config {
     #[serde(default)] pub name: String,
     #[serde(default)] pub symbol: String,
     #[serde(default = "default_pair")] pub pair: Pair,
+
+    /// permissionless compound
+    #[serde(default)] pub permissionless_compound: bool,
+    #[serde(default)] pub min_compound_interval: u64,
+    #[serde(default)] pub compound_bounty: Decimal,
+
+    /// fee schedule
+    #[serde(default)] pub fee_recipients: Vec<(Addr, u64)>,
+    #[serde(default)] pub deposit_fee: Decimal,
+    #[serde(default)] pub withdraw_fee: Decimal,
+    #[serde(default)] pub fee_in_shares: bool,
+    #[serde(default)] pub exit_fee: Option<ExitFee>,
+}
+
+impl Config {
+    /// Splits the fee amount between the fee recipients by weight, the last recipient receives the remainder.
+    /// All fees go to the fee collector when no recipient is set.
+    pub fn split_fee(&self, amount: Uint128) -> StdResult<Vec<(Addr, Uint128)>> {
+        if self.fee_recipients.is_empty() {
+            return Ok(vec![(self.fee_collector.clone(), amount)]);
+        }
+
+        let total_weight = self.total_fee_weight()?;
+        let mut remaining = amount;
+        let mut fees = vec![];
+        for (i, (recipient, weight)) in self.fee_recipients.iter().enumerate() {
+            let fee = if i == self.fee_recipients.len() - 1 {
+                remaining
+            } else {
+                amount.multiply_ratio(*weight, total_weight)
+            };
+            remaining = remaining.checked_sub(fee)?;
+            fees.push((recipient.clone(), fee));
+        }
+
+        Ok(fees)
+    }
+
+    /// Returns the sum of the fee recipient weights, or an error when it does not fit in u64.
+    pub fn total_fee_weight(&self) -> StdResult<u64> {
+        self.fee_recipients
+            .iter()
+            .try_fold(0u64, |total, (_, weight)| total.checked_add(*weight))
+            .ok_or_else(|| StdError::generic_err("total fee recipient weight overflow"))
+    }
+
+    /// Returns the exit fee of the amount deposited at **deposit_time**
+    pub fn calc_exit_fee(&self, amount: Uint128, deposit_time: u64, time: u64) -> Uint128 {
+        match &self.exit_fee {
+            Some(exit_fee) => amount * exit_fee.rate(time.saturating_sub(deposit_time)),
+            None => Uint128::zero(),
+        }
+    }
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
@@ -38,6 +92,7 @@ pub const CONFIG: Item<Config> = Item::new("config");
 #[derive(Serialize, Deserialize, Clone,Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub total_bond_share: Uint128,
+    #[serde(default)] pub last_compound: u64,
 }
 
 pub const STATE: Item<State> = Item::new("state");
@@ -154,4 +209,7 @@ pub enum ScalingOperation {
 }
 
 pub const ALLOWANCES: Map<(&Addr, &Addr), AllowanceResponse> = Map::new("allowance");
+
+/// Stores the pair proxy used to swap withdrawn assets into the target asset
+pub const PAIR_PROXY: Map<String, Pair> = Map::new("pair_proxy");
 pub const POOL_INFO: Item<PoolResponse> = Item::new("pool_info");
@@ -3,22 +3,23 @@ use crate::error::ContractError;
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
 use crate::state::{Config, State};
 
-use astroport::asset::{Asset, AssetInfo};
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
 use astroport::generator::{
     Cw20HookMsg as GeneratorCw20HookMsg, ExecuteMsg as GeneratorExecuteMsg,
 };
+use astroport::pair::{Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg};
 
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, to_binary, Addr, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError,
-    Timestamp, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError,
+    StdResult, Timestamp, Uint128, WasmMsg,
 };
 use cw20::{AllAccountsResponse, AllAllowancesResponse, AllowanceInfo, AllowanceResponse, BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, Expiration, Logo, MarketingInfoResponse, MinterResponse, TokenInfoResponse};
 use baz::adapters::generator::Generator;
 use baz::adapters::pair::Pair;
 use baz::astroport_farm::{
-    CallbackMsg, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, RewardInfoResponse,
-    RewardInfoResponseItem,
+    CallbackMsg, Cw20HookMsg, ExecuteMsg, ExitFee, ExitFeeCurve, InstantiateMsg, QueryMsg,
+    RewardInfoResponse, RewardInfoResponseItem,
 };
 use baz::compound_proxy::{Compounder, ExecuteMsg as CompoundProxyExecuteMsg};
 
@@ -37,6 +38,7 @@ const CONTROLLER_2: &str = "controller_2";
 const FEE_COLLECTOR_2: &str = "fee_collector_2";
 const LP_TOKEN: &str = "lp_token";
 const IBC_TOKEN: &str = "ibc/stablecoin";
+const PAIR_PROXY: &str = "pair_proxy";
 
 #[test]
 fn test() -> Result<(), ContractError> {
@@ -47,6 +49,7 @@ fn test() -> Result<(), ContractError> {
     bond(&mut deps)?;
     deposit_time(&mut deps)?;
     compound(&mut deps)?;
+    permissionless_compound(&mut deps)?;
     callback(&mut deps)?;
     cw20(&mut deps)?;
 
@@ -119,6 +122,14 @@ fn create(
             name: "name".to_string(),
             symbol: "SYMBOL".to_string(),
             pair: Pair(Addr::unchecked("pair")),
+            permissionless_compound: false,
+            min_compound_interval: 0,
+            compound_bounty: Decimal::zero(),
+            fee_recipients: vec![],
+            deposit_fee: Decimal::zero(),
+            withdraw_fee: Decimal::zero(),
+            fee_in_shares: false,
+            exit_fee: None,
         }
     );
 
@@ -196,6 +207,14 @@ fn config(
             name: "name".to_string(),
             symbol: "SYMBOL".to_string(),
             pair: Pair(Addr::unchecked("pair")),
+            permissionless_compound: false,
+            min_compound_interval: 0,
+            compound_bounty: Decimal::zero(),
+            fee_recipients: vec![],
+            deposit_fee: Decimal::zero(),
+            withdraw_fee: Decimal::zero(),
+            fee_in_shares: false,
+            exit_fee: None,
         }
     );
 
@@ -224,6 +243,14 @@ fn config(
             name: "name".to_string(),
             symbol: "SYMBOL".to_string(),
             pair: Pair(Addr::unchecked("pair")),
+            permissionless_compound: false,
+            min_compound_interval: 0,
+            compound_bounty: Decimal::zero(),
+            fee_recipients: vec![],
+            deposit_fee: Decimal::zero(),
+            withdraw_fee: Decimal::zero(),
+            fee_in_shares: false,
+            exit_fee: None,
         }
     );
 
@@ -383,6 +410,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(100000u128),
                     deposit_amount: Uint128::from(100000u128),
                     deposit_time: 101,
@@ -445,6 +473,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_2.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(50000u128),
                     deposit_amount: Uint128::from(50000u128),
                     deposit_time: 102,
@@ -466,6 +495,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         res,
         State {
             total_bond_share: Uint128::from(150000u128),
+            last_compound: 0,
         }
     );
 
@@ -487,6 +517,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(100000u128),
                     deposit_amount: Uint128::from(100000u128),
                     deposit_time: 101,
@@ -512,6 +543,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_2.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(50000u128),
                     deposit_amount: Uint128::from(50000u128),
                     deposit_time: 102,
@@ -589,6 +621,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(50000u128),
                     deposit_amount: Uint128::from(50000u128),
                     deposit_time: 101,
@@ -614,6 +647,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_2.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(50000u128),
                     deposit_amount: Uint128::from(50000u128),
                     deposit_time: 102,
@@ -677,6 +711,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_2.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(0u128),
                     deposit_amount: Uint128::from(0u128),
                     deposit_time: 102,
@@ -699,6 +734,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(50000u128),
                     deposit_amount: Uint128::from(50000u128),
                     deposit_time: 101,
@@ -935,6 +971,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(58333u128),
                     deposit_amount: Uint128::from(59999u128),
                     deposit_time: 33448,
@@ -956,6 +993,7 @@ fn bond(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         res,
         State {
             total_bond_share: Uint128::from(58333u128),
+            last_compound: 0,
         }
     );
 
@@ -996,6 +1034,7 @@ fn deposit_time(
             staker_addr: USER_3.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(8333u128),
                     deposit_amount: Uint128::from(9999u128),
                     deposit_time: 300000,
@@ -1023,6 +1062,7 @@ fn deposit_time(
             staker_addr: USER_3.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(8333u128),
                     deposit_amount: Uint128::from(9999u128),
                     deposit_time: 300000,
@@ -1048,6 +1088,7 @@ fn deposit_time(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(58333u128),
                     deposit_amount: Uint128::from(59999u128),
                     deposit_time: 33448,
@@ -1076,6 +1117,7 @@ fn deposit_time(
             staker_addr: USER_3.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(8333u128),
                     deposit_amount: Uint128::from(9999u128),
                     deposit_time: 300000,
@@ -1142,6 +1184,7 @@ fn deposit_time(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(58333u128),
                     deposit_amount: Uint128::from(59999u128),
                     deposit_time: 33448,
@@ -1323,6 +1366,142 @@ fn compound(
     Ok(())
 }
 
+fn permissionless_compound(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.height = 700;
+
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+
+    // permissionless compound is disabled by default
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // only owner can update compound config
+    let update_msg = ExecuteMsg::UpdateCompoundConfig {
+        permissionless: Some(true),
+        min_interval: Some(3600),
+        bounty: Some(Decimal::percent(96)),
+    };
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, update_msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // fee + bounty cannot exceed 100%
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), update_msg);
+    assert_error(res, "fee + bounty must be 0 to 1");
+
+    let update_msg = ExecuteMsg::UpdateCompoundConfig {
+        permissionless: Some(true),
+        min_interval: Some(3600),
+        bounty: Some(Decimal::percent(1)),
+    };
+    execute(deps.as_mut(), env.clone(), info, update_msg)?;
+
+    // last compound by controller is in the current block
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(
+        res,
+        &format!(
+            "Compound is not available until {}",
+            env.block.time.seconds() + 3600
+        ),
+    );
+
+    // caller receives the bounty after the interval
+    env.block.time = env.block.time.plus_seconds(3600);
+    let loose_msg = ExecuteMsg::Compound {
+        minimum_receive: Some(Uint128::from(1u128)),
+        slippage_tolerance: Some(Decimal::percent(50)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), loose_msg)?;
+    let msgs: Vec<CosmosMsg> = res.messages.into_iter().map(|it| it.msg).collect();
+    let bounty_msgs: Vec<CosmosMsg> = msgs
+        .iter()
+        .filter(|msg| match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => matches!(
+                from_binary(msg),
+                Ok(Cw20ExecuteMsg::Transfer { recipient, .. }) if recipient == USER_2
+            ),
+            _ => false,
+        })
+        .cloned()
+        .collect();
+    assert_eq!(
+        bounty_msgs,
+        vec![
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_2.to_string(),
+                    amount: Uint128::from(100u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: REWARD_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: USER_2.to_string(),
+                    amount: Uint128::from(500u128)
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // caller's minimum receive and slippage tolerance are ignored
+    assert!(msgs.iter().any(|msg| match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => matches!(
+            from_binary::<CompoundProxyExecuteMsg>(msg),
+            Ok(CompoundProxyExecuteMsg::Compound { slippage_tolerance: None, .. })
+        ),
+        _ => false,
+    }));
+    assert!(msgs.iter().any(|msg| match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => matches!(
+            from_binary::<ExecuteMsg>(msg),
+            Ok(ExecuteMsg::Callback(CallbackMsg::Stake { minimum_receive: None, .. }))
+        ),
+        _ => false,
+    }));
+
+    let res: State = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::State {})?)?;
+    assert_eq!(res.last_compound, env.block.time.seconds());
+
+    // a large interval does not overflow
+    let update_msg = ExecuteMsg::UpdateCompoundConfig {
+        permissionless: None,
+        min_interval: Some(u64::MAX),
+        bounty: None,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), update_msg)?;
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, &format!("Compound is not available until {}", u64::MAX));
+
+    // controller is not rate limited and receives no bounty
+    let info = mock_info(CONTROLLER, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(res.messages.len(), 7);
+
+    // reset compound config
+    let update_msg = ExecuteMsg::UpdateCompoundConfig {
+        permissionless: Some(false),
+        min_interval: Some(0),
+        bounty: Some(Decimal::zero()),
+    };
+    let info = mock_info(OWNER, &[]);
+    execute(deps.as_mut(), env, info, update_msg)?;
+
+    Ok(())
+}
+
 fn callback(
     deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
 ) -> Result<(), ContractError> {
@@ -1392,6 +1571,7 @@ fn cw20(
             staker_addr: USER_3.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(156202u128),
                     deposit_amount: Uint128::from(199999u128),
                     deposit_time: 1571797419,
@@ -1459,6 +1639,7 @@ fn cw20(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(108333u128),
                     deposit_amount: Uint128::from(124018u128),
                     deposit_time: 811389522,
@@ -1484,6 +1665,7 @@ fn cw20(
             staker_addr: FEE_COLLECTOR_2.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(50000u128),
                     deposit_amount: Uint128::from(64019u128),
                     deposit_time: 1571797419,
@@ -1508,6 +1690,7 @@ fn cw20(
             staker_addr: USER_3.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(56202u128),
                     deposit_amount: Uint128::from(71960u128),
                     deposit_time: 1571797419,
@@ -1628,6 +1811,7 @@ fn cw20(
             staker_addr: USER_3.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(106202u128),
                     deposit_amount: Uint128::from(135979u128),
                     deposit_time: 1571797419,
@@ -1678,6 +1862,7 @@ fn cw20(
             staker_addr: FEE_COLLECTOR_2.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(25000u128),
                     deposit_amount: Uint128::from(32009u128),
                     deposit_time: 1571797419,
@@ -1828,6 +2013,7 @@ fn cw20(
             staker_addr: USER_3.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(66202u128),
                     deposit_amount: Uint128::from(84764u128),
                     deposit_time: 1571797419,
@@ -1870,6 +2056,7 @@ fn cw20(
             staker_addr: FEE_COLLECTOR_2.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(65000u128),
                     deposit_amount: Uint128::from(83223u128),
                     deposit_time: 1571797419,
@@ -1894,6 +2081,7 @@ fn cw20(
             staker_addr: USER_3.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(46202u128),
                     deposit_amount: Uint128::from(59156u128),
                     deposit_time: 1571797419,
@@ -2036,6 +2224,7 @@ fn poc_self_transfer(
             staker_addr: USER_1.to_string(),
             reward_info: {
                 RewardInfoResponseItem {
+                    exit_fee: Uint128::zero(),
                     bond_share: Uint128::from(100000u128),
                     deposit_amount: Uint128::from(100000u128),
                     deposit_time: 101,
@@ -2068,4 +2257,801 @@ fn poc_self_transfer(
     assert_eq!(new_res, res);
 
     Ok(())
-}
\ No newline at end of file
+}
+#[test]
+fn test_unbond_assets() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    unbond_assets(&mut deps)?;
+
+    Ok(())
+}
+
+fn unbond_assets(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let env = mock_env();
+    let denom1 = AssetInfo::NativeToken {
+        denom: "denom1".to_string(),
+    };
+    let denom2 = AssetInfo::NativeToken {
+        denom: "denom2".to_string(),
+    };
+    let ibc_token = AssetInfo::NativeToken {
+        denom: IBC_TOKEN.to_string(),
+    };
+
+    // user_1 bond 100000 LP
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+
+    // minimum receive is only for target asset
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::UnbondAssets {
+        amount: Uint128::from(10000u128),
+        target_asset: None,
+        minimum_receive: Some(Uint128::from(10000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "minimum_receive requires target_asset");
+
+    // swaps into target asset must be bounded
+    let msg = ExecuteMsg::UnbondAssets {
+        amount: Uint128::from(10000u128),
+        target_asset: Some(ibc_token.clone()),
+        minimum_receive: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "target_asset requires minimum_receive");
+
+    // target asset without pair proxy
+    let msg = ExecuteMsg::UnbondAssets {
+        amount: Uint128::from(10000u128),
+        target_asset: Some(ibc_token.clone()),
+        minimum_receive: Some(Uint128::from(10000u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "No pair proxy for ibc/stablecoin");
+
+    let msg = ExecuteMsg::UnbondAssets {
+        amount: Uint128::from(10000u128),
+        target_asset: None,
+        minimum_receive: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_PROXY.to_string(),
+                msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+                    lp_token: LP_TOKEN.to_string(),
+                    amount: Uint128::from(10000u128)
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::WithdrawLiquidity {
+                    to: Addr::unchecked(USER_1),
+                    amount: Uint128::from(10000u128),
+                    target_asset: None,
+                    minimum_receive: None,
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(90000u128),
+    );
+
+    // only contract itself can execute callback
+    let msg = ExecuteMsg::Callback(CallbackMsg::WithdrawLiquidity {
+        to: Addr::unchecked(USER_1),
+        amount: Uint128::from(10000u128),
+        target_asset: None,
+        minimum_receive: None,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let contract_info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), env.clone(), contract_info.clone(), msg)?;
+    let prev_balances = vec![denom1.with_balance(0u128), denom2.with_balance(0u128)];
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: LP_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: "pair".to_string(),
+                    amount: Uint128::from(10000u128),
+                    msg: to_binary(&PairCw20HookMsg::WithdrawLiquidity { assets: vec![] })?,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::SendAssets {
+                    to: Addr::unchecked(USER_1),
+                    prev_balances: prev_balances.clone(),
+                    target_asset: None,
+                    minimum_receive: None,
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // withdrawn assets are sent to user
+    deps.querier.set_balance("denom1".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10000u128));
+    deps.querier.set_balance("denom2".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10000u128));
+    let msg = ExecuteMsg::Callback(CallbackMsg::SendAssets {
+        to: Addr::unchecked(USER_1),
+        prev_balances,
+        target_asset: None,
+        minimum_receive: None,
+    });
+    let res = execute(deps.as_mut(), env.clone(), contract_info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_1.to_string(),
+                amount: vec![Coin::new(10000, "denom1")],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER_1.to_string(),
+                amount: vec![Coin::new(10000, "denom2")],
+            }),
+        ]
+    );
+    deps.querier.set_balance("denom1".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+    deps.querier.set_balance("denom2".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+
+    // swap to a pair asset
+    let msg = ExecuteMsg::Callback(CallbackMsg::WithdrawLiquidity {
+        to: Addr::unchecked(USER_1),
+        amount: Uint128::from(10000u128),
+        target_asset: Some(denom1.clone()),
+        minimum_receive: Some(Uint128::from(19900u128)),
+    });
+    let res = execute(deps.as_mut(), env.clone(), contract_info.clone(), msg)?;
+    let prev_balances = vec![denom1.with_balance(0u128), denom2.with_balance(0u128)];
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .skip(1)
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::SwapAssets {
+                    prev_balances: prev_balances.clone(),
+                    target_asset: denom1.clone(),
+                }))?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::SendAssets {
+                    to: Addr::unchecked(USER_1),
+                    prev_balances: prev_balances.clone(),
+                    target_asset: Some(denom1.clone()),
+                    minimum_receive: Some(Uint128::from(19900u128)),
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    deps.querier.set_balance("denom1".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10000u128));
+    deps.querier.set_balance("denom2".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(10000u128));
+    let msg = ExecuteMsg::Callback(CallbackMsg::SwapAssets {
+        prev_balances: prev_balances.clone(),
+        target_asset: denom1.clone(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), contract_info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pair".to_string(),
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset: denom2.with_balance(10000u128),
+                ask_asset_info: Some(denom1.clone()),
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            })?,
+            funds: vec![Coin::new(10000, "denom2")],
+        })]
+    );
+
+    // swap returns less than minimum receive
+    deps.querier.set_balance("denom1".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(19800u128));
+    deps.querier.set_balance("denom2".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+    let msg = ExecuteMsg::Callback(CallbackMsg::SendAssets {
+        to: Addr::unchecked(USER_1),
+        prev_balances: prev_balances.clone(),
+        target_asset: Some(denom1.clone()),
+        minimum_receive: Some(Uint128::from(19900u128)),
+    });
+    let res = execute(deps.as_mut(), env.clone(), contract_info.clone(), msg);
+    assert_error(res, "Assertion failed; minimum receive amount: 19900, actual amount: 19800");
+
+    deps.querier.set_balance("denom1".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(19900u128));
+    let msg = ExecuteMsg::Callback(CallbackMsg::SendAssets {
+        to: Addr::unchecked(USER_1),
+        prev_balances,
+        target_asset: Some(denom1.clone()),
+        minimum_receive: Some(Uint128::from(19900u128)),
+    });
+    let res = execute(deps.as_mut(), env.clone(), contract_info.clone(), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER_1.to_string(),
+            amount: vec![Coin::new(19900, "denom1")],
+        })]
+    );
+    deps.querier.set_balance("denom1".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::zero());
+
+    // only owner can update pair proxies
+    let msg = ExecuteMsg::UpdatePairProxies {
+        add: Some(vec![(ibc_token.clone(), PAIR_PROXY.to_string())]),
+        remove: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+    execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg)?;
+
+    let msg = QueryMsg::PairProxies {
+        start_after: None,
+        limit: None,
+    };
+    let res: Vec<(String, Pair)> = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, vec![(IBC_TOKEN.to_string(), Pair(Addr::unchecked(PAIR_PROXY)))]);
+
+    // swap to the pair asset of pair proxy, then swap to target asset with pair proxy
+    let msg = ExecuteMsg::Callback(CallbackMsg::WithdrawLiquidity {
+        to: Addr::unchecked(USER_1),
+        amount: Uint128::from(10000u128),
+        target_asset: Some(ibc_token.clone()),
+        minimum_receive: None,
+    });
+    let res = execute(deps.as_mut(), env.clone(), contract_info.clone(), msg)?;
+    let prev_balances = vec![
+        denom1.with_balance(0u128),
+        denom2.with_balance(0u128),
+        ibc_token.with_balance(0u128),
+    ];
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .skip(1)
+            .take(2)
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::SwapAssets {
+                    prev_balances: prev_balances.clone(),
+                    target_asset: denom2.clone(),
+                }))?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: MOCK_CONTRACT_ADDR.to_string(),
+                msg: to_binary(&ExecuteMsg::Callback(CallbackMsg::SwapAssets {
+                    prev_balances: prev_balances.clone(),
+                    target_asset: ibc_token.clone(),
+                }))?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    deps.querier.set_balance("denom2".to_string(), MOCK_CONTRACT_ADDR.to_string(), Uint128::from(19900u128));
+    let msg = ExecuteMsg::Callback(CallbackMsg::SwapAssets {
+        prev_balances,
+        target_asset: ibc_token.clone(),
+    });
+    let res = execute(deps.as_mut(), env.clone(), contract_info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: PAIR_PROXY.to_string(),
+            msg: to_binary(&PairExecuteMsg::Swap {
+                offer_asset: denom2.with_balance(19900u128),
+                ask_asset_info: Some(ibc_token.clone()),
+                belief_price: None,
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            })?,
+            funds: vec![Coin::new(19900, "denom2")],
+        })]
+    );
+
+    // simulate
+    let msg = QueryMsg::SimulateUnbondAssets {
+        amount: Uint128::from(10000u128),
+        target_asset: None,
+    };
+    let res: Vec<Asset> = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, vec![denom1.with_balance(10000u128), denom2.with_balance(10000u128)]);
+
+    let msg = QueryMsg::SimulateUnbondAssets {
+        amount: Uint128::from(10000u128),
+        target_asset: Some(denom1.clone()),
+    };
+    let res: Vec<Asset> = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, vec![denom1.with_balance(19900u128)]);
+
+    let msg = QueryMsg::SimulateUnbondAssets {
+        amount: Uint128::from(10000u128),
+        target_asset: Some(ibc_token.clone()),
+    };
+    let res: Vec<Asset> = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res, vec![ibc_token.with_balance(19701u128)]);
+
+    Ok(())
+}
+
+#[test]
+fn test_fee_schedule() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    fee_schedule(&mut deps)?;
+
+    Ok(())
+}
+
+fn lp_transfer_msg(recipient: &str, amount: u128) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: LP_TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })?,
+        funds: vec![],
+    }))
+}
+
+fn lp_deposit_msg(amount: u128) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: LP_TOKEN.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Send {
+            contract: GENERATOR_PROXY.to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&GeneratorCw20HookMsg::Deposit {})?,
+        })?,
+        funds: vec![],
+    }))
+}
+
+fn lp_withdraw_msg(amount: u128) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: GENERATOR_PROXY.to_string(),
+        msg: to_binary(&GeneratorExecuteMsg::Withdraw {
+            lp_token: LP_TOKEN.to_string(),
+            amount: Uint128::from(amount),
+        })?,
+        funds: vec![],
+    }))
+}
+
+fn query_bond_share(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    staker_addr: &str,
+) -> Result<Uint128, ContractError> {
+    let msg = QueryMsg::RewardInfo {
+        staker_addr: staker_addr.to_string(),
+    };
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), msg)?)?;
+    Ok(res.reward_info.bond_share)
+}
+
+fn fee_schedule(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let env = mock_env();
+    let treasury = "treasury";
+    let dev_fund = "dev_fund";
+
+    // only owner can update fee config
+    let msg = ExecuteMsg::UpdateFeeConfig {
+        fee_recipients: Some(vec![(treasury.to_string(), 3), (dev_fund.to_string(), 1)]),
+        deposit_fee: Some(Decimal::percent(1)),
+        withdraw_fee: Some(Decimal::percent(1)),
+        fee_in_shares: None,
+    };
+    let info = mock_info(USER_2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // total weight must fit
+    let info = mock_info(USER_1, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::UpdateFeeConfig {
+            fee_recipients: Some(vec![(treasury.to_string(), u64::MAX), (dev_fund.to_string(), 1)]),
+            deposit_fee: None,
+            withdraw_fee: None,
+            fee_in_shares: None,
+        },
+    );
+    assert_error(res, "total fee recipient weight overflow");
+
+    // deposit and withdraw fees are capped
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::UpdateFeeConfig {
+            fee_recipients: None,
+            deposit_fee: Some(Decimal::percent(6)),
+            withdraw_fee: None,
+            fee_in_shares: None,
+        },
+    );
+    assert_error(res, "deposit_fee must be 0 to 0.05");
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::UpdateFeeConfig {
+            fee_recipients: Some(vec![(treasury.to_string(), 0)]),
+            deposit_fee: None,
+            withdraw_fee: None,
+            fee_in_shares: None,
+        },
+    );
+    assert_error(res, "fee recipient weight must be positive");
+
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    // deposit fee is split between recipients by weight
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            lp_transfer_msg(treasury, 750)?,
+            lp_transfer_msg(dev_fund, 250)?,
+            lp_deposit_msg(99000)?,
+        ]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(99000u128),
+    );
+    assert_eq!(query_bond_share(deps, USER_1)?, Uint128::from(99000u128));
+
+    // withdraw fee is withdrawn and split between recipients
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(10000u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            lp_withdraw_msg(100)?,
+            lp_transfer_msg(treasury, 75)?,
+            lp_transfer_msg(dev_fund, 25)?,
+            lp_withdraw_msg(9900)?,
+            lp_transfer_msg(USER_1, 9900)?,
+        ]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(89000u128),
+    );
+
+    // performance fee is split between recipients
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        ASTRO_TOKEN.to_string(),
+        Uint128::from(10000u128),
+    );
+    let info = mock_info(CONTROLLER, &[]);
+    let msg = ExecuteMsg::Compound {
+        minimum_receive: None,
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    let transfers: Vec<CosmosMsg> = res
+        .messages
+        .into_iter()
+        .map(|it| it.msg)
+        .filter(|msg| match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => {
+                matches!(from_binary(msg), Ok(Cw20ExecuteMsg::Transfer { .. }))
+            }
+            _ => false,
+        })
+        .collect();
+    assert_eq!(
+        transfers,
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: treasury.to_string(),
+                    amount: Uint128::from(375u128),
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: ASTRO_TOKEN.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: dev_fund.to_string(),
+                    amount: Uint128::from(125u128),
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    // pay fees in shares
+    let owner_info = mock_info(USER_1, &[]);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        owner_info,
+        ExecuteMsg::UpdateFeeConfig {
+            fee_recipients: None,
+            deposit_fee: None,
+            withdraw_fee: None,
+            fee_in_shares: Some(true),
+        },
+    )?;
+
+    // performance fee is not taken from rewards
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert!(res.messages.iter().all(|it| match &it.msg {
+        CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) =>
+            !matches!(from_binary(msg), Ok(Cw20ExecuteMsg::Transfer { .. })),
+        _ => true,
+    }));
+
+    // deposit fee is minted as shares to recipients
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_2.to_string(),
+        amount: Uint128::from(10000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [lp_deposit_msg(10000)?]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(99000u128),
+    );
+    assert_eq!(query_bond_share(deps, USER_2)?, Uint128::from(9900u128));
+    assert_eq!(query_bond_share(deps, treasury)?, Uint128::from(75u128));
+    assert_eq!(query_bond_share(deps, dev_fund)?, Uint128::from(25u128));
+
+    // performance fee is minted as shares from compounded LP
+    deps.querier.set_balance(
+        LP_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(1000u128),
+    );
+    let msg = ExecuteMsg::Callback(CallbackMsg::Stake {
+        prev_balance: Uint128::zero(),
+        minimum_receive: None,
+    });
+    let contract_info = mock_info(MOCK_CONTRACT_ADDR, &[]);
+    let res = execute(deps.as_mut(), env, contract_info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [lp_deposit_msg(1000)?]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+    // 37 LP = 37 * 99000 / 99950 share, 13 LP = 13 * 99036 / 99987 share
+    assert_eq!(query_bond_share(deps, treasury)?, Uint128::from(111u128));
+    assert_eq!(query_bond_share(deps, dev_fund)?, Uint128::from(37u128));
+
+    Ok(())
+}
+
+#[test]
+fn test_exit_fee() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+    exit_fee(&mut deps)?;
+
+    Ok(())
+}
+
+fn exit_fee(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    let mut exit_fee = ExitFee {
+        max_fee: Decimal::percent(11),
+        period: 10000,
+        curve: ExitFeeCurve::Linear {},
+        to_depositors: false,
+    };
+
+    // exit fee is capped
+    let info = mock_info(USER_1, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::UpdateExitFee {
+            exit_fee: Some(exit_fee.clone()),
+        },
+    );
+    assert_error(res, "exit fee must be 0 to 0.1");
+
+    exit_fee.max_fee = Decimal::percent(10);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::UpdateExitFee {
+            exit_fee: Some(exit_fee.clone()),
+        },
+    )?;
+
+    // user_1 bond 100000 LP
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(100000u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None })?,
+    });
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(100000u128),
+    );
+
+    // half of the exit period is elapsed
+    env.block.time = env.block.time.plus_seconds(5000);
+    let msg = QueryMsg::RewardInfo {
+        staker_addr: USER_1.to_string(),
+    };
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.reward_info.exit_fee, Uint128::from(5000u128));
+
+    // exit fee is sent to the fee collector
+    let info = mock_info(USER_1, &[]);
+    let msg = ExecuteMsg::Unbond {
+        amount: Uint128::from(10000u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            lp_withdraw_msg(500)?,
+            lp_transfer_msg(FEE_COLLECTOR, 500)?,
+            lp_withdraw_msg(9500)?,
+            lp_transfer_msg(USER_1, 9500)?,
+        ]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(90000u128),
+    );
+
+    // exit fee is left to the remaining depositors
+    exit_fee.curve = ExitFeeCurve::Quadratic {};
+    exit_fee.to_depositors = true;
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USER_1, &[]),
+        ExecuteMsg::UpdateExitFee {
+            exit_fee: Some(exit_fee.clone()),
+        },
+    )?;
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [lp_withdraw_msg(9750)?, lp_transfer_msg(USER_1, 9750)?]
+    );
+    deps.querier.set_balance(
+        GENERATOR_PROXY.to_string(),
+        LP_TOKEN.to_string(),
+        Uint128::from(80250u128),
+    );
+
+    // step curve charges the max fee until the end of the period
+    exit_fee.curve = ExitFeeCurve::Step {};
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(USER_1, &[]),
+        ExecuteMsg::UpdateExitFee {
+            exit_fee: Some(exit_fee),
+        },
+    )?;
+    env.block.time = env.block.time.plus_seconds(4999);
+    let msg = QueryMsg::RewardInfo {
+        staker_addr: USER_1.to_string(),
+    };
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    // the fee left by the previous unbond is vested over a day
+    assert_eq!(res.reward_info.bond_amount, Uint128::from(80028u128));
+    assert_eq!(res.reward_info.exit_fee, Uint128::from(8002u128));
+
+    // no fee after the period
+    env.block.time = env.block.time.plus_seconds(1);
+    let msg = QueryMsg::RewardInfo {
+        staker_addr: USER_1.to_string(),
+    };
+    let res: RewardInfoResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(res.reward_info.exit_fee, Uint128::zero());
+
+    Ok(())
+}
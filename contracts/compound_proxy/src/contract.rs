@@ -1,36 +1,39 @@
 use crate::error::ContractError;
-use crate::simulation::query_compound_simulation;
-use crate::state::{Config, CONFIG, PAIR_PROXY};
+use crate::simulation::{
+    compute_stable_lp_amount, query_compound_simulation, simulate_stable_swap, StablePool,
+};
+use crate::state::{Config, CONFIG, LEGACY_CONFIG, OWNERSHIP_PROPOSAL, PAIR_PROXY};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::convert::TryInto;
 
 use astroport::factory::PairType;
+use astroport::querier::query_supply;
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use cosmwasm_std::{
-    entry_point, to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Decimal256, Deps, DepsMut, Env,
-    Isqrt, MessageInfo, QuerierWrapper, Response, StdError, StdResult, Uint128, Uint256,
+    attr, entry_point, to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Decimal256, Deps, DepsMut,
+    Env, Isqrt, MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Uint128, Uint256,
 };
+use cw_storage_plus::Bound;
 use cw20::Expiration;
-use baz::compound_proxy::{CallbackMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use baz::compound_proxy::{
+    validate_commission, CallbackMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+};
 
 use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
 use baz::adapters::asset::AssetEx;
 use baz::adapters::pair::Pair;
+use baz::price_guard::{query_swap_limits, PriceGuard};
+use astroport::querier::query_token_precision;
 
 /// Scaling denominator for commission
-const COMMISSION_DENOM: u64 = 10000u64;
+pub(crate) const COMMISSION_DENOM: u64 = 10000u64;
 
 /// Maximum spread percentage when swapping
 const MAX_SPREAD: u64 = 50; // 50%
 
-/// ## Description
-/// Validates that commission bps must be less than or equal 10000
-fn validate_commission(commission_bps: u64) -> StdResult<u64> {
-    if commission_bps >= 10000u64 {
-        Err(StdError::generic_err("commission rate must be 0 to 9999"))
-    } else {
-        Ok(commission_bps)
-    }
-}
+/// Maximum number of steps when searching the swap amount on stableswap pair
+const STABLE_SWAP_SEARCH_ITERATIONS: u8 = 64;
 
 /// ## Description
 /// Validates that decimal value is in the range 0 to 1
@@ -56,11 +59,16 @@ pub fn instantiate(
     let slippage_tolerance = validate_percentage(msg.slippage_tolerance, "slippage_tolerance")?;
     let pair_contract = deps.api.addr_validate(&msg.pair_contract)?;
     let pair_info = Pair(pair_contract).query_pair_info(&deps.querier)?;
+    if let Some(price_guard) = &msg.price_guard {
+        price_guard.check(deps.api)?;
+    }
 
     let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
         pair_info,
         commission_bps,
         slippage_tolerance,
+        price_guard: msg.price_guard,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -105,9 +113,129 @@ pub fn execute(
             )
         }
         ExecuteMsg::Callback(msg) => handle_callback(deps, env, info, msg),
+        ExecuteMsg::UpdateConfig {
+            commission_bps,
+            slippage_tolerance,
+        } => update_config(deps, info, commission_bps, slippage_tolerance),
+        ExecuteMsg::UpdatePairProxies { add, remove } => {
+            update_pair_proxies(deps, info, add, remove)
+        }
+        ExecuteMsg::UpdatePriceGuard { price_guard } => update_price_guard(deps, info, price_guard),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        }
     }
 }
 
+/// ## Description
+/// Updates contract config. Returns a [`ContractError`] on failure or the [`CONFIG`] data will be updated.
+fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    commission_bps: Option<u64>,
+    slippage_tolerance: Option<Decimal>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(commission_bps) = commission_bps {
+        config.commission_bps = validate_commission(commission_bps)?;
+    }
+
+    if let Some(slippage_tolerance) = slippage_tolerance {
+        config.slippage_tolerance = validate_percentage(slippage_tolerance, "slippage_tolerance")?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
+}
+
+/// ## Description
+/// Adds or removes pair proxies used to swap reward tokens. Returns a [`ContractError`] on failure.
+fn update_pair_proxies(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Option<Vec<(AssetInfo, String)>>,
+    remove: Option<Vec<AssetInfo>>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(remove) = remove {
+        for asset_info in remove {
+            PAIR_PROXY.remove(deps.storage, asset_info.to_string());
+        }
+    }
+
+    if let Some(add) = add {
+        for (asset_info, pair_proxy) in add {
+            asset_info.check(deps.api)?;
+            let pair_proxy_addr = deps.api.addr_validate(&pair_proxy)?;
+            PAIR_PROXY.save(deps.storage, asset_info.to_string(), &Pair(pair_proxy_addr))?;
+        }
+    }
+
+    Ok(Response::new().add_attribute("action", "update_pair_proxies"))
+}
+
+/// ## Description
+/// Sets or removes the price guard used to bound swaps. Returns a [`ContractError`] on failure or the [`CONFIG`] data will be updated.
+fn update_price_guard(
+    deps: DepsMut,
+    info: MessageInfo,
+    price_guard: Option<PriceGuard>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(price_guard) = &price_guard {
+        price_guard.check(deps.api)?;
+    }
+    config.price_guard = price_guard;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_price_guard"))
+}
+
 /// ## Description
 /// Performs rewards compounding to LP token. Sender must do token approval upon calling this function.
 #[allow(clippy::too_many_arguments)]
@@ -121,6 +249,7 @@ pub fn compound(
     slippage_tolerance: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let no_swap = no_swap.unwrap_or(false);
+    let config = CONFIG.load(deps.storage)?;
 
     let mut messages: Vec<CosmosMsg> = vec![];
     let mut native_reward_map: HashMap<AssetInfo, Uint128> = HashMap::new();
@@ -130,8 +259,10 @@ pub fn compound(
 
         let pair_proxy = PAIR_PROXY.may_load(deps.storage, reward.info.to_string())?;
         if let Some(pair_proxy) = pair_proxy {
+            let (belief_price, max_spread) =
+                query_pair_proxy_swap_limits(&deps.querier, &config, &pair_proxy, &reward)?;
             let swap_reward =
-                pair_proxy.swap_msg(&reward, Some(Decimal::MAX), Some(Decimal::percent(MAX_SPREAD)), None)?;
+                pair_proxy.swap_msg(&reward, None, belief_price, max_spread, None)?;
             messages.push(swap_reward);
         }
 
@@ -144,7 +275,6 @@ pub fn compound(
         messages.push(CallbackMsg::OptimalSwap {}.into_cosmos_msg(&env.contract.address)?);
     }
 
-    let config = CONFIG.load(deps.storage)?;
     let assets = config
         .pair_info
         .query_pools(&deps.querier, &env.contract.address)?;
@@ -173,6 +303,46 @@ pub fn compound(
         .add_attribute("action", "compound"))
 }
 
+/// ## Description
+/// Returns the belief price and max spread to swap **reward** with the pair proxy.
+/// `pair_proxy` contracts expect the belief price in whole tokens, so it is adjusted by the asset precisions.
+fn query_pair_proxy_swap_limits(
+    querier: &QuerierWrapper,
+    config: &Config,
+    pair_proxy: &Pair,
+    reward: &Asset,
+) -> StdResult<(Option<Decimal>, Option<Decimal>)> {
+    let price_guard = match &config.price_guard {
+        Some(price_guard) => price_guard,
+        None => return Ok((Some(Decimal::MAX), Some(Decimal::percent(MAX_SPREAD)))),
+    };
+
+    let pair_proxy_info = pair_proxy.query_pair_info(querier)?;
+    let ask_asset_info = if reward.info.equal(&pair_proxy_info.asset_infos[0]) {
+        &pair_proxy_info.asset_infos[1]
+    } else if reward.info.equal(&pair_proxy_info.asset_infos[1]) {
+        &pair_proxy_info.asset_infos[0]
+    } else {
+        return Err(StdError::generic_err("Invalid pair proxy"));
+    };
+    let precisions = match &pair_proxy_info.pair_type {
+        PairType::Custom(pair_type) if pair_type == "pair_proxy" => Some((
+            query_token_precision(querier, &reward.info)?,
+            query_token_precision(querier, ask_asset_info)?,
+        )),
+        _ => None,
+    };
+
+    query_swap_limits(
+        querier,
+        Some(price_guard),
+        reward,
+        ask_asset_info,
+        precisions,
+        Decimal::percent(MAX_SPREAD),
+    )
+}
+
 /// # Description
 /// Handle the callbacks describes in the [`CallbackMsg`]. Returns an [`ContractError`] on failure, otherwise returns the [`Response`]
 pub fn handle_callback(
@@ -202,26 +372,11 @@ fn optimal_swap(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<Response,
 
     let mut messages: Vec<CosmosMsg> = vec![];
 
-    match config.pair_info.pair_type {
-        PairType::Stable {} => {
-            //Do nothing for stable pair
-        }
-        _ => {
-            let assets = config
-                .pair_info
-                .query_pools(&deps.querier, env.contract.address)?;
-            let asset_a = assets[0].clone();
-            let asset_b = assets[1].clone();
-            if !asset_a.amount.is_zero() || !asset_b.amount.is_zero() {
-                calculate_optimal_swap(
-                    &deps.querier,
-                    &config,
-                    asset_a,
-                    asset_b,
-                    &mut messages,
-                )?;
-            }
-        }
+    let assets = config
+        .pair_info
+        .query_pools(&deps.querier, env.contract.address)?;
+    if assets.iter().any(|asset| !asset.amount.is_zero()) {
+        calculate_optimal_swap(&deps.querier, &config, &assets, &mut messages)?;
     }
 
     Ok(Response::new()
@@ -231,25 +386,31 @@ fn optimal_swap(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<Response,
 
 /// # Description
 /// Calculates the amount of asset in the pair contract that need to be swapped before providing liquidity.
-/// The swap messages will be added to **messages**.
+/// **assets** must be in the order of the pair assets.
+/// Returns the swap and return amounts of each pair asset, the swap messages will be added to **messages**.
 pub fn calculate_optimal_swap(
     querier: &QuerierWrapper,
     config: &Config,
-    asset_a: Asset,
-    asset_b: Asset,
+    assets: &[Asset],
     messages: &mut Vec<CosmosMsg>,
-) -> StdResult<(Uint128, Uint128, Uint128, Uint128)> {
-    let mut swap_asset_a_amount = Uint128::zero();
-    let mut swap_asset_b_amount = Uint128::zero();
-    let mut return_a_amount = Uint128::zero();
-    let mut return_b_amount = Uint128::zero();
+) -> StdResult<(Vec<Uint128>, Vec<Uint128>)> {
+    if let PairType::Stable {} = config.pair_info.pair_type {
+        return calculate_stable_optimal_swap(querier, config, assets, messages);
+    }
+
+    if assets.len() != 2 {
+        return Err(StdError::generic_err("Xyk pair must have 2 assets"));
+    }
+
+    let mut swap_asset_amounts = vec![Uint128::zero(); 2];
+    let mut return_asset_amounts = vec![Uint128::zero(); 2];
 
     let pair_contract = config.pair_info.contract_addr.clone();
     let pools = config
         .pair_info
         .query_pools(querier, &pair_contract)?;
-    let provide_a_amount: Uint256 = asset_a.amount.into();
-    let provide_b_amount: Uint256 = asset_b.amount.into();
+    let provide_a_amount: Uint256 = assets[0].amount.into();
+    let provide_b_amount: Uint256 = assets[1].amount.into();
     let pool_a_amount: Uint256 = pools[0].amount.into();
     let pool_b_amount: Uint256 = pools[1].amount.into();
     let provide_a_area = provide_a_amount * pool_b_amount;
@@ -266,21 +427,30 @@ pub fn calculate_optimal_swap(
         )?;
         if !swap_amount.is_zero() {
             let swap_asset = Asset {
-                info: asset_a.info,
+                info: assets[0].info.clone(),
                 amount: swap_amount,
             };
-            return_b_amount = simulate(
+            return_asset_amounts[1] = simulate(
                 pool_a_amount,
                 pool_b_amount,
                 swap_asset.amount.into(),
                 Decimal256::from_ratio(config.commission_bps, COMMISSION_DENOM),
             )?;
-            if !return_b_amount.is_zero() {
-                swap_asset_a_amount = swap_asset.amount;
+            if !return_asset_amounts[1].is_zero() {
+                swap_asset_amounts[0] = swap_asset.amount;
+                let (belief_price, max_spread) = query_swap_limits(
+                    querier,
+                    config.price_guard.as_ref(),
+                    &swap_asset,
+                    &assets[1].info,
+                    None,
+                    Decimal::percent(MAX_SPREAD),
+                )?;
                 messages.push(Pair(pair_contract).swap_msg(
                     &swap_asset,
-                    Some(Decimal::MAX),
-                    Some(Decimal::percent(MAX_SPREAD)),
+                    None,
+                    belief_price,
+                    max_spread,
                     None,
                 )?);
             }
@@ -295,33 +465,197 @@ pub fn calculate_optimal_swap(
         )?;
         if !swap_amount.is_zero() {
             let swap_asset = Asset {
-                info: asset_b.info,
+                info: assets[1].info.clone(),
                 amount: swap_amount,
             };
-            return_a_amount = simulate(
+            return_asset_amounts[0] = simulate(
                 pool_b_amount,
                 pool_a_amount,
                 swap_asset.amount.into(),
                 Decimal256::from_ratio(config.commission_bps, COMMISSION_DENOM),
             )?;
-            if !return_a_amount.is_zero() {
-                swap_asset_b_amount = swap_asset.amount;
+            if !return_asset_amounts[0].is_zero() {
+                swap_asset_amounts[1] = swap_asset.amount;
+                let (belief_price, max_spread) = query_swap_limits(
+                    querier,
+                    config.price_guard.as_ref(),
+                    &swap_asset,
+                    &assets[0].info,
+                    None,
+                    Decimal::percent(MAX_SPREAD),
+                )?;
                 messages.push(Pair(pair_contract).swap_msg(
                     &swap_asset,
-                    Some(Decimal::MAX),
-                    Some(Decimal::percent(MAX_SPREAD)),
+                    None,
+                    belief_price,
+                    max_spread,
                     None,
                 )?);
             }
         }
     };
 
-    Ok((
-        swap_asset_a_amount,
-        swap_asset_b_amount,
-        return_a_amount,
-        return_b_amount,
-    ))
+    Ok((swap_asset_amounts, return_asset_amounts))
+}
+
+/// # Description
+/// Calculates the amount of asset in the stableswap pair contract that need to be swapped before providing liquidity.
+/// The stableswap pair charges imbalanced liquidity, so the swap amount is searched to maximize the LP token minted.
+/// With more than 2 assets, the most oversupplied asset is swapped to the most undersupplied asset
+/// repeatedly, up to once per asset, until swapping no longer mints more LP token.
+/// The swap messages will be added to **messages**.
+fn calculate_stable_optimal_swap(
+    querier: &QuerierWrapper,
+    config: &Config,
+    assets: &[Asset],
+    messages: &mut Vec<CosmosMsg>,
+) -> StdResult<(Vec<Uint128>, Vec<Uint128>)> {
+    let mut swap_asset_amounts = vec![Uint128::zero(); assets.len()];
+    let mut return_asset_amounts = vec![Uint128::zero(); assets.len()];
+
+    // The first liquidity provider sets the pool ratio, nothing to balance against
+    let total_share = query_supply(querier, &config.pair_info.liquidity_token)?;
+    if total_share.is_zero() {
+        return Ok((swap_asset_amounts, return_asset_amounts));
+    }
+
+    let pair_contract = config.pair_info.contract_addr.clone();
+    let pools = config.pair_info.query_pools(querier, &pair_contract)?;
+    let stable_pool = StablePool::load(querier, &pair_contract, &config.pair_info.asset_infos)?;
+    let commission_rate = Decimal256::from_ratio(config.commission_bps, COMMISSION_DENOM);
+
+    let mut provide_amounts = assets
+        .iter()
+        .enumerate()
+        .map(|(i, asset)| stable_pool.to_invariant_precision(i, asset.amount))
+        .collect::<StdResult<Vec<Uint128>>>()?;
+    let mut pool_amounts = pools
+        .iter()
+        .enumerate()
+        .map(|(i, pool)| stable_pool.to_invariant_precision(i, pool.amount))
+        .collect::<StdResult<Vec<Uint128>>>()?;
+
+    for _ in 1..assets.len() {
+        // compare provide / pool ratios by cross multiplication
+        let ratio_cmp = |i: &usize, j: &usize| {
+            (Uint256::from(provide_amounts[*i]) * Uint256::from(pool_amounts[*j]))
+                .cmp(&(Uint256::from(provide_amounts[*j]) * Uint256::from(pool_amounts[*i])))
+        };
+        let offer_index = (0..assets.len()).max_by(ratio_cmp).unwrap_or_default();
+        let ask_index = (0..assets.len()).min_by(ratio_cmp).unwrap_or_default();
+        if ratio_cmp(&offer_index, &ask_index) != Ordering::Greater {
+            break;
+        }
+
+        let (swap_amount, return_amount) = get_stable_swap_amount(
+            stable_pool.leverage,
+            (offer_index, ask_index),
+            &provide_amounts,
+            &pool_amounts,
+            total_share,
+            commission_rate,
+        )?;
+        let offer_amount = stable_pool.from_invariant_precision(offer_index, swap_amount)?;
+        let ask_amount = stable_pool.from_invariant_precision(ask_index, return_amount)?;
+        if offer_amount.is_zero() || ask_amount.is_zero() {
+            break;
+        }
+
+        provide_amounts[offer_index] -= swap_amount;
+        provide_amounts[ask_index] += return_amount;
+        pool_amounts[offer_index] += swap_amount;
+        pool_amounts[ask_index] -= return_amount;
+        swap_asset_amounts[offer_index] += offer_amount;
+        return_asset_amounts[ask_index] += ask_amount;
+
+        let swap_asset = assets[offer_index].info.with_balance(offer_amount);
+        let ask_asset_info = &assets[ask_index].info;
+        let (belief_price, max_spread) = query_swap_limits(
+            querier,
+            config.price_guard.as_ref(),
+            &swap_asset,
+            ask_asset_info,
+            None,
+            Decimal::percent(MAX_SPREAD),
+        )?;
+        messages.push(Pair(pair_contract.clone()).swap_msg(
+            &swap_asset,
+            Some(ask_asset_info.clone()),
+            belief_price,
+            max_spread,
+            None,
+        )?);
+    }
+
+    Ok((swap_asset_amounts, return_asset_amounts))
+}
+
+/// ## Description
+/// Searches the amount of the offer asset to swap on the stableswap pair that maximizes the LP token minted.
+/// **swap** is the (offer, ask) index pair, **provide** and **pools** must be in the invariant precision.
+/// Returns the swap amount and the simulated return amount.
+pub(crate) fn get_stable_swap_amount(
+    leverage: u64,
+    swap: (usize, usize),
+    provide: &[Uint128],
+    pools: &[Uint128],
+    total_share: Uint128,
+    commission_rate: Decimal256,
+) -> StdResult<(Uint128, Uint128)> {
+    let (offer_index, ask_index) = swap;
+    let lp_after_swap = |swap_amount: Uint128| -> StdResult<(Uint128, Uint128)> {
+        let return_amount = if swap_amount.is_zero() {
+            Uint128::zero()
+        } else {
+            simulate_stable_swap(
+                leverage,
+                pools,
+                offer_index,
+                ask_index,
+                swap_amount,
+                commission_rate,
+            )?
+        };
+        let mut new_pools = pools.to_vec();
+        new_pools[offer_index] += swap_amount;
+        new_pools[ask_index] = new_pools[ask_index].checked_sub(return_amount)?;
+        let mut deposits = provide.to_vec();
+        deposits[offer_index] -= swap_amount;
+        deposits[ask_index] += return_amount;
+        let lp_amount = compute_stable_lp_amount(
+            leverage,
+            &new_pools,
+            &deposits,
+            total_share,
+            commission_rate,
+        )?;
+        Ok((lp_amount, return_amount))
+    };
+
+    // LP amount is unimodal on the swap amount, narrow the range with ternary search
+    let mut low = Uint128::zero();
+    let mut high = provide[offer_index];
+    for _ in 0..STABLE_SWAP_SEARCH_ITERATIONS {
+        let third = (high - low) / Uint128::from(3u8);
+        if third.is_zero() {
+            break;
+        }
+        let (lp_low, _) = lp_after_swap(low + third)?;
+        let (lp_high, _) = lp_after_swap(high - third)?;
+        if lp_low < lp_high {
+            low += third;
+        } else {
+            high -= third;
+        }
+    }
+
+    let (lp_no_swap, _) = lp_after_swap(Uint128::zero())?;
+    let (lp_swap, return_amount) = lp_after_swap(low)?;
+    if lp_swap > lp_no_swap {
+        Ok((low, return_amount))
+    } else {
+        Ok((Uint128::zero(), Uint128::zero()))
+    }
 }
 
 /// ## Description
@@ -444,12 +778,52 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::CompoundSimulation { rewards } => {
             to_binary(&query_compound_simulation(deps, rewards)?)
         }
+        QueryMsg::PairProxies { start_after, limit } => {
+            to_binary(&query_pair_proxies(deps, start_after, limit)?)
+        }
     }
 }
 
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns the list of reward asset and the pair proxy used to swap it.
+fn query_pair_proxies(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Pair)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    PAIR_PROXY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
 /// ## Description
-/// Used for migration of contract. Returns the default object of type [`Response`].
+/// Used for migration of contract. Sets the owner when migrating from a contract without owner.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    if CONFIG.load(deps.storage).is_err() {
+        let owner = msg
+            .owner
+            .ok_or_else(|| StdError::generic_err("owner is required"))?;
+        let legacy_config = LEGACY_CONFIG.load(deps.storage)?;
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                owner: deps.api.addr_validate(&owner)?,
+                pair_info: legacy_config.pair_info,
+                commission_bps: legacy_config.commission_bps,
+                slippage_tolerance: legacy_config.slippage_tolerance,
+                price_guard: legacy_config.price_guard,
+            },
+        )?;
+    }
+
     Ok(Response::default())
 }
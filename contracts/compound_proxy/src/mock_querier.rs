@@ -1,16 +1,17 @@
 use astroport::asset::{PairInfo, AssetInfo};
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, from_slice, to_binary, Addr, Coin, Empty, OwnedDeps, Querier, QuerierResult,
-    QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+    from_binary, from_slice, to_binary, Addr, Coin, Decimal, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, Uint128, Uint256, WasmQuery,
 };
 use std::collections::HashMap;
 
 use astroport::factory::FeeInfoResponse;
 use astroport::factory::QueryMsg::FeeInfo;
-use astroport::pair::QueryMsg::{Pair, Simulation};
+use astroport::oracle::QueryMsg as OracleQueryMsg;
+use astroport::pair::QueryMsg::{Config, Pair, Simulation};
 use cw20::{BalanceResponse, Cw20QueryMsg, TokenInfoResponse};
-use astroport::pair::SimulationResponse;
+use astroport::pair::{ConfigResponse, SimulationResponse, StablePoolConfig};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
@@ -134,6 +135,69 @@ impl WasmMockQuerier {
                         ),
                         _ => panic!("DO NOT ENTER HERE"),
                     }
+                } else if contract_addr == "pair_contract_stable" {
+                    match from_binary(&msg).unwrap() {
+                        Pair { .. } => SystemResult::Ok(
+                            to_binary(&PairInfo {
+                                asset_infos: vec![
+                                    {
+                                        AssetInfo::NativeToken { denom: "uusdc".to_string() }
+                                    },
+                                    {
+                                        AssetInfo::NativeToken { denom: "uusdt".to_string() }
+                                    }
+                                ],
+                                contract_addr: Addr::unchecked("pair_contract_stable"),
+                                liquidity_token: Addr::unchecked("liquidity_token"),
+                                pair_type: astroport::factory::PairType::Stable {  },
+                            })
+                                .into(),
+                        ),
+                        Config { .. } => SystemResult::Ok(
+                            to_binary(&ConfigResponse {
+                                block_time_last: 0,
+                                params: Some(to_binary(&StablePoolConfig {
+                                    amp: Decimal::from_ratio(10u128, 1u128),
+                                }).unwrap()),
+                                owner: None,
+                            })
+                                .into(),
+                        ),
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
+                } else if contract_addr == "pair_contract_stable_3" {
+                    match from_binary(&msg).unwrap() {
+                        Pair { .. } => SystemResult::Ok(
+                            to_binary(&PairInfo {
+                                asset_infos: vec![
+                                    {
+                                        AssetInfo::NativeToken { denom: "uusdc".to_string() }
+                                    },
+                                    {
+                                        AssetInfo::NativeToken { denom: "uusdt".to_string() }
+                                    },
+                                    {
+                                        AssetInfo::NativeToken { denom: "udai".to_string() }
+                                    }
+                                ],
+                                contract_addr: Addr::unchecked("pair_contract_stable_3"),
+                                liquidity_token: Addr::unchecked("liquidity_token"),
+                                pair_type: astroport::factory::PairType::Stable {  },
+                            })
+                                .into(),
+                        ),
+                        Config { .. } => SystemResult::Ok(
+                            to_binary(&ConfigResponse {
+                                block_time_last: 0,
+                                params: Some(to_binary(&StablePoolConfig {
+                                    amp: Decimal::from_ratio(10u128, 1u128),
+                                }).unwrap()),
+                                owner: None,
+                            })
+                                .into(),
+                        ),
+                        _ => panic!("DO NOT ENTER HERE"),
+                    }
                 } else if contract_addr == "pair_astro_token" {
                     match from_binary(&msg).unwrap() {
                         Pair { .. } => SystemResult::Ok(
@@ -160,6 +224,15 @@ impl WasmMockQuerier {
                         ),
                         _ => panic!("DO NOT ENTER HERE"),
                     }
+                } else if contract_addr == "oracle" {
+                    match from_binary(&msg).unwrap() {
+                        OracleQueryMsg::Consult { .. } => SystemResult::Ok(
+                            to_binary(&vec![(
+                                AssetInfo::Token { contract_addr: Addr::unchecked("token") },
+                                Uint256::from(1000000u128),
+                            )]).into(),
+                        ),
+                    }
                 } else {
                     match from_binary(&msg).unwrap() {
                         Cw20QueryMsg::TokenInfo {} => {
@@ -1,13 +1,16 @@
 use std::cmp::Ordering;
 
 use crate::{
-    contract::calculate_optimal_swap,
+    contract::{calculate_optimal_swap, COMMISSION_DENOM},
     state::{Config, CONFIG, PAIR_PROXY},
 };
 use astroport::{asset::AssetInfo, pair::StablePoolConfig, querier::query_token_precision, U256};
 
 use astroport::querier::query_supply;
-use cosmwasm_std::{from_binary, CosmosMsg, Deps, StdError, StdResult, Uint128};
+use cosmwasm_std::{
+    from_binary, Addr, CosmosMsg, Decimal256, Deps, QuerierWrapper, StdError, StdResult, Uint128,
+    Uint256,
+};
 
 use baz::compound_proxy::CompoundSimulationResponse;
 
@@ -17,7 +20,6 @@ use baz::adapters::pair::Pair;
 
 const ITERATIONS: u8 = 32;
 
-const N_COINS: u8 = 2;
 const AMP_PRECISION: u64 = 100;
 
 /// ## Description
@@ -27,10 +29,8 @@ pub fn query_compound_simulation(
     rewards: Vec<Asset>,
 ) -> StdResult<CompoundSimulationResponse> {
     let config: Config = CONFIG.load(deps.storage)?;
-    let asset_a_info = config.pair_info.asset_infos[0].clone();
-    let asset_b_info = config.pair_info.asset_infos[1].clone();
-    let mut asset_a_amount = Uint128::zero();
-    let mut asset_b_amount = Uint128::zero();
+    let asset_infos = config.pair_info.asset_infos.clone();
+    let mut asset_amounts = vec![Uint128::zero(); asset_infos.len()];
 
     for reward in rewards {
         let pair_proxy = PAIR_PROXY.may_load(deps.storage, reward.info.to_string())?;
@@ -48,162 +48,258 @@ pub fn query_compound_simulation(
         } else {
             reward
         };
-        if add_asset.info.equal(&asset_a_info) {
-            asset_a_amount += add_asset.amount;
-        } else if add_asset.info.equal(&asset_b_info) {
-            asset_b_amount += add_asset.amount;
-        } else {
-            return Err(StdError::generic_err("Invalid reward"));
-        }
+        let index = asset_infos
+            .iter()
+            .position(|asset_info| asset_info.equal(&add_asset.info))
+            .ok_or_else(|| StdError::generic_err("Invalid reward"))?;
+        asset_amounts[index] += add_asset.amount;
     }
 
-    let pair = Pair(config.pair_info.contract_addr.clone());
     let mut pools = config
         .pair_info
         .query_pools(&deps.querier, &config.pair_info.contract_addr)?;
 
     let total_share = query_supply(&deps.querier, &config.pair_info.liquidity_token)?;
 
-    let (lp_amount, swap_asset_a_amount, swap_asset_b_amount, return_a_amount, return_b_amount) =
-        match config.pair_info.pair_type {
-            PairType::Xyk {} => {
-                let asset_a = Asset {
-                    info: asset_a_info,
-                    amount: asset_a_amount,
-                };
-                let asset_b = Asset {
-                    info: asset_b_info,
-                    amount: asset_b_amount,
-                };
-                let mut _messages: Vec<CosmosMsg> = vec![];
-                let (swap_asset_a_amount, swap_asset_b_amount, return_a_amount, return_b_amount) =
-                    calculate_optimal_swap(
-                        &deps.querier,
-                        &config,
-                        asset_a,
-                        asset_b,
-                        &mut _messages,
-                    )?;
-
-                if !swap_asset_a_amount.is_zero() {
-                    asset_a_amount -= swap_asset_a_amount;
-                    asset_b_amount += return_b_amount;
-                    pools[0].amount += swap_asset_a_amount;
-                    pools[1].amount -= return_b_amount;
-                }
-
-                if !swap_asset_b_amount.is_zero() {
-                    asset_b_amount -= swap_asset_b_amount;
-                    asset_a_amount += return_a_amount;
-                    pools[1].amount += swap_asset_b_amount;
-                    pools[0].amount -= return_a_amount;
-                }
-
-                let lp_amount = if total_share.is_zero() {
+    if let PairType::Custom(_) = config.pair_info.pair_type {
+        return Err(StdError::generic_err("Custom pair type not supported"));
+    }
+
+    let assets: Vec<Asset> = asset_infos
+        .iter()
+        .zip(asset_amounts.iter())
+        .map(|(info, amount)| info.with_balance(*amount))
+        .collect();
+    let mut _messages: Vec<CosmosMsg> = vec![];
+    let (swap_asset_amounts, return_asset_amounts) =
+        calculate_optimal_swap(&deps.querier, &config, &assets, &mut _messages)?;
+
+    for (i, (swap_amount, return_amount)) in swap_asset_amounts
+        .iter()
+        .zip(return_asset_amounts.iter())
+        .enumerate()
+    {
+        asset_amounts[i] = asset_amounts[i].checked_sub(*swap_amount)? + *return_amount;
+        pools[i].amount = (pools[i].amount + *swap_amount).checked_sub(*return_amount)?;
+    }
+
+    let lp_amount = match config.pair_info.pair_type {
+        PairType::Stable {} => {
+            let stable_pool = StablePool::load(
+                &deps.querier,
+                &config.pair_info.contract_addr,
+                &config.pair_info.asset_infos,
+            )?;
+
+            let deposits = asset_amounts
+                .iter()
+                .enumerate()
+                .map(|(i, amount)| stable_pool.to_invariant_precision(i, *amount))
+                .collect::<StdResult<Vec<Uint128>>>()?;
+
+            if total_share.is_zero() {
+                let liquidity_token_precision = query_token_precision(
+                    &deps.querier,
+                    &AssetInfo::Token {
+                        contract_addr: config.pair_info.liquidity_token,
+                    },
+                )?;
+
+                // Initial share = collateral amount, multi-asset pairs mint the invariant instead
+                let share = if deposits.len() == 2 {
                     Uint128::new(
-                        (U256::from(asset_a_amount.u128()) * U256::from(asset_b_amount.u128()))
+                        (U256::from(deposits[0].u128()) * U256::from(deposits[1].u128()))
                             .integer_sqrt()
                             .as_u128(),
                     )
                 } else {
-                    std::cmp::min(
-                        asset_a_amount.multiply_ratio(total_share, pools[0].amount),
-                        asset_b_amount.multiply_ratio(total_share, pools[1].amount),
+                    let amounts: Vec<u128> = deposits.iter().map(|it| it.u128()).collect();
+                    Uint128::new(
+                        compute_d(stable_pool.leverage, &amounts)
+                            .ok_or_else(|| StdError::generic_err("Failed to calculate the D"))?,
                     )
                 };
-                (
-                    lp_amount,
-                    swap_asset_a_amount,
-                    swap_asset_b_amount,
-                    return_a_amount,
-                    return_b_amount,
-                )
+                adjust_precision(
+                    share,
+                    stable_pool.greater_precision,
+                    liquidity_token_precision,
+                )?
+            } else {
+                let pool_amounts = pools
+                    .iter()
+                    .enumerate()
+                    .map(|(i, pool)| stable_pool.to_invariant_precision(i, pool.amount))
+                    .collect::<StdResult<Vec<Uint128>>>()?;
+
+                compute_stable_lp_amount(
+                    stable_pool.leverage,
+                    &pool_amounts,
+                    &deposits,
+                    total_share,
+                    Decimal256::from_ratio(config.commission_bps, COMMISSION_DENOM),
+                )?
             }
-            PairType::Stable {} => {
-                let token_precision_0 = query_token_precision(&deps.querier, &asset_a_info)?;
-                let token_precision_1 = query_token_precision(&deps.querier, &asset_b_info)?;
-
-                let greater_precision = token_precision_0.max(token_precision_1);
-
-                let deposit_amount_0 =
-                    adjust_precision(asset_a_amount, token_precision_0, greater_precision)?;
-                let deposit_amount_1 =
-                    adjust_precision(asset_b_amount, token_precision_1, greater_precision)?;
-
-                let lp_amount = if total_share.is_zero() {
-                    let liquidity_token_precision = query_token_precision(
-                        &deps.querier,
-                        &AssetInfo::Token {
-                            contract_addr: config.pair_info.liquidity_token,
-                        },
-                    )?;
-
-                    // Initial share = collateral amount
-                    adjust_precision(
-                        Uint128::new(
-                            (U256::from(deposit_amount_0.u128())
-                                * U256::from(deposit_amount_1.u128()))
-                            .integer_sqrt()
-                            .as_u128(),
-                        ),
-                        greater_precision,
-                        liquidity_token_precision,
-                    )?
-                } else {
-                    let params = pair.query_config(&deps.querier)?.params
-                        .ok_or_else(|| StdError::generic_err("params not found"))?;
-                    let stable_pool_config: StablePoolConfig = from_binary(&params)?;
-                    let amp = stable_pool_config.amp * Uint128::from(AMP_PRECISION);
-                    let leverage = u64::try_from(amp.u128() * u128::from(N_COINS))
-                        .map_err(|_| StdError::generic_err("Overflow in leverage"))?;
-
-                    let mut pool_amount_0 =
-                        adjust_precision(pools[0].amount, token_precision_0, greater_precision)?;
-                    let mut pool_amount_1 =
-                        adjust_precision(pools[1].amount, token_precision_1, greater_precision)?;
-
-                    let d_before_addition_liquidity =
-                        compute_d(leverage, pool_amount_0.u128(), pool_amount_1.u128()).unwrap();
-
-                    pool_amount_0 = pool_amount_0.checked_add(deposit_amount_0)?;
-                    pool_amount_1 = pool_amount_1.checked_add(deposit_amount_1)?;
-
-                    let d_after_addition_liquidity =
-                        compute_d(leverage, pool_amount_0.u128(), pool_amount_1.u128()).unwrap();
-
-                    // d after adding liquidity may be less than or equal to d before adding liquidity because of rounding
-                    if d_before_addition_liquidity >= d_after_addition_liquidity {
-                        Uint128::zero()
-                    } else {
-                        total_share.multiply_ratio(
-                            d_after_addition_liquidity - d_before_addition_liquidity,
-                            d_before_addition_liquidity,
-                        )
-                    }
-                };
-
-                (
-                    lp_amount,
-                    Uint128::zero(),
-                    Uint128::zero(),
-                    Uint128::zero(),
-                    Uint128::zero(),
+        }
+        _ => {
+            if total_share.is_zero() {
+                Uint128::new(
+                    (U256::from(asset_amounts[0].u128()) * U256::from(asset_amounts[1].u128()))
+                        .integer_sqrt()
+                        .as_u128(),
+                )
+            } else {
+                std::cmp::min(
+                    asset_amounts[0].multiply_ratio(total_share, pools[0].amount),
+                    asset_amounts[1].multiply_ratio(total_share, pools[1].amount),
                 )
             }
-            PairType::Custom(_) => {
-                return Err(StdError::generic_err("Custom pair type not supported"));
-            }
-        };
+        }
+    };
 
     Ok(CompoundSimulationResponse {
         lp_amount,
-        swap_asset_a_amount,
-        swap_asset_b_amount,
-        return_a_amount,
-        return_b_amount,
+        swap_asset_a_amount: swap_asset_amounts[0],
+        swap_asset_b_amount: swap_asset_amounts[1],
+        return_a_amount: return_asset_amounts[0],
+        return_b_amount: return_asset_amounts[1],
+        swap_asset_amounts,
+        return_asset_amounts,
     })
 }
 
+/// ## Description
+/// Holds the parameters of a stableswap pool needed to replay the pair math locally.
+pub struct StablePool {
+    /// The pool amplification multiplied by [`AMP_PRECISION`] and the number of assets
+    pub leverage: u64,
+    /// The precision of each asset in the pool
+    pub precisions: Vec<u8>,
+    /// The greatest precision among the pool assets, used for the invariant calculations
+    pub greater_precision: u8,
+}
+
+impl StablePool {
+    /// ## Description
+    /// Loads amplification and asset precisions of the stableswap pair.
+    pub fn load(
+        querier: &QuerierWrapper,
+        pair_contract: &Addr,
+        asset_infos: &[AssetInfo],
+    ) -> StdResult<Self> {
+        let params = Pair(pair_contract.clone())
+            .query_config(querier)?
+            .params
+            .ok_or_else(|| StdError::generic_err("params not found"))?;
+        let stable_pool_config: StablePoolConfig = from_binary(&params)?;
+        let amp = stable_pool_config.amp * Uint128::from(AMP_PRECISION);
+        let leverage = u64::try_from(amp.u128() * asset_infos.len() as u128)
+            .map_err(|_| StdError::generic_err("Overflow in leverage"))?;
+
+        let precisions = asset_infos
+            .iter()
+            .map(|asset_info| query_token_precision(querier, asset_info))
+            .collect::<StdResult<Vec<u8>>>()?;
+        let greater_precision = precisions.iter().copied().max().unwrap_or_default();
+
+        Ok(StablePool {
+            leverage,
+            precisions,
+            greater_precision,
+        })
+    }
+
+    /// ## Description
+    /// Converts the amount of the asset at **index** to the precision used by the invariant.
+    pub fn to_invariant_precision(&self, index: usize, value: Uint128) -> StdResult<Uint128> {
+        adjust_precision(value, self.precisions[index], self.greater_precision)
+    }
+
+    /// ## Description
+    /// Converts the amount in the invariant precision back to the precision of the asset at **index**.
+    pub fn from_invariant_precision(&self, index: usize, value: Uint128) -> StdResult<Uint128> {
+        adjust_precision(value, self.greater_precision, self.precisions[index])
+    }
+}
+
+/// ## Description
+/// Returns the amount of LP token minted by the stableswap pair when providing **deposits** to **pools**.
+/// Like the pair contract, the imbalanced part of the deposit is charged with
+/// `commission_rate * n / (4 * (n - 1))` before minting, where `n` is the number of assets.
+/// All amounts must be in the invariant precision.
+pub fn compute_stable_lp_amount(
+    leverage: u64,
+    pools: &[Uint128],
+    deposits: &[Uint128],
+    total_share: Uint128,
+    commission_rate: Decimal256,
+) -> StdResult<Uint128> {
+    let n_coins = pools.len() as u64;
+    let init_d = compute_d_checked(leverage, pools)?;
+    let mut new_balances = pools
+        .iter()
+        .zip(deposits.iter())
+        .map(|(pool, deposit)| Ok(pool.checked_add(*deposit)?))
+        .collect::<StdResult<Vec<Uint128>>>()?;
+    let deposit_d = compute_d_checked(leverage, &new_balances)?;
+    if init_d == 0 || deposit_d <= init_d {
+        return Ok(Uint128::zero());
+    }
+
+    let fee = commission_rate * Decimal256::from_ratio(n_coins, 4u64 * (n_coins - 1));
+    for (new_balance, old_balance) in new_balances.iter_mut().zip(pools.iter()) {
+        let ideal_balance = old_balance.multiply_ratio(deposit_d, init_d);
+        let difference = if ideal_balance > *new_balance {
+            ideal_balance - *new_balance
+        } else {
+            *new_balance - ideal_balance
+        };
+        let charge: Uint128 = (Uint256::from(difference) * fee)
+            .try_into()
+            .map_err(|_| StdError::generic_err("overflow"))?;
+        *new_balance = new_balance.checked_sub(charge)?;
+    }
+
+    let after_fee_d = compute_d_checked(leverage, &new_balances)?;
+
+    // d after adding liquidity may be less than or equal to d before adding liquidity because of rounding
+    if init_d >= after_fee_d {
+        Ok(Uint128::zero())
+    } else {
+        Ok(total_share.multiply_ratio(after_fee_d - init_d, init_d))
+    }
+}
+
+/// ## Description
+/// Simulates return amount from swapping the asset at **offer_index** to the asset at **ask_index**
+/// on the stableswap pair. All amounts must be in the invariant precision.
+pub fn simulate_stable_swap(
+    leverage: u64,
+    pools: &[Uint128],
+    offer_index: usize,
+    ask_index: usize,
+    offer_amount: Uint128,
+    commission_rate: Decimal256,
+) -> StdResult<Uint128> {
+    let d = compute_d_checked(leverage, pools)?;
+    let mut new_pools: Vec<u128> = pools.iter().map(|it| it.u128()).collect();
+    new_pools[offer_index] = pools[offer_index].checked_add(offer_amount)?.u128();
+    let new_ask_pool = compute_y(leverage, &new_pools, ask_index, d)
+        .ok_or_else(|| StdError::generic_err("Failed to calculate the Y"))?;
+
+    // Round against the trader like the pair does
+    let return_amount = pools[ask_index]
+        .u128()
+        .saturating_sub(new_ask_pool)
+        .saturating_sub(1);
+    let return_amount = Uint256::from(return_amount);
+
+    // commission will be absorbed to pool
+    let commission_amount = return_amount * commission_rate;
+    (return_amount - commission_amount)
+        .try_into()
+        .map_err(|_| StdError::generic_err("overflow"))
+}
+
 /// ## Description
 /// Return a value using a newly bazified precision.
 /// ## Params
@@ -212,7 +308,7 @@ pub fn query_compound_simulation(
 /// * **current_precision** is an object of type [`u8`]. This is the `value`'s current precision
 ///
 /// * **new_precision** is an object of type [`u8`]. This is the new precision to use when returning the `value`.
-fn adjust_precision(
+pub fn adjust_precision(
     value: Uint128,
     current_precision: u8,
     new_precision: u8,
@@ -234,12 +330,15 @@ fn adjust_precision(
 /// * **Equation**
 ///
 /// A * sum(x_i) * n**n + D = A * D * n**n + D**(n+1) / (n**n * prod(x_i))
-pub fn compute_d(leverage: u64, amount_a: u128, amount_b: u128) -> Option<u128> {
-    let amount_a_times_coins =
-        checked_u8_mul(&U256::from(amount_a), N_COINS)?.checked_add(U256::one())?;
-    let amount_b_times_coins =
-        checked_u8_mul(&U256::from(amount_b), N_COINS)?.checked_add(U256::one())?;
-    let sum_x = amount_a.checked_add(amount_b)?; // sum(x_i), a.k.a S
+pub fn compute_d(leverage: u64, amounts: &[u128]) -> Option<u128> {
+    let n_coins = u8::try_from(amounts.len()).ok()?;
+    let amounts_times_coins = amounts
+        .iter()
+        .map(|amount| checked_u8_mul(&U256::from(*amount), n_coins)?.checked_add(U256::one()))
+        .collect::<Option<Vec<U256>>>()?;
+    let sum_x = amounts
+        .iter()
+        .try_fold(0u128, |sum, amount| sum.checked_add(*amount))?; // sum(x_i), a.k.a S
     if sum_x == 0 {
         Some(0)
     } else {
@@ -249,15 +348,14 @@ pub fn compute_d(leverage: u64, amount_a: u128, amount_b: u128) -> Option<u128>
         // Newton's method to approximate D
         for _ in 0..ITERATIONS {
             let mut d_product = d;
-            d_product = d_product
-                .checked_mul(d)?
-                .checked_div(amount_a_times_coins)?;
-            d_product = d_product
-                .checked_mul(d)?
-                .checked_div(amount_b_times_coins)?;
+            for amount_times_coins in amounts_times_coins.iter() {
+                d_product = d_product
+                    .checked_mul(d)?
+                    .checked_div(*amount_times_coins)?;
+            }
             d_previous = d;
             // d = (leverage * sum_x + d_p * n_coins) * d / ((leverage - 1) * d + (n_coins + 1) * d_p);
-            d = calculate_step(&d, leverage, sum_x, &d_product)?;
+            d = calculate_step(&d, leverage, sum_x, &d_product, n_coins)?;
             // Equality with the precision of 1
             if d == d_previous {
                 break;
@@ -267,21 +365,88 @@ pub fn compute_d(leverage: u64, amount_a: u128, amount_b: u128) -> Option<u128>
     }
 }
 
+/// ## Description
+/// Same as [`compute_d`] but takes [`Uint128`] amounts and returns a [`StdError`] on failure.
+fn compute_d_checked(leverage: u64, amounts: &[Uint128]) -> StdResult<u128> {
+    let amounts: Vec<u128> = amounts.iter().map(|it| it.u128()).collect();
+    compute_d(leverage, &amounts).ok_or_else(|| StdError::generic_err("Failed to calculate the D"))
+}
+
+/// ## Description
+/// Computes the new balance of the asset at **ask_index** that keeps the stableswap invariant (D)
+/// after the other pools change to **new_pools**. The balance at **ask_index** is ignored.
+///
+/// * **Equation**
+///
+/// y**2 + (sum'(x_j) + D / Ann - D) * y = D**(n+1) / (n**n * prod'(x_j) * Ann)
+///
+/// where sum' and prod' run over the assets other than the ask asset.
+pub fn compute_y(leverage: u64, new_pools: &[u128], ask_index: usize, d: u128) -> Option<u128> {
+    let d: U256 = d.into();
+    let leverage: U256 = leverage.into();
+    let amp_precision: U256 = AMP_PRECISION.into();
+    let n_coins: U256 = new_pools.len().into();
+
+    // c = D ** (n + 1) / (n ** n * prod'(x_j) * Ann)
+    let mut c = d;
+    let mut sum_x = U256::zero();
+    for (i, pool) in new_pools.iter().enumerate() {
+        if i == ask_index {
+            continue;
+        }
+        if *pool == 0 {
+            return None;
+        }
+        sum_x = sum_x.checked_add((*pool).into())?;
+        c = c
+            .checked_mul(d)?
+            .checked_div(U256::from(*pool).checked_mul(n_coins)?)?;
+    }
+    let c = c
+        .checked_mul(d)?
+        .checked_mul(amp_precision)?
+        .checked_div(leverage.checked_mul(n_coins)?)?;
+    // b = sum'(x_j) + D / Ann
+    let b = sum_x.checked_add(d.checked_mul(amp_precision)?.checked_div(leverage)?)?;
+
+    // Newton's method to approximate y
+    let mut y = d;
+    for _ in 0..ITERATIONS {
+        let y_prev = y;
+        // y = (y^2 + c) / (2y + b - D)
+        y = y
+            .checked_mul(y)?
+            .checked_add(c)?
+            .checked_div(y.checked_mul(2u8.into())?.checked_add(b)?.checked_sub(d)?)?;
+        // Equality with the precision of 1
+        if y == y_prev {
+            break;
+        }
+    }
+    u128::try_from(y).ok()
+}
+
 /// ## Description
 /// Helper function used to calculate the D invariant as a last step in the `compute_d` public function.
 ///
 /// * **Equation**:
 ///
 /// d = (leverage * sum_x + d_product * n_coins) * initial_d / ((leverage - 1) * initial_d + (n_coins + 1) * d_product)
-fn calculate_step(initial_d: &U256, leverage: u64, sum_x: u128, d_product: &U256) -> Option<U256> {
+fn calculate_step(
+    initial_d: &U256,
+    leverage: u64,
+    sum_x: u128,
+    d_product: &U256,
+    n_coins: u8,
+) -> Option<U256> {
     let leverage_mul = U256::from(leverage).checked_mul(sum_x.into())? / AMP_PRECISION;
-    let d_p_mul = checked_u8_mul(d_product, N_COINS)?;
+    let d_p_mul = checked_u8_mul(d_product, n_coins)?;
 
     let l_val = leverage_mul.checked_add(d_p_mul)?.checked_mul(*initial_d)?;
 
     let leverage_sub =
         initial_d.checked_mul((leverage.checked_sub(AMP_PRECISION)?).into())? / AMP_PRECISION;
-    let n_coins_sum = checked_u8_mul(d_product, N_COINS.checked_add(1)?)?;
+    let n_coins_sum = checked_u8_mul(d_product, n_coins.checked_add(1)?)?;
 
     let r_val = leverage_sub.checked_add(n_coins_sum)?;
 
@@ -1,19 +1,25 @@
 use astroport::asset::{PairInfo};
-use cosmwasm_std::{Decimal};
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::{Addr, Decimal};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use baz::adapters::pair::Pair;
+use baz::price_guard::PriceGuard;
 
 /// This structure describes the main control config of pair.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
+    /// The owner address
+    pub owner: Addr,
     /// The pair info
     pub pair_info: PairInfo,
     /// The swap commission
     pub commission_bps: u64,
     /// The slippage tolerance when providing liquidity
     pub slippage_tolerance: Decimal,
+    /// The price guard to bound swaps to a reference price
+    pub price_guard: Option<PriceGuard>,
 }
 
 /// Stores config at the given key
@@ -21,3 +27,18 @@ pub const CONFIG: Item<Config> = Item::new("config");
 
 /// Stores pair proxy for the given reward
 pub const PAIR_PROXY: Map<String, Pair> = Map::new("pair_proxy");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// This structure describes the config of the contract before the owner was added.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
+    pub pair_info: PairInfo,
+    pub commission_bps: u64,
+    pub slippage_tolerance: Decimal,
+    pub price_guard: Option<PriceGuard>,
+}
+
+/// Stores legacy config at the same key as [`CONFIG`]
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
@@ -3,21 +3,25 @@ use astroport::pair::{
     Cw20HookMsg as AstroportPairCw20HookMsg, ExecuteMsg as AstroportPairExecuteMsg,
 };
 use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
-use cosmwasm_std::{coin, to_binary, Addr, Coin, CosmosMsg, Decimal, Order, StdResult, Uint128, WasmMsg, from_binary, Uint256};
+use cosmwasm_std::{coin, to_binary, Addr, Coin, CosmosMsg, Decimal, Order, StdError, StdResult, Uint128, WasmMsg, from_binary, Uint256};
 use cw20::{Cw20ExecuteMsg};
 use baz::adapters::pair::Pair;
-use baz::compound_proxy::{CallbackMsg, ExecuteMsg, InstantiateMsg, QueryMsg};
+use baz::price_guard::{PriceGuard, PriceSource};
+use baz::compound_proxy::{
+    CallbackMsg, CompoundSimulationResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+};
 
-use crate::contract::{execute, get_swap_amount, instantiate, query};
+use crate::contract::{execute, get_swap_amount, instantiate, migrate, query};
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
-use crate::state::{Config, PAIR_PROXY};
+use crate::state::{Config, LegacyConfig, CONFIG, LEGACY_CONFIG, PAIR_PROXY};
 
 #[test]
 fn proper_initialization() -> StdResult<()> {
     let mut deps = mock_dependencies(&[]);
 
     let msg = InstantiateMsg {
+        owner: "owner".to_string(),
         pair_contract: "pair_contract".to_string(),
         commission_bps: 30,
         pair_proxies: vec![
@@ -35,6 +39,7 @@ fn proper_initialization() -> StdResult<()> {
             ),
         ],
         slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
 
     let sender = "addr0000";
@@ -81,15 +86,127 @@ fn proper_initialization() -> StdResult<()> {
     Ok(())
 }
 
+#[test]
+fn update_config() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![(
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("token0001"),
+            },
+            "pair0001".to_string(),
+        )],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = ExecuteMsg::UpdateConfig {
+        commission_bps: Some(50),
+        slippage_tolerance: Some(Decimal::percent(2)),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info("owner", &[]);
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.commission_bps, 50);
+    assert_eq!(config.slippage_tolerance, Decimal::percent(2));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        commission_bps: None,
+        slippage_tolerance: Some(Decimal::percent(120)),
+    };
+    let info = mock_info("owner", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert!(res.is_err());
+
+    let msg = ExecuteMsg::UpdatePairProxies {
+        add: Some(vec![(
+            AssetInfo::NativeToken {
+                denom: "ibc/token".to_string(),
+            },
+            "pair0002".to_string(),
+        )]),
+        remove: Some(vec![AssetInfo::Token {
+            contract_addr: Addr::unchecked("token0001"),
+        }]),
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_eq!(res, Err(ContractError::Unauthorized {}));
+
+    let info = mock_info("owner", &[]);
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = QueryMsg::PairProxies {
+        start_after: None,
+        limit: None,
+    };
+    let pair_proxies: Vec<(String, Pair)> = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(
+        pair_proxies,
+        vec![("ibc/token".to_string(), Pair(Addr::unchecked("pair0002")))]
+    );
+
+    let msg = ExecuteMsg::UpdatePriceGuard {
+        price_guard: Some(PriceGuard {
+            max_deviation: Decimal::percent(3),
+            price_sources: vec![],
+        }),
+    };
+    let info = mock_info("owner", &[]);
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {})?)?;
+    assert_eq!(config.price_guard.map(|it| it.max_deviation), Some(Decimal::percent(3)));
+
+    // ownership transfer
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: "new_owner".to_string(),
+        expires_in: 100,
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert!(res.is_err());
+
+    let info = mock_info("owner", &[]);
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimOwnership {});
+    assert!(res.is_err());
+
+    let info = mock_info("new_owner", &[]);
+    execute(deps.as_mut(), env.clone(), info, ExecuteMsg::ClaimOwnership {})?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env, QueryMsg::Config {})?)?;
+    assert_eq!(config.owner, Addr::unchecked("new_owner"));
+
+    Ok(())
+}
+
 #[test]
 fn compound() -> Result<(), ContractError> {
     let mut deps = mock_dependencies(&[]);
 
     let msg = InstantiateMsg {
+        owner: "owner".to_string(),
         pair_contract: "pair_contract".to_string(),
         commission_bps: 30,
         pair_proxies: vec![],
         slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
 
     let sender = "addr0000";
@@ -211,6 +328,69 @@ fn compound() -> Result<(), ContractError> {
     Ok(())
 }
 
+#[test]
+fn compound_with_price_guard() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract".to_string(),
+        commission_bps: 30,
+        pair_proxies: vec![(
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("astro"),
+            },
+            "pair_astro_token".to_string(),
+        )],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: Some(PriceGuard {
+            max_deviation: Decimal::percent(3),
+            price_sources: vec![(
+                AssetInfo::Token {
+                    contract_addr: Addr::unchecked("astro"),
+                },
+                AssetInfo::Token {
+                    contract_addr: Addr::unchecked("token"),
+                },
+                PriceSource::Oracle {
+                    contract_addr: Addr::unchecked("oracle"),
+                },
+            )],
+        }),
+    };
+
+    let env = mock_env();
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let msg = ExecuteMsg::Compound {
+        rewards: vec![token_asset(Addr::unchecked("astro"), Uint128::from(100u128))],
+        to: None,
+        no_swap: Some(true),
+        slippage_tolerance: None,
+    };
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages[1].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "astro".to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "pair_astro_token".to_string(),
+                amount: Uint128::new(100),
+                msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: Some(Decimal::from_ratio(100u128, 1000000u128)),
+                    max_spread: Some(Decimal::percent(3)),
+                    to: None,
+                })?
+            })?,
+        })
+    );
+
+    Ok(())
+}
+
 #[test]
 fn optimal_swap() -> Result<(), ContractError> {
     let mut deps = mock_dependencies(&[]);
@@ -232,10 +412,12 @@ fn optimal_swap() -> Result<(), ContractError> {
     let env = mock_env();
 
     let msg = InstantiateMsg {
+        owner: "owner".to_string(),
         pair_contract: "pair_contract".to_string(),
         commission_bps: 30,
         pair_proxies: vec![],
         slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -277,6 +459,156 @@ fn optimal_swap() -> Result<(), ContractError> {
     Ok(())
 }
 
+#[test]
+fn optimal_swap_stable() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_balance(&[
+        (
+            &String::from("pair_contract_stable"),
+            &[
+                Coin {
+                    denom: "uusdc".to_string(),
+                    amount: Uint128::new(1000000000000),
+                },
+                Coin {
+                    denom: "uusdt".to_string(),
+                    amount: Uint128::new(1000000000000),
+                },
+            ],
+        ),
+        (
+            &String::from(MOCK_CONTRACT_ADDR),
+            &[Coin {
+                denom: "uusdc".to_string(),
+                amount: Uint128::new(500000000000),
+            }],
+        ),
+    ]);
+    deps.querier.with_token_balances(&[(
+        &String::from("liquidity_token"),
+        &[(&String::from("xxxx"), &Uint128::new(2000000000000))],
+    )]);
+
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract_stable".to_string(),
+        commission_bps: 5,
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = ExecuteMsg::Callback(CallbackMsg::OptimalSwap {});
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        vec![CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "pair_contract_stable".to_string(),
+            funds: vec![coin(19012918240, "uusdc")],
+            msg: to_binary(&AstroportPairExecuteMsg::Swap {
+                offer_asset: native_asset("uusdc".to_string(), Uint128::new(19012918240)),
+                ask_asset_info: Some(AssetInfo::NativeToken {
+                    denom: "uusdt".to_string()
+                }),
+                belief_price: Some(Decimal::MAX),
+                max_spread: Some(Decimal::percent(50)),
+                to: None,
+            })?,
+        }),]
+    );
+
+    // simulation must agree with the swap done on execution
+    let msg = QueryMsg::CompoundSimulation {
+        rewards: vec![native_asset("uusdc".to_string(), Uint128::new(500000000000))],
+    };
+    let res: CompoundSimulationResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(
+        res.swap_asset_amounts,
+        vec![Uint128::new(19012918240), Uint128::zero()]
+    );
+    assert_eq!(res.swap_asset_a_amount, Uint128::new(19012918240));
+    assert_eq!(res.swap_asset_b_amount, Uint128::zero());
+    assert!(res.lp_amount > Uint128::zero());
+
+    Ok(())
+}
+
+#[test]
+fn optimal_swap_stable_multi_asset() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+    deps.querier.with_balance(&[
+        (
+            &String::from("pair_contract_stable_3"),
+            &[
+                coin(1000000000000, "uusdc"),
+                coin(1000000000000, "uusdt"),
+                coin(1000000000000, "udai"),
+            ],
+        ),
+        (
+            &String::from(MOCK_CONTRACT_ADDR),
+            &[coin(2000000000000, "uusdc"), coin(100000000000, "uusdt")],
+        ),
+    ]);
+    deps.querier.with_token_balances(&[(
+        &String::from("liquidity_token"),
+        &[(&String::from("xxxx"), &Uint128::new(3000000000000))],
+    )]);
+
+    let env = mock_env();
+
+    let msg = InstantiateMsg {
+        owner: "owner".to_string(),
+        pair_contract: "pair_contract_stable_3".to_string(),
+        commission_bps: 5,
+        pair_proxies: vec![],
+        slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    let msg = ExecuteMsg::Callback(CallbackMsg::OptimalSwap {});
+    let info = mock_info(env.contract.address.as_str(), &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    // the imbalance fee of a 3-asset pool is lower than the swap fee, depositing as is mints more
+    assert!(res.messages.is_empty());
+
+    let msg = QueryMsg::CompoundSimulation {
+        rewards: vec![
+            native_asset("uusdc".to_string(), Uint128::new(2000000000000)),
+            native_asset("uusdt".to_string(), Uint128::new(100000000000)),
+        ],
+    };
+    let res: CompoundSimulationResponse = from_binary(&query(deps.as_ref(), env, msg)?)?;
+    assert_eq!(
+        res,
+        CompoundSimulationResponse {
+            lp_amount: Uint128::new(2027949851689),
+            swap_asset_a_amount: Uint128::zero(),
+            swap_asset_b_amount: Uint128::zero(),
+            return_a_amount: Uint128::zero(),
+            return_b_amount: Uint128::zero(),
+            swap_asset_amounts: vec![Uint128::zero(); 3],
+            return_asset_amounts: vec![Uint128::zero(); 3],
+        }
+    );
+
+    Ok(())
+}
+
 #[test]
 fn provide_liquidity() -> Result<(), ContractError> {
     let mut deps = mock_dependencies(&[]);
@@ -312,10 +644,12 @@ fn provide_liquidity() -> Result<(), ContractError> {
     let env = mock_env();
 
     let msg = InstantiateMsg {
+        owner: "owner".to_string(),
         pair_contract: "pair_contract_2".to_string(),
         commission_bps: 30,
         pair_proxies: vec![],
         slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -486,6 +820,7 @@ fn test_compound_simulation() -> StdResult<()> {
         )]);
 
     let msg = InstantiateMsg {
+        owner: "owner".to_string(),
         pair_contract: "pair_contract".to_string(),
         commission_bps: 30,
         pair_proxies: vec![
@@ -497,6 +832,7 @@ fn test_compound_simulation() -> StdResult<()> {
             ),
         ],
         slippage_tolerance: Decimal::percent(1),
+        price_guard: None,
     };
 
     let sender = "addr0000";
@@ -516,3 +852,91 @@ fn test_compound_simulation() -> StdResult<()> {
 
     Ok(())
 }
+
+
+#[test]
+fn test_migrate() -> StdResult<()> {
+    let mut deps = mock_dependencies(&[]);
+    let env = mock_env();
+
+    let pair_info = PairInfo {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("token"),
+            },
+        ],
+        contract_addr: Addr::unchecked("pair_contract"),
+        liquidity_token: Addr::unchecked("liquidity_token"),
+        pair_type: astroport::factory::PairType::Xyk {},
+    };
+    let price_guard = PriceGuard {
+        max_deviation: Decimal::percent(3),
+        price_sources: vec![(
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("astro"),
+            },
+            AssetInfo::Token {
+                contract_addr: Addr::unchecked("token"),
+            },
+            PriceSource::Oracle {
+                contract_addr: Addr::unchecked("oracle"),
+            },
+        )],
+    };
+    LEGACY_CONFIG.save(
+        deps.as_mut().storage,
+        &LegacyConfig {
+            pair_info: pair_info.clone(),
+            commission_bps: 30,
+            slippage_tolerance: Decimal::percent(1),
+            price_guard: Some(price_guard.clone()),
+        },
+    )?;
+    PAIR_PROXY.save(
+        deps.as_mut().storage,
+        "astro".to_string(),
+        &Pair(Addr::unchecked("pair_astro_token")),
+    )?;
+
+    // the legacy contract has no owner
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg { owner: None });
+    assert_eq!(res, Err(StdError::generic_err("owner is required")));
+
+    migrate(
+        deps.as_mut(),
+        env.clone(),
+        MigrateMsg {
+            owner: Some("owner".to_string()),
+        },
+    )?;
+    assert_eq!(
+        CONFIG.load(deps.as_ref().storage)?,
+        Config {
+            owner: Addr::unchecked("owner"),
+            pair_info,
+            commission_bps: 30,
+            slippage_tolerance: Decimal::percent(1),
+            price_guard: Some(price_guard),
+        }
+    );
+
+    // the pair proxies are kept
+    let res: Vec<(String, Pair)> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::PairProxies {
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res, vec![("astro".to_string(), Pair(Addr::unchecked("pair_astro_token")))]);
+
+    // migrating again keeps the config
+    migrate(deps.as_mut(), env, MigrateMsg { owner: None })?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.owner, Addr::unchecked("owner"));
+
+    Ok(())
+}
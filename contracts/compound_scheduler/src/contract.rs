@@ -0,0 +1,309 @@
+use crate::error::ContractError;
+use crate::state::{Config, CONFIG, OWNERSHIP_PROPOSAL, VAULTS};
+
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use astroport::asset::{AssetInfo, AssetInfoExt};
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Order, QuerierWrapper, Response, StdError, StdResult, WasmMsg,
+};
+use cw_storage_plus::Bound;
+use serde::Deserialize;
+
+use baz::adapters::asset::AssetEx;
+use baz::adapters::generator::Generator;
+use baz::astroport_farm::{ExecuteMsg as FarmExecuteMsg, QueryMsg as FarmQueryMsg};
+use baz::compound_scheduler::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, VaultInfo, VaultSchedule,
+};
+
+/// This structure holds the fields of the farm config used by the scheduler
+#[derive(Deserialize)]
+struct FarmConfig {
+    staking_contract: Addr,
+    liquidity_token: Addr,
+}
+
+/// ## Description
+/// Creates a new contract with the bazified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the bazified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner: deps.api.addr_validate(&msg.owner)?,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateVaults { add, remove } => update_vaults(deps, env, info, add, remove),
+        ExecuteMsg::Compound { farms } => compound(deps, env, farms),
+        ExecuteMsg::Sweep {
+            asset_infos,
+            recipient,
+        } => sweep(deps, env, info, asset_infos, recipient),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        }
+    }
+}
+
+/// ## Description
+/// Adds, updates or removes vault schedules. The last compound time is kept when a schedule is updated.
+fn update_vaults(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    add: Option<Vec<VaultSchedule>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(remove) = remove {
+        for farm in remove {
+            let farm = deps.api.addr_validate(&farm)?;
+            VAULTS.remove(deps.storage, &farm);
+        }
+    }
+
+    if let Some(add) = add {
+        for schedule in add {
+            let farm = deps.api.addr_validate(&schedule.farm)?;
+            let vault = match VAULTS.may_load(deps.storage, &farm)? {
+                Some(vault) => VaultInfo {
+                    min_pending_reward: schedule.min_pending_reward,
+                    max_staleness: schedule.max_staleness,
+                    ..vault
+                },
+                None => {
+                    let farm_config: FarmConfig = deps
+                        .querier
+                        .query_wasm_smart(farm.to_string(), &FarmQueryMsg::Config {})?;
+                    VaultInfo {
+                        farm: farm.clone(),
+                        staking_contract: farm_config.staking_contract,
+                        liquidity_token: farm_config.liquidity_token,
+                        min_pending_reward: schedule.min_pending_reward,
+                        max_staleness: schedule.max_staleness,
+                        last_compound: env.block.time.seconds(),
+                    }
+                }
+            };
+            VAULTS.save(deps.storage, &farm, &vault)?;
+        }
+    }
+
+    Ok(Response::new().add_attribute("action", "update_vaults"))
+}
+
+/// ## Description
+/// Returns true if the pending reward of the vault reaches the threshold, or the vault is stale.
+/// A vault compounded in the current block is never due.
+fn is_due(querier: &QuerierWrapper, env: &Env, vault: &VaultInfo) -> StdResult<bool> {
+    let now = env.block.time.seconds();
+    if vault.last_compound >= now {
+        return Ok(false);
+    }
+    if now >= vault.last_compound.saturating_add(vault.max_staleness) {
+        return Ok(true);
+    }
+
+    let pending_token = Generator(vault.staking_contract.clone()).query_pending_token(
+        querier,
+        &vault.liquidity_token,
+        &vault.farm,
+    )?;
+
+    Ok(pending_token.pending >= vault.min_pending_reward)
+}
+
+/// ## Description
+/// Compounds the farms which are due and records the compound time. Farms which are not due are skipped.
+fn compound(deps: DepsMut, env: Env, farms: Vec<String>) -> Result<Response, ContractError> {
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![attr("action", "compound")];
+    for farm in farms {
+        let farm = deps.api.addr_validate(&farm)?;
+        let mut vault = VAULTS
+            .may_load(deps.storage, &farm)?
+            .ok_or_else(|| ContractError::VaultNotFound(farm.to_string()))?;
+
+        if !is_due(&deps.querier, &env, &vault)? {
+            continue;
+        }
+
+        vault.last_compound = env.block.time.seconds();
+        VAULTS.save(deps.storage, &farm, &vault)?;
+
+        messages.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: farm.to_string(),
+            msg: to_binary(&FarmExecuteMsg::Compound {
+                minimum_receive: None,
+                slippage_tolerance: None,
+            })?,
+            funds: vec![],
+        }));
+        attributes.push(attr("farm", farm));
+    }
+
+    if messages.is_empty() {
+        return Err(ContractError::NothingToCompound {});
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+/// ## Description
+/// Sends the whole balance of the assets to the recipient. Assets without balance are skipped.
+fn sweep(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_infos: Vec<AssetInfo>,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let mut messages: Vec<CosmosMsg> = vec![];
+    let mut attributes = vec![attr("action", "sweep")];
+    for asset_info in asset_infos {
+        asset_info.check(deps.api)?;
+        let balance = asset_info.query_pool(&deps.querier, &env.contract.address)?;
+        if balance.is_zero() {
+            continue;
+        }
+        messages.push(asset_info.with_balance(balance).transfer_msg(&recipient)?);
+        attributes.push(attr("asset", asset_info.to_string()));
+        attributes.push(attr("amount", balance));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Vault { farm } => {
+            let farm = deps.api.addr_validate(&farm)?;
+            to_binary(&VAULTS.load(deps.storage, &farm)?)
+        }
+        QueryMsg::Vaults { start_after, limit } => {
+            to_binary(&query_vaults(deps, start_after, limit)?)
+        }
+        QueryMsg::DueVaults { start_after, limit } => {
+            to_binary(&query_due_vaults(deps, env, start_after, limit)?)
+        }
+    }
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns the list of vault schedules ordered by farm address.
+fn query_vaults(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<VaultInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    VAULTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
+/// ## Description
+/// Returns the farms which are due to compound within the page of vaults.
+/// The result may contain fewer farms than `limit`, keepers should paginate with the last farm of [`QueryMsg::Vaults`].
+fn query_due_vaults(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let mut farms = vec![];
+    for vault in query_vaults(deps, start_after, limit)? {
+        if is_due(&deps.querier, &env, &vault)? {
+            farms.push(vault.farm);
+        }
+    }
+
+    Ok(farms)
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    Ok(Response::default())
+}
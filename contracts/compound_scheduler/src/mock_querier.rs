@@ -0,0 +1,112 @@
+use astroport::generator::{PendingTokenResponse, QueryMsg as GeneratorQueryMsg};
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Addr, Coin, Empty, OwnedDeps, Querier, QuerierResult,
+    QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use baz::astroport_farm::QueryMsg as FarmQueryMsg;
+
+pub const GENERATOR: &str = "generator";
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        storage: MockStorage::default(),
+        api: MockApi::default(),
+        querier: custom_querier,
+        custom_query_type: Default::default(),
+    }
+}
+
+#[derive(Serialize)]
+struct FarmConfig {
+    owner: Addr,
+    staking_contract: Addr,
+    liquidity_token: Addr,
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    pending_tokens: HashMap<String, Uint128>,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if contract_addr == GENERATOR =>
+            {
+                match from_binary(msg).unwrap() {
+                    GeneratorQueryMsg::PendingToken { lp_token, .. } => SystemResult::Ok(
+                        to_binary(&PendingTokenResponse {
+                            pending: self
+                                .pending_tokens
+                                .get(&lp_token)
+                                .cloned()
+                                .unwrap_or_default(),
+                            pending_on_proxy: None,
+                        })
+                        .into(),
+                    ),
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
+            QueryRequest::Wasm(WasmQuery::Smart { contract_addr, msg })
+                if contract_addr.starts_with("farm") =>
+            {
+                match from_binary(msg).unwrap() {
+                    FarmQueryMsg::Config {} => SystemResult::Ok(
+                        to_binary(&FarmConfig {
+                            owner: Addr::unchecked("owner"),
+                            staking_contract: Addr::unchecked(GENERATOR),
+                            liquidity_token: Addr::unchecked(
+                                contract_addr.replace("farm", "lp_token"),
+                            ),
+                        })
+                        .into(),
+                    ),
+                    _ => panic!("DO NOT ENTER HERE"),
+                }
+            }
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            pending_tokens: HashMap::new(),
+        }
+    }
+
+    pub fn set_pending_token(&mut self, lp_token: &str, amount: Uint128) {
+        self.pending_tokens.insert(lp_token.to_string(), amount);
+    }
+}
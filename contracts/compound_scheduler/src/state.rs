@@ -0,0 +1,23 @@
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::Addr;
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use baz::compound_scheduler::VaultInfo;
+
+/// This structure stores the main config parameters of the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to update the vault schedules
+    pub owner: Addr,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Stores the vault schedules by farm address
+pub const VAULTS: Map<&Addr, VaultInfo> = Map::new("vaults");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
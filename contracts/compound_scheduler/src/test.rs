@@ -0,0 +1,319 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage};
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Env, OwnedDeps, Response, StdError,
+    SubMsg, Uint128, WasmMsg,
+};
+
+use baz::astroport_farm::ExecuteMsg as FarmExecuteMsg;
+use baz::compound_scheduler::{ExecuteMsg, InstantiateMsg, QueryMsg, VaultInfo, VaultSchedule};
+
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier, GENERATOR};
+use crate::state::Config;
+
+const OWNER: &str = "owner";
+const USER_1: &str = "user_1";
+const FARM_1: &str = "farm_1";
+const FARM_2: &str = "farm_2";
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+    let env = mock_env();
+    create(&mut deps, env.clone())?;
+    update_vaults(&mut deps, env)?;
+
+    Ok(())
+}
+
+#[test]
+fn compound_not_registered() -> Result<(), ContractError> {
+    let (mut deps, env) = setup()?;
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(
+        deps.as_mut(),
+        env,
+        info,
+        ExecuteMsg::Compound {
+            farms: vec!["farm_3".to_string()],
+        },
+    );
+    assert_error(res, "Vault is not registered: farm_3");
+
+    Ok(())
+}
+
+#[test]
+fn compound_not_due() -> Result<(), ContractError> {
+    let (mut deps, mut env) = setup()?;
+
+    // below threshold and not stale
+    env.block.time = env.block.time.plus_seconds(100);
+    deps.querier.set_pending_token("lp_token_1", Uint128::from(999u128));
+    deps.querier.set_pending_token("lp_token_2", Uint128::from(1000u128));
+
+    let res: Vec<Addr> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::DueVaults {
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res, Vec::<Addr>::new());
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env, info, compound_all_msg());
+    assert_eq!(res, Err(ContractError::NothingToCompound {}));
+
+    Ok(())
+}
+
+#[test]
+fn compound_due_by_pending_reward() -> Result<(), ContractError> {
+    let (mut deps, mut env) = setup()?;
+
+    // farm_1 reaches the threshold
+    env.block.time = env.block.time.plus_seconds(100);
+    deps.querier.set_pending_token("lp_token_1", Uint128::from(1000u128));
+    deps.querier.set_pending_token("lp_token_2", Uint128::from(1000u128));
+    let res: Vec<Addr> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::DueVaults {
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res, vec![Addr::unchecked(FARM_1)]);
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), compound_all_msg())?;
+    assert_eq!(res.messages, vec![compound_msg(FARM_1)]);
+
+    let res: VaultInfo = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Vault {
+            farm: FARM_1.to_string(),
+        },
+    )?)?;
+    assert_eq!(res.last_compound, env.block.time.seconds());
+
+    // compounded vault is skipped in the same block
+    let res = execute(deps.as_mut(), env, info, compound_all_msg());
+    assert_eq!(res, Err(ContractError::NothingToCompound {}));
+
+    Ok(())
+}
+
+#[test]
+fn compound_due_by_staleness() -> Result<(), ContractError> {
+    let (mut deps, mut env) = setup()?;
+
+    // farm_2 becomes stale without pending reward
+    env.block.time = env.block.time.plus_seconds(3600);
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env, info, compound_all_msg())?;
+    assert_eq!(res.messages, vec![compound_msg(FARM_2)]);
+
+    Ok(())
+}
+
+#[test]
+fn compound_max_staleness_overflow() -> Result<(), ContractError> {
+    let (mut deps, mut env) = setup()?;
+
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::UpdateVaults {
+        add: Some(vec![VaultSchedule {
+            farm: FARM_2.to_string(),
+            min_pending_reward: Uint128::from(5000u128),
+            max_staleness: u64::MAX,
+        }]),
+        remove: None,
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    // a huge max_staleness never becomes stale, but farm_1 is still compounded
+    env.block.time = env.block.time.plus_seconds(86400);
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env, info, compound_all_msg())?;
+    assert_eq!(res.messages, vec![compound_msg(FARM_1)]);
+
+    Ok(())
+}
+
+#[test]
+fn sweep_bounty() -> Result<(), ContractError> {
+    // a permissionless farm pays the compound bounty to the scheduler
+    let mut deps = mock_dependencies(&[Coin {
+        denom: "uluna".to_string(),
+        amount: Uint128::from(100u128),
+    }]);
+    let env = mock_env();
+    create(&mut deps, env.clone())?;
+    update_vaults(&mut deps, env.clone())?;
+
+    let msg = ExecuteMsg::Sweep {
+        asset_infos: vec![
+            AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+        ],
+        recipient: USER_1.to_string(),
+    };
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // assets without balance are skipped
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER_1.to_string(),
+            amount: vec![Coin {
+                denom: "uluna".to_string(),
+                amount: Uint128::from(100u128),
+            }],
+        }))]
+    );
+
+    Ok(())
+}
+
+fn setup() -> Result<(OwnedDeps<MockStorage, MockApi, WasmMockQuerier>, Env), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+    let env = mock_env();
+    create(&mut deps, env.clone())?;
+    update_vaults(&mut deps, env.clone())?;
+
+    Ok((deps, env))
+}
+
+fn compound_all_msg() -> ExecuteMsg {
+    ExecuteMsg::Compound {
+        farms: vec![FARM_1.to_string(), FARM_2.to_string()],
+    }
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+fn compound_msg(farm: &str) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: farm.to_string(),
+        msg: to_binary(&FarmExecuteMsg::Compound {
+            minimum_receive: None,
+            slippage_tolerance: None,
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: Env,
+) -> Result<(), ContractError> {
+    let info = mock_info(USER_1, &[]);
+    let msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+    };
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env, QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            owner: Addr::unchecked(OWNER),
+        }
+    );
+
+    Ok(())
+}
+
+fn update_vaults(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    env: Env,
+) -> Result<(), ContractError> {
+    let msg = ExecuteMsg::UpdateVaults {
+        add: Some(vec![
+            VaultSchedule {
+                farm: FARM_1.to_string(),
+                min_pending_reward: Uint128::from(1000u128),
+                max_staleness: 86400,
+            },
+            VaultSchedule {
+                farm: FARM_2.to_string(),
+                min_pending_reward: Uint128::from(5000u128),
+                max_staleness: 3600,
+            },
+            VaultSchedule {
+                farm: "farm_3".to_string(),
+                min_pending_reward: Uint128::from(5000u128),
+                max_staleness: 3600,
+            },
+        ]),
+        remove: None,
+    };
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+
+    let msg = ExecuteMsg::UpdateVaults {
+        add: None,
+        remove: Some(vec!["farm_3".to_string()]),
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let res: Vec<VaultInfo> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Vaults {
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(
+        res,
+        vec![
+            VaultInfo {
+                farm: Addr::unchecked(FARM_1),
+                staking_contract: Addr::unchecked(GENERATOR),
+                liquidity_token: Addr::unchecked("lp_token_1"),
+                min_pending_reward: Uint128::from(1000u128),
+                max_staleness: 86400,
+                last_compound: env.block.time.seconds(),
+            },
+            VaultInfo {
+                farm: Addr::unchecked(FARM_2),
+                staking_contract: Addr::unchecked(GENERATOR),
+                liquidity_token: Addr::unchecked("lp_token_2"),
+                min_pending_reward: Uint128::from(5000u128),
+                max_staleness: 3600,
+                last_compound: env.block.time.seconds(),
+            },
+        ]
+    );
+
+    Ok(())
+}
@@ -9,6 +9,7 @@ use cosmwasm_std::{entry_point, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env
 use baz::fees_collector::{AssetWithLimit, BalancesResponse, CollectSimulationResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use std::collections::{HashMap, HashSet};
 use baz::adapters::asset::AssetEx;
+use baz::price_guard::PriceGuard;
 
 /// ## Description
 /// Creates a new contract with the bazified parameters in the [`InstantiateMsg`].
@@ -22,6 +23,9 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
 
     msg.stablecoin.check(deps.api)?;
+    if let Some(price_guard) = &msg.price_guard {
+        price_guard.check(deps.api)?;
+    }
 
     let config = Config {
         owner: deps.api.addr_validate(&msg.owner)?,
@@ -31,6 +35,7 @@ pub fn instantiate(
         target_list: msg.target_list.into_iter()
                                 .map(|(addr, weight)| Ok((deps.api.addr_validate(&addr)?, weight)))
                                 .collect::<StdResult<_>>()?,
+        price_guard: msg.price_guard,
     };
 
     CONFIG.save(deps.storage, &config)?;
@@ -61,6 +66,7 @@ pub fn execute(
             factory_contract,
             target_list,
         ),
+        ExecuteMsg::UpdatePriceGuard { price_guard } => update_price_guard(deps, info, price_guard),
         ExecuteMsg::SwapBridgeAssets { assets, depth } => {
             swap_bridge_assets(deps, env, info, assets, depth)
         }
@@ -402,6 +408,29 @@ pub fn update_config(
     Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
 }
 
+/// ## Description
+/// Sets or removes the price guard used to bound swaps. Returns a [`ContractError`] on failure or the [`CONFIG`] data will be updated.
+fn update_price_guard(
+    deps: DepsMut,
+    info: MessageInfo,
+    price_guard: Option<PriceGuard>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(price_guard) = &price_guard {
+        price_guard.check(deps.api)?;
+    }
+    config.price_guard = price_guard;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "update_price_guard")]))
+}
+
 /// ## Description
 /// Adds or removes bridge tokens used to swap fee tokens to stablecoin. Returns a [`ContractError`] on failure.
 fn update_bridges(
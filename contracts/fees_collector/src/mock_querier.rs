@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Decimal, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdError, StdResult, SystemError, SystemResult, to_binary, Uint128, WasmQuery};
+use cosmwasm_std::{Addr, BalanceResponse, BankQuery, Binary, Coin, ContractResult, Decimal, Empty, from_binary, from_slice, OwnedDeps, Querier, QuerierResult, QueryRequest, StdError, StdResult, SystemError, SystemResult, to_binary, Uint128, Uint256, WasmQuery};
 use cosmwasm_std::testing::{MockApi, MockStorage};
 
 use schemars::JsonSchema;
@@ -27,6 +27,7 @@ pub struct WasmMockQuerier {
     raw: HashMap<(String, Binary), Binary>,
     pairs: HashMap<Vec<u8>, PairInfo>,
     prices: HashMap<String, Decimal>,
+    oracle_prices: HashMap<String, (AssetInfo, Decimal)>,
 }
 
 impl WasmMockQuerier {
@@ -36,6 +37,7 @@ impl WasmMockQuerier {
             raw: HashMap::new(),
             pairs: HashMap::new(),
             prices: HashMap::new(),
+            oracle_prices: HashMap::new(),
         }
     }
 
@@ -64,6 +66,10 @@ impl WasmMockQuerier {
         self.prices.get(pair)
     }
 
+    pub fn set_oracle_price(&mut self, oracle: String, ask_asset_info: AssetInfo, price: Decimal) {
+        self.oracle_prices.insert(oracle, (ask_asset_info, price));
+    }
+
     fn execute_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         let result = match request {
             QueryRequest::Bank(BankQuery::Balance {
@@ -141,6 +147,11 @@ impl WasmMockQuerier {
                     commission_amount: Default::default()
                 })
             },
+            MockQueryMsg::Consult { amount, .. } => {
+                let (ask_asset_info, price) = self.oracle_prices.get(contract_addr)
+                    .ok_or_else(|| StdError::generic_err("No oracle price"))?;
+                to_binary(&vec![(ask_asset_info.clone(), Uint256::from(amount * *price))])
+            },
         }
     }
 }
@@ -165,6 +176,10 @@ enum MockQueryMsg {
     Simulation {
         offer_asset: Asset,
     },
+    Consult {
+        token: AssetInfo,
+        amount: Uint128,
+    },
 }
 
 impl Querier for WasmMockQuerier {
@@ -2,6 +2,7 @@ use astroport::{asset::AssetInfo, common::OwnershipProposal};
 use cosmwasm_std::{Addr};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
+use baz::price_guard::PriceGuard;
 use serde::{Deserialize, Serialize};
 
 /// This structure stores the main parameter for the fees collector contract.
@@ -17,6 +18,8 @@ pub struct Config {
     pub target_list: Vec<(Addr, u64)>,
     /// The stablecoin token address
     pub stablecoin: AssetInfo,
+    /// The price guard to bound swaps to a reference price
+    pub price_guard: Option<PriceGuard>,
 }
 
 /// Stores the contract configuration at the given key
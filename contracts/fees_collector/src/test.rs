@@ -10,6 +10,7 @@ use cosmwasm_std::{
 };
 use cw20::Cw20ExecuteMsg;
 use baz::fees_collector::{AssetWithLimit, CollectSimulationResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+use baz::price_guard::{PriceGuard, PriceSource};
 
 use crate::contract::{execute, instantiate, query};
 use crate::error::ContractError;
@@ -36,6 +37,7 @@ fn test() -> Result<(), ContractError> {
     owner(&mut deps)?;
     bridges(&mut deps)?;
     collect(&mut deps)?;
+    price_guard(&mut deps)?;
     distribute_fees(&mut deps)?;
 
     Ok(())
@@ -63,6 +65,7 @@ fn create(
             denom: IBC_TOKEN.to_string(),
         },
         target_list: vec![(USER_2.to_string(), 2), (USER_3.to_string(), 3)],
+        price_guard: None,
     };
     let res = instantiate(deps.as_mut(), env, info, instantiate_msg);
     assert!(res.is_ok());
@@ -78,6 +81,7 @@ fn create(
             stablecoin: AssetInfo::NativeToken {
                 denom: IBC_TOKEN.to_string(),
             },
+            price_guard: None,
         }
     );
 
@@ -130,6 +134,7 @@ fn config(
             stablecoin: AssetInfo::NativeToken {
                 denom: IBC_TOKEN.to_string(),
             },
+            price_guard: None,
         }
     );
 
@@ -153,6 +158,7 @@ fn config(
             stablecoin: AssetInfo::NativeToken {
                 denom: IBC_TOKEN.to_string(),
             },
+            price_guard: None,
         }
     );
 
@@ -521,6 +527,98 @@ fn collect(
     Ok(())
 }
 
+fn price_guard(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let msg = ExecuteMsg::UpdatePriceGuard {
+        price_guard: Some(PriceGuard {
+            max_deviation: Decimal::percent(2),
+            price_sources: vec![(
+                AssetInfo::Token {
+                    contract_addr: Addr::unchecked(TOKEN_2),
+                },
+                AssetInfo::NativeToken {
+                    denom: IBC_TOKEN.to_string(),
+                },
+                PriceSource::Oracle {
+                    contract_addr: Addr::unchecked("token2ibc_oracle"),
+                },
+            )],
+        }),
+    };
+
+    // unauthorized check
+    let info = mock_info(OPERATOR_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    deps.querier.set_oracle_price(
+        "token2ibc_oracle".to_string(),
+        AssetInfo::NativeToken {
+            denom: IBC_TOKEN.to_string(),
+        },
+        Decimal::percent(25u64),
+    );
+
+    let msg = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_2),
+            },
+            limit: Some(Uint128::from(1500000u128)),
+        }],
+        minimum_receive: None
+    };
+
+    // belief price is derived from the oracle price
+    let info = mock_info(OPERATOR_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN_2.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: "token2ibc".to_string(),
+                amount: Uint128::new(1500000u128),
+                msg: to_binary(&AstroportPairCw20HookMsg::Swap {
+                    ask_asset_info: None,
+                    belief_price: Some(Decimal::percent(400)),
+                    max_spread: Some(Decimal::percent(2)),
+                    to: None,
+                })?
+            })?,
+        })
+    );
+
+    // swap without price source cannot be built
+    let msg = ExecuteMsg::Collect {
+        assets: vec![AssetWithLimit {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked(TOKEN_1),
+            },
+            limit: None,
+        }],
+        minimum_receive: None
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "No price source for token_1-token_2");
+
+    // disable price guard
+    let info = mock_info(OWNER, &[]);
+    let msg = ExecuteMsg::UpdatePriceGuard { price_guard: None };
+    execute(deps.as_mut(), env, info, msg)?;
+    let config = CONFIG.load(deps.as_ref().storage)?;
+    assert_eq!(config.price_guard, None);
+
+    Ok(())
+}
+
 fn distribute_fees(
     deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
 ) -> Result<(), ContractError> {
@@ -5,6 +5,7 @@ use astroport::querier::{query_pair_info, simulate};
 use cosmwasm_std::{to_binary, Deps, StdResult, Uint128, WasmMsg, CosmosMsg, Addr, QuerierWrapper, Decimal};
 use baz::adapters::pair::Pair;
 use baz::fees_collector::ExecuteMsg;
+use baz::price_guard::query_swap_limits;
 
 /// The default bridge depth for a fee token
 pub const BRIDGES_INITIAL_DEPTH: u64 = 0;
@@ -24,11 +25,21 @@ pub fn try_build_swap_msg(
     to: AssetInfo,
     amount: Uint128,
 ) -> Result<CosmosMsg, ContractError> {
-    let pool = query_pair_info(querier, &config.factory_contract, &[from.clone(), to])?;
+    let pool = query_pair_info(querier, &config.factory_contract, &[from.clone(), to.clone()])?;
+    let offer_asset = Asset { info: from, amount };
+    let (belief_price, max_spread) = query_swap_limits(
+        querier,
+        config.price_guard.as_ref(),
+        &offer_asset,
+        &to,
+        None,
+        Decimal::percent(MAX_SPREAD),
+    )?;
     let msg = Pair(pool.contract_addr).swap_msg(
-        &Asset { info: from, amount },
-        Some(Decimal::MAX),
-        Some(Decimal::percent(MAX_SPREAD)),
+        &offer_asset,
+        None,
+        belief_price,
+        max_spread,
         None,
     )?;
     Ok(msg)
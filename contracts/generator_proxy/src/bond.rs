@@ -1,6 +1,6 @@
 use std::cmp;
 use std::collections::HashMap;
-use cosmwasm_std::{Addr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError, StdResult, Uint128};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, QuerierWrapper, Response, StdError, StdResult, Storage, Uint128};
 use astroport::asset::{Asset, token_asset};
 use astroport::querier::query_token_balance;
 use crate::error::ContractError;
@@ -8,8 +8,8 @@ use astroport::generator::{PendingTokenResponse, UserInfoV2};
 use astroport::restricted_vector::RestrictedVector;
 use baz::adapters::asset::AssetEx;
 use crate::astro_generator::GeneratorEx;
-use crate::model::{CallbackMsg, Config, PoolInfo, RewardInfo, UserInfo};
-use crate::state::{CONFIG, POOL_INFO, REWARD_INFO, USER_INFO};
+use crate::model::{BoostInfo, CallbackMsg, Config, PoolInfo, RewardInfo, UserInfo};
+use crate::state::{BOOST_INFO, CONFIG, POOL_CONFIG, POOL_INFO, REWARD_INFO, USER_INFO};
 
 pub fn execute_deposit(
     deps: DepsMut,
@@ -19,9 +19,15 @@ pub fn execute_deposit(
     amount: Uint128,
 ) -> Result<Response, ContractError> {
 
+    // only registered LP tokens and depositors are allowed
+    let config = CONFIG.load(deps.storage)?;
+    let pool_config = POOL_CONFIG.may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::DepositNotAllowed {})?;
+    let deposit_amount = config.generator.query_deposit(&deps.querier, &info.sender, &env.contract.address)?;
+    pool_config.check_deposit(&staker_addr, deposit_amount, amount)?;
+
     // reward cannot be claimed if there is no record
     let mut messages: Vec<CosmosMsg> = vec![];
-    let config = CONFIG.load(deps.storage)?;
     let astro_user_info = config.generator.query_user_info(&deps.querier, &info.sender, &env.contract.address)?;
     if let Some(astro_user_info) = astro_user_info {
         let (claim, prev_balances) = reconcile_claimed_by_others(
@@ -187,8 +193,9 @@ fn reconcile_claimed_by_others(
     let target_add_astro_amount = (astro_user_info.reward_user_index - pool_info.prev_reward_user_index) * astro_user_info.virtual_amount;
     let net_astro_amount = cmp::min(add_astro_amount, target_add_astro_amount);
     if !net_astro_amount.is_zero() {
-        reconcile_astro_reward(config, astro_user_info, &mut pool_info, &mut astro_reward, net_astro_amount)?;
+        let boost_info = reconcile_astro_reward(config, astro_user_info, &mut pool_info, &mut astro_reward, net_astro_amount)?;
         REWARD_INFO.save(deps.storage, &config.astro_token, &astro_reward)?;
+        save_boost_info(deps.storage, lp_token, &boost_info)?;
     }
 
     // track balances
@@ -230,24 +237,45 @@ fn reconcile_astro_reward(
     pool_info: &mut PoolInfo,
     astro_reward: &mut RewardInfo,
     net_astro_amount: Uint128,
-) -> StdResult<()> {
-
-    let based_astro = net_astro_amount.multiply_ratio(
-        astro_user_info.amount * Decimal::percent(40),
-        astro_user_info.virtual_amount,
+) -> StdResult<BoostInfo> {
+
+    let based_astro = cmp::min(
+        net_astro_amount.multiply_ratio(
+            astro_user_info.amount * config.base_reward_rate,
+            astro_user_info.virtual_amount,
+        ),
+        net_astro_amount,
     );
     let boosted_astro = net_astro_amount.checked_sub(based_astro)?;
     let fee = boosted_astro * config.boost_fee;
     let net_boosted_astro = boosted_astro - fee;
     let to_staker = net_boosted_astro * config.staker_rate;
-    let to_lp = net_boosted_astro - to_staker + based_astro;
+    let boosted_to_lp = net_boosted_astro - to_staker;
+    let to_lp = boosted_to_lp + based_astro;
     let astro_per_share = Decimal::from_ratio(to_lp, pool_info.total_bond_share);
     astro_reward.fee += fee;
     astro_reward.staker_income += to_staker;
     astro_reward.reconciled_amount += net_astro_amount;
     pool_info.reward_indexes.update(&config.astro_token, astro_per_share)?;
 
-    Ok(())
+    Ok(BoostInfo {
+        base_astro: based_astro,
+        boosted_astro,
+        fee,
+        staker_income: to_staker,
+        lp_reward: boosted_to_lp,
+    })
+}
+
+fn save_boost_info(
+    storage: &mut dyn Storage,
+    lp_token: &Addr,
+    boost_info: &BoostInfo,
+) -> StdResult<()> {
+    let mut total_boost_info = BOOST_INFO.may_load(storage, lp_token)?
+        .unwrap_or_default();
+    total_boost_info.add(boost_info);
+    BOOST_INFO.save(storage, lp_token, &total_boost_info)
 }
 
 fn reconcile_token_reward(
@@ -285,7 +313,7 @@ pub fn callback_after_bond_claimed(
     if let Some(prev_astro_amount) = prev_balance_map.get(&config.astro_token) {
         let net_astro_amount = astro_amount.checked_sub(*prev_astro_amount)?;
         if !net_astro_amount.is_zero() {
-            reconcile_astro_reward(
+            let boost_info = reconcile_astro_reward(
                 &config,
                 &astro_user_info,
                 &mut pool_info,
@@ -293,6 +321,7 @@ pub fn callback_after_bond_claimed(
                 net_astro_amount,
             )?;
             REWARD_INFO.save(deps.storage, &config.astro_token, &astro_reward)?;
+            save_boost_info(deps.storage, &lp_token, &boost_info)?;
         }
     }
 
@@ -1,15 +1,17 @@
 use astroport::common::{propose_new_owner, drop_ownership_proposal, claim_ownership};
-use cosmwasm_std::{entry_point, DepsMut, Env, MessageInfo, Response, from_binary, Deps, Binary, to_binary, Empty, StdError, Uint128, Decimal};
-use cw20::Cw20ReceiveMsg;
+use cosmwasm_std::{entry_point, Addr, DepsMut, Order, StdResult, Env, MessageInfo, Response, from_binary, Deps, Binary, to_binary, Empty, StdError, Uint128, Decimal};
+use cw20::{Cw20ReceiveMsg, MarketingInfoResponse, MinterResponse};
 use astroport_governance::utils::get_period;
 use baz::adapters::generator::Generator;
+use crate::cw20::{execute_decrease_allowance, execute_increase_allowance, execute_send, execute_send_from, execute_transfer, execute_transfer_from, query_all_accounts, query_all_allowances, query_allowance, query_balance, query_token_info};
 use crate::bond::{callback_after_bond_changed, callback_after_bond_claimed, callback_claim_rewards, callback_deposit, callback_withdraw, execute_deposit, execute_withdraw, query_deposit, query_pending_token, execute_claim_rewards};
-use crate::oper::{execute_controller_vote, execute_send_income, execute_update_config, execute_update_parameters, query_config, validate_percentage};
+use crate::oper::{execute_controller_vote, execute_send_income, execute_update_config, execute_update_parameters, execute_update_pools, query_config, validate_percentage};
 use crate::error::ContractError;
-use crate::model::{CallbackMsg, Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, StakingState};
-use crate::query::{query_pool_info, query_reward_info, query_staker_info, query_staking_state, query_user_info};
+use crate::model::{CallbackMsg, Config, Cw20HookMsg, default_base_reward_rate, ExecuteMsg, InstantiateMsg, PoolConfig, QueryMsg, StakingState};
+use crate::query::{query_boost_info, query_pool_info, query_pools, query_unstake_requests, query_reward_info, query_staker_info, query_staking_state, query_user_info};
 use crate::staking::{callback_after_staking_claimed, execute_claim_income, execute_relock, execute_request_unstake, execute_stake, execute_withdraw_unstaked};
-use crate::state::{CONFIG, OWNERSHIP_PROPOSAL, STAKING_STATE};
+use crate::vote::{execute_submit_votes, execute_vote, query_pool_votes, query_user_votes};
+use crate::state::{CONFIG, OWNERSHIP_PROPOSAL, POOL_CONFIG, POOL_INFO, STAKING_STATE, USER_INFO};
 
 /// ## Description
 /// Creates a new contract with the bazified parameters in the [`InstantiateMsg`].
@@ -24,6 +26,8 @@ pub fn instantiate(
 
     validate_percentage(msg.staker_rate, "staker_rate")?;
     validate_percentage(msg.boost_fee, "boost_fee")?;
+    let base_reward_rate = msg.base_reward_rate.unwrap_or_else(default_base_reward_rate);
+    validate_percentage(base_reward_rate, "base_reward_rate")?;
 
     let config = Config {
         generator: Generator(deps.api.addr_validate(&msg.generator)?),
@@ -35,6 +39,9 @@ pub fn instantiate(
         max_quota: msg.max_quota,
         staker_rate: msg.staker_rate,
         boost_fee: msg.boost_fee,
+        base_reward_rate,
+        name: msg.name,
+        symbol: msg.symbol,
     };
     CONFIG.save(deps.storage, &config)?;
 
@@ -63,7 +70,11 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> R
         ExecuteMsg::UpdateConfig {
             controller,
             boost_fee,
-        } => execute_update_config(deps, env, info, controller, boost_fee),
+            base_reward_rate,
+            name,
+            symbol,
+        } => execute_update_config(deps, env, info, controller, boost_fee, base_reward_rate, name, symbol),
+        ExecuteMsg::UpdatePools { add, remove } => execute_update_pools(deps, env, info, add, remove),
         ExecuteMsg::UpdateParameters {
             max_quota,
             staker_rate,
@@ -111,6 +122,15 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> R
         ExecuteMsg::RequestUnstake { amount } => execute_request_unstake(deps, env, info, amount),
         ExecuteMsg::WithdrawUnstaked { amount } => execute_withdraw_unstaked(deps, env, info, amount),
         ExecuteMsg::ClaimIncome {} => execute_claim_income(deps, env, info),
+        ExecuteMsg::Vote { votes } => execute_vote(deps, env, info, votes),
+        ExecuteMsg::SubmitVotes {} => execute_submit_votes(deps, env, info),
+
+        ExecuteMsg::Transfer { recipient, amount } => execute_transfer(deps, env, info, recipient, amount),
+        ExecuteMsg::Send { contract, amount, msg } => execute_send(deps, env, info, contract, amount, msg),
+        ExecuteMsg::IncreaseAllowance { spender, amount, expires } => execute_increase_allowance(deps, env, info, spender, amount, expires),
+        ExecuteMsg::DecreaseAllowance { spender, amount, expires } => execute_decrease_allowance(deps, env, info, spender, amount, expires),
+        ExecuteMsg::TransferFrom { owner, recipient, amount } => execute_transfer_from(deps, env, info, owner, recipient, amount),
+        ExecuteMsg::SendFrom { owner, contract, amount, msg } => execute_send_from(deps, env, info, owner, contract, amount, msg),
     }
 }
 
@@ -164,8 +184,21 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         QueryMsg::PoolInfo { lp_token } => to_binary(&query_pool_info(deps, env, lp_token)?),
         QueryMsg::UserInfo { lp_token, user } => to_binary(&query_user_info(deps, env, lp_token, user)?),
         QueryMsg::RewardInfo { token } => to_binary(&query_reward_info(deps, env, token)?),
+        QueryMsg::Pools { start_after, limit } => to_binary(&query_pools(deps, env, start_after, limit)?),
+        QueryMsg::BoostInfo { lp_token } => to_binary(&query_boost_info(deps, env, lp_token)?),
         QueryMsg::StakingState { } => to_binary(&query_staking_state(deps, env)?),
         QueryMsg::StakerInfo { user } => to_binary(&query_staker_info(deps, env, user)?),
+        QueryMsg::UnstakeRequests { user } => to_binary(&query_unstake_requests(deps, env, user)?),
+        QueryMsg::UserVotes { user } => to_binary(&query_user_votes(deps, env, user)?),
+        QueryMsg::PoolVotes {} => to_binary(&query_pool_votes(deps, env)?),
+
+        QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
+        QueryMsg::TokenInfo { } => to_binary(&query_token_info(deps)?),
+        QueryMsg::Minter { } => to_binary::<Option<MinterResponse>>(&None),
+        QueryMsg::Allowance { owner, spender } => to_binary(&query_allowance(deps, owner, spender)?),
+        QueryMsg::AllAllowances { owner, start_after, limit } => to_binary(&query_all_allowances(deps, owner, start_after, limit)?),
+        QueryMsg::AllAccounts { start_after, limit } => to_binary(&query_all_accounts(deps, start_after, limit)?),
+        QueryMsg::MarketingInfo { } => to_binary(&MarketingInfoResponse::default()),
     }?;
     Ok(result)
 }
@@ -173,7 +206,24 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
 /// ## Description
 /// Used for contract migration. Returns a default object of type [`Response`].
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, _env: Env, _msg: Empty) -> Result<Response, ContractError> {
+
+    // allow the pools deposited before the allowlist to their existing depositors
+    let lp_tokens = POOL_INFO.keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for lp_token in lp_tokens {
+        if POOL_CONFIG.has(deps.storage, &lp_token) {
+            continue;
+        }
+        let depositors = USER_INFO.prefix(&lp_token)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<Addr>>>()?;
+        POOL_CONFIG.save(deps.storage, &lp_token, &PoolConfig {
+            depositors,
+            deposit_cap: None,
+        })?;
+    }
+
     Ok(Response::default())
 }
 
@@ -0,0 +1,331 @@
+use cosmwasm_std::{Addr, attr, Binary, BlockInfo, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128};
+use cw20::{AllAccountsResponse, AllAllowancesResponse, AllowanceInfo, AllowanceResponse, BalanceResponse, Cw20ReceiveMsg, Expiration, TokenInfoResponse};
+use cw_storage_plus::Bound;
+use crate::error::ContractError;
+use crate::model::StakerInfo;
+use crate::staking::{reconcile_staker_income, reconcile_to_staker_info};
+use crate::vote::update_vote_share;
+use crate::state::{ALLOWANCES, CONFIG, REWARD_INFO, STAKER_INFO, STAKING_STATE};
+
+fn transfer_internal(
+    deps: DepsMut,
+    _env: Env,
+    sender_addr: &Addr,
+    recipient: &str,
+    share: Uint128,
+) -> Result<(), ContractError> {
+
+    if share == Uint128::zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    // load
+    let rcpt_addr = deps.api.addr_validate(recipient)?;
+    let config = CONFIG.load(deps.storage)?;
+    let mut astro_reward = REWARD_INFO.load(deps.storage, &config.astro_token)?;
+    let mut state = STAKING_STATE.load(deps.storage)?;
+
+    // income is settled to both parties before the share moves
+    reconcile_staker_income(&mut astro_reward, &mut state)?;
+
+    let mut sender = STAKER_INFO.load(deps.storage, sender_addr)?;
+    reconcile_to_staker_info(&state, &mut sender)?;
+    sender.bond_share = sender.bond_share.checked_sub(share)?;
+    STAKER_INFO.save(deps.storage, sender_addr, &sender)?;
+    update_vote_share(deps.storage, sender_addr, sender.bond_share + share, sender.bond_share)?;
+
+    let mut receiver = STAKER_INFO.may_load(deps.storage, &rcpt_addr)?
+        .unwrap_or_else(|| StakerInfo::create(&state));
+    reconcile_to_staker_info(&state, &mut receiver)?;
+    receiver.bond_share += share;
+    STAKER_INFO.save(deps.storage, &rcpt_addr, &receiver)?;
+    update_vote_share(deps.storage, &rcpt_addr, receiver.bond_share - share, receiver.bond_share)?;
+
+    // save
+    STAKING_STATE.save(deps.storage, &state)?;
+    REWARD_INFO.save(deps.storage, &config.astro_token, &astro_reward)?;
+
+    Ok(())
+}
+
+pub fn execute_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+
+    transfer_internal(deps, env, &info.sender, &recipient, amount)?;
+
+    let res = Response::new()
+        .add_attribute("action", "transfer")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", recipient)
+        .add_attribute("amount", amount);
+    Ok(res)
+}
+
+pub fn execute_send(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+
+    transfer_internal(deps, env, &info.sender, &contract, amount)?;
+
+    let res = Response::new()
+        .add_attribute("action", "send")
+        .add_attribute("from", &info.sender)
+        .add_attribute("to", &contract)
+        .add_attribute("amount", amount)
+        .add_message(
+            Cw20ReceiveMsg {
+                sender: info.sender.into(),
+                amount,
+                msg,
+            }.into_cosmos_msg(contract)?,
+        );
+    Ok(res)
+}
+
+pub fn execute_increase_allowance(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    if spender_addr == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+
+    ALLOWANCES.update(
+        deps.storage,
+        (&info.sender, &spender_addr),
+        |allow| -> StdResult<_> {
+            let mut val = allow.unwrap_or_default();
+            if let Some(exp) = expires {
+                val.expires = exp;
+            }
+            val.allowance += amount;
+            Ok(val)
+        },
+    )?;
+
+    let res = Response::new().add_attributes(vec![
+        attr("action", "increase_allowance"),
+        attr("owner", info.sender),
+        attr("spender", spender),
+        attr("amount", amount),
+    ]);
+    Ok(res)
+}
+
+pub fn execute_decrease_allowance(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    if spender_addr == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+
+    let key = (&info.sender, &spender_addr);
+    // load value and delete if it hits 0, or update otherwise
+    let mut allowance = ALLOWANCES.load(deps.storage, key)?;
+    if amount < allowance.allowance {
+        // update the new amount
+        allowance.allowance = allowance
+            .allowance
+            .checked_sub(amount)
+            .map_err(StdError::overflow)?;
+        if let Some(exp) = expires {
+            allowance.expires = exp;
+        }
+        ALLOWANCES.save(deps.storage, key, &allowance)?;
+    } else {
+        ALLOWANCES.remove(deps.storage, key);
+    }
+
+    let res = Response::new().add_attributes(vec![
+        attr("action", "decrease_allowance"),
+        attr("owner", info.sender),
+        attr("spender", spender),
+        attr("amount", amount),
+    ]);
+    Ok(res)
+}
+
+// this can be used to update a lower allowance - call bucket.update with proper keys
+fn deduct_allowance(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+    block: &BlockInfo,
+    amount: Uint128,
+) -> Result<AllowanceResponse, ContractError> {
+    ALLOWANCES.update(storage, (owner, spender), |current| {
+        match current {
+            Some(mut a) => {
+                if a.expires.is_expired(block) {
+                    Err(ContractError::Expired {})
+                } else {
+                    // deduct the allowance if enough
+                    a.allowance = a
+                        .allowance
+                        .checked_sub(amount)
+                        .map_err(StdError::overflow)?;
+                    Ok(a)
+                }
+            }
+            None => Err(ContractError::NoAllowance {}),
+        }
+    })
+}
+
+pub fn execute_transfer_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+
+    // deduct allowance before doing anything else have enough allowance
+    deduct_allowance(deps.storage, &owner_addr, &info.sender, &env.block, amount)?;
+    transfer_internal(deps, env, &owner_addr, &recipient, amount)?;
+
+    let res = Response::new().add_attributes(vec![
+        attr("action", "transfer_from"),
+        attr("from", owner),
+        attr("to", recipient),
+        attr("by", info.sender),
+        attr("amount", amount),
+    ]);
+    Ok(res)
+}
+
+pub fn execute_send_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+
+    // deduct allowance before doing anything else have enough allowance
+    deduct_allowance(deps.storage, &owner_addr, &info.sender, &env.block, amount)?;
+    transfer_internal(deps, env, &owner_addr, &contract, amount)?;
+
+    let attrs = vec![
+        attr("action", "send_from"),
+        attr("from", &owner),
+        attr("to", &contract),
+        attr("by", &info.sender),
+        attr("amount", amount),
+    ];
+
+    // create a send message
+    let msg = Cw20ReceiveMsg {
+        sender: info.sender.into(),
+        amount,
+        msg,
+    }.into_cosmos_msg(contract)?;
+
+    let res = Response::new().add_message(msg).add_attributes(attrs);
+    Ok(res)
+}
+
+pub fn query_balance(
+    deps: Deps,
+    address: String
+) -> StdResult<BalanceResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let balance = STAKER_INFO
+        .may_load(deps.storage, &address)?
+        .map(|it| it.bond_share)
+        .unwrap_or_default();
+    Ok(BalanceResponse { balance })
+}
+
+pub fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let state = STAKING_STATE.load(deps.storage)?;
+    let res = TokenInfoResponse {
+        name: config.name,
+        symbol: config.symbol,
+        decimals: 6u8,
+        total_supply: state.total_bond_share,
+    };
+    Ok(res)
+}
+
+pub fn query_allowance(deps: Deps, owner: String, spender: String) -> StdResult<AllowanceResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    let allowance = ALLOWANCES
+        .may_load(deps.storage, (&owner_addr, &spender_addr))?
+        .unwrap_or_default();
+    Ok(allowance)
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+pub fn query_all_allowances(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllAllowancesResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    let allowances = ALLOWANCES
+        .prefix(&owner_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(addr, allow)| AllowanceInfo {
+                spender: addr.into(),
+                allowance: allow.allowance,
+                expires: allow.expires,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(AllAllowancesResponse { allowances })
+}
+
+pub fn query_all_accounts(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllAccountsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let accounts = STAKER_INFO
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(Into::into))
+        .collect::<StdResult<_>>()?;
+
+    Ok(AllAccountsResponse { accounts })
+}
@@ -16,9 +16,30 @@ pub enum ContractError {
     #[error("Callbacks cannot be invoked externally")]
     CallbackUnauthorized {},
 
+    #[error("Invalid zero amount")]
+    InvalidZeroAmount {},
+
+    #[error("Allowance is expired")]
+    Expired {},
+
+    #[error("No allowance for this account")]
+    NoAllowance {},
+
+    #[error("Cannot set to own account")]
+    CannotSetOwnAccount {},
+
     #[error("Exceed quota, remaining quota is {0}")]
     ExceedQuota(Uint128),
 
+    #[error("Deposit is not allowed")]
+    DepositNotAllowed {},
+
+    #[error("Exceed deposit cap, remaining cap is {0}")]
+    ExceedDepositCap(Uint128),
+
+    #[error("Votes are already submitted in this period")]
+    AlreadyVoted {},
+
     #[error("Cannot update {0} after set")]
     CannotUpdateAfterSet(String),
 }
@@ -8,6 +8,8 @@ pub mod bond;
 pub mod astro_generator;
 pub mod query;
 pub mod staking;
+pub mod cw20;
+pub mod vote;
 
 #[cfg(test)]
 mod mock_querier;
@@ -1,11 +1,12 @@
-use cosmwasm_std::{Addr, CosmosMsg, Decimal, StdResult, to_binary, Uint128, WasmMsg};
-use cw20::{Cw20ReceiveMsg};
+use cosmwasm_std::{Addr, Binary, CosmosMsg, Decimal, StdResult, to_binary, Uint128, WasmMsg};
+use cw20::{Cw20ReceiveMsg, Expiration};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use astroport::restricted_vector::RestrictedVector;
 use baz::adapters::generator::Generator;
 use baz::helper::ScalingUint128;
 use crate::astro_gov::{AstroGov, AstroGovUnchecked};
+use crate::error::ContractError;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
@@ -18,6 +19,12 @@ pub struct InstantiateMsg {
     pub max_quota: Uint128,
     pub staker_rate: Decimal,
     pub boost_fee: Decimal,
+    /// The share of the LP amount which earns ASTRO without boost, default is 40%
+    pub base_reward_rate: Option<Decimal>,
+    /// The name of the staked xASTRO token
+    pub name: String,
+    /// The symbol of the staked xASTRO token
+    pub symbol: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -31,6 +38,17 @@ pub struct Config {
     pub max_quota: Uint128,
     pub staker_rate: Decimal,
     pub boost_fee: Decimal,
+    /// The share of the LP amount which earns ASTRO without boost
+    #[serde(default = "default_base_reward_rate")]
+    pub base_reward_rate: Decimal,
+
+    /// token info
+    #[serde(default)] pub name: String,
+    #[serde(default)] pub symbol: String,
+}
+
+pub fn default_base_reward_rate() -> Decimal {
+    Decimal::percent(40)
 }
 
 pub fn zero_address() -> Addr {
@@ -187,6 +205,54 @@ impl StakerInfo {
     }
 }
 
+/// This structure describes an unstake request waiting to be withdrawn
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnstakeRequest {
+    /// The xASTRO amount left to withdraw
+    pub amount: Uint128,
+    /// The unstaking period of the staking state when requested, the request is unstaked after it
+    pub batch_period: u64,
+    /// The period the lock was expected to end when requested
+    pub unlock_period: u64,
+}
+
+impl UnstakeRequest {
+    pub fn is_unstaked(&self, state: &StakingState) -> bool {
+        state.unstaking_period > self.batch_period
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnstakeRequestResponse {
+    pub amount: Uint128,
+    pub unlock_period: u64,
+    /// The estimated time the request can be withdrawn
+    pub claim_time: u64,
+    pub claimable: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnstakeRequestsResponse {
+    pub requests: Vec<UnstakeRequestResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserVotesResponse {
+    pub bond_share: Uint128,
+    /// The list of (LP token, bps, weight)
+    pub votes: Vec<(Addr, u16, Uint128)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolVotesResponse {
+    /// The aggregated weight per LP token
+    pub weights: Vec<(Addr, Uint128)>,
+    /// The votes in bps to be submitted to the generator controller
+    pub votes: Vec<(String, u16)>,
+    /// The last period the proxy voted, either by stakers or by the controller
+    pub last_vote_period: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct StakerInfoResponse {
     pub bond_share: Uint128,
@@ -198,6 +264,71 @@ pub struct StakerInfoResponse {
     pub unstaking_period: u64,
 }
 
+/// This structure describes an LP token allowed to be deposited and who can deposit it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolItem {
+    pub lp_token: String,
+    /// The contracts allowed to deposit the LP token
+    pub depositors: Vec<String>,
+    /// The maximum amount of the LP token deposited through the proxy
+    pub deposit_cap: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolConfig {
+    pub depositors: Vec<Addr>,
+    pub deposit_cap: Option<Uint128>,
+}
+
+impl PoolConfig {
+    pub fn check_deposit(&self, depositor: &Addr, deposit_amount: Uint128, amount: Uint128) -> Result<(), ContractError> {
+        if !self.depositors.contains(depositor) {
+            return Err(ContractError::DepositNotAllowed {});
+        }
+        if let Some(deposit_cap) = self.deposit_cap {
+            if deposit_amount + amount > deposit_cap {
+                return Err(ContractError::ExceedDepositCap(deposit_cap.saturating_sub(deposit_amount)));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolResponse {
+    pub lp_token: Addr,
+    pub depositors: Vec<Addr>,
+    pub deposit_cap: Option<Uint128>,
+    /// The amount of the LP token currently deposited through the proxy
+    pub deposit_amount: Uint128,
+}
+
+/// This structure describes the accumulated ASTRO of a pool and how its boost was split
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct BoostInfo {
+    /// The ASTRO the LP amount earns without boost
+    pub base_astro: Uint128,
+    /// The ASTRO earned from the boost of the proxy's vxASTRO
+    pub boosted_astro: Uint128,
+    /// The part of the boosted ASTRO sent to the fee collector
+    pub fee: Uint128,
+    /// The part of the boosted ASTRO distributed to stakers
+    pub staker_income: Uint128,
+    /// The part of the boosted ASTRO distributed to LP providers
+    pub lp_reward: Uint128,
+}
+
+impl BoostInfo {
+    pub fn add(&mut self, other: &BoostInfo) {
+        self.base_astro += other.base_astro;
+        self.boosted_astro += other.boosted_astro;
+        self.fee += other.fee;
+        self.staker_income += other.staker_income;
+        self.lp_reward += other.lp_reward;
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 pub struct RewardInfo {
     pub reconciled_amount: Uint128,
@@ -215,6 +346,16 @@ pub enum ExecuteMsg {
     UpdateConfig {
         controller: Option<String>,
         boost_fee: Option<Decimal>,
+        base_reward_rate: Option<Decimal>,
+        /// The name of the staked xASTRO token
+        name: Option<String>,
+        /// The symbol of the staked xASTRO token
+        symbol: Option<String>,
+    },
+    /// Adds or removes LP tokens allowed to be deposited
+    UpdatePools {
+        add: Option<Vec<PoolItem>>,
+        remove: Option<Vec<String>>,
     },
 
     // controller's actions
@@ -263,6 +404,55 @@ pub enum ExecuteMsg {
         amount: Option<Uint128>,
     },
     ClaimIncome {},
+    /// Votes the weights of the registered pools in bps with the staker's bond share
+    Vote {
+        votes: Vec<(String, u16)>,
+    },
+    /// Submits the aggregated votes of stakers to the generator controller, once per period
+    /// after the first day, which is reserved for the controller to vote instead
+    SubmitVotes {},
+
+    /// cw20 of the staked xASTRO shares, minted on stake and burned on unstake
+    /// Transfer is a base message to move tokens to another account without triggering actions
+    Transfer { recipient: String, amount: Uint128 },
+    /// Send is a base message to transfer tokens to a contract and trigger an action
+    /// on the receiving contract.
+    Send {
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Only with "approval" extension. Allows spender to access an additional amount tokens
+    /// from the owner's (env.sender) account. If expires is Some(), overwrites current allowance
+    /// expiration with this one.
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Only with "approval" extension. Lowers the spender's access of tokens
+    /// from the owner's (env.sender) account by amount. If expires is Some(), overwrites current
+    /// allowance expiration with this one.
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Only with "approval" extension. Transfers amount tokens from owner -> recipient
+    /// if `env.sender` has sufficient pre-approval.
+    TransferFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Only with "approval" extension. Sends amount tokens from owner -> contract
+    /// if `env.sender` has sufficient pre-approval.
+    SendFrom {
+        owner: String,
+        contract: String,
+        amount: Uint128,
+        msg: Binary,
+    },
 }
 
 impl ExecuteMsg {
@@ -339,6 +529,15 @@ pub enum QueryMsg {
     RewardInfo {
         token: String,
     },
+    /// Returns the LP tokens allowed to be deposited and their deposit amount
+    Pools {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the accumulated ASTRO of a pool and how its boost was split
+    BoostInfo {
+        lp_token: String,
+    },
 
     // from generator
     PendingToken { lp_token: String, user: String },
@@ -347,4 +546,46 @@ pub enum QueryMsg {
     // staker
     StakingState {},
     StakerInfo { user: String },
+    /// Returns the unstake requests of a staker with their estimated claim time
+    UnstakeRequests { user: String },
+    /// Returns the votes of a staker
+    UserVotes { user: String },
+    /// Returns the aggregated votes of stakers
+    PoolVotes {},
+
+    /// cw20
+    /// Returns the current balance of the given address, 0 if unset.
+    /// Return type: BalanceResponse.
+    Balance { address: String },
+    /// Returns metadata on the contract - name, decimals, supply, etc.
+    /// Return type: TokenInfoResponse.
+    TokenInfo {},
+    /// Only with "mintable" extension.
+    /// Returns who can mint and the hard cap on maximum tokens after minting.
+    /// Return type: MinterResponse.
+    Minter {},
+    /// Only with "allowance" extension.
+    /// Returns how much spender can use from owner account, 0 if unset.
+    /// Return type: AllowanceResponse.
+    Allowance { owner: String, spender: String },
+    /// Only with "enumerable" extension (and "allowances")
+    /// Returns all allowances this owner has approved. Supports pagination.
+    /// Return type: AllAllowancesResponse.
+    AllAllowances {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Only with "enumerable" extension
+    /// Returns all accounts that have balances. Supports pagination.
+    /// Return type: AllAccountsResponse.
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Only with "marketing" extension
+    /// Returns more metadata on the contract to display in the client,
+    /// such as description, logo, project url, etc.
+    /// Return type: MarketingInfoResponse
+    MarketingInfo {},
 }
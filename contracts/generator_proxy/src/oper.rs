@@ -1,9 +1,10 @@
 use cosmwasm_std::{CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128};
 use astroport::asset::{token_asset};
+use astroport_governance::utils::get_period;
 use baz::adapters::asset::AssetEx;
 use crate::error::ContractError;
-use crate::model::{Config};
-use crate::state::{CONFIG, REWARD_INFO};
+use crate::model::{Config, PoolConfig, PoolItem};
+use crate::state::{CONFIG, LAST_VOTE_PERIOD, POOL_CONFIG, REWARD_INFO};
 
 pub fn validate_percentage(value: Decimal, field: &str) -> StdResult<()> {
     if value > Decimal::one() {
@@ -12,12 +13,16 @@ pub fn validate_percentage(value: Decimal, field: &str) -> StdResult<()> {
         Ok(())
     }
 }
+#[allow(clippy::too_many_arguments)]
 pub fn execute_update_config(
     deps: DepsMut,
     _env: Env,
     info: MessageInfo,
     controller: Option<String>,
     boost_fee: Option<Decimal>,
+    base_reward_rate: Option<Decimal>,
+    name: Option<String>,
+    symbol: Option<String>,
 ) -> Result<Response, ContractError> {
 
     // only owner can update
@@ -35,11 +40,63 @@ pub fn execute_update_config(
         config.boost_fee = boost_fee;
     }
 
+    if let Some(base_reward_rate) = base_reward_rate {
+        validate_percentage(base_reward_rate, "base_reward_rate")?;
+        config.base_reward_rate = base_reward_rate;
+    }
+
+    if let Some(name) = name {
+        config.name = name;
+    }
+
+    if let Some(symbol) = symbol {
+        config.symbol = symbol;
+    }
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::default())
 }
 
+pub fn execute_update_pools(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    add: Option<Vec<PoolItem>>,
+    remove: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+
+    // only owner can update
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(remove) = remove {
+        for lp_token in remove {
+            let lp_token = deps.api.addr_validate(&lp_token)?;
+            POOL_CONFIG.remove(deps.storage, &lp_token);
+        }
+    }
+
+    if let Some(add) = add {
+        for pool in add {
+            let lp_token = deps.api.addr_validate(&pool.lp_token)?;
+            let depositors = pool.depositors.iter()
+                .map(|depositor| deps.api.addr_validate(depositor))
+                .collect::<StdResult<Vec<_>>>()?;
+            POOL_CONFIG.save(deps.storage, &lp_token, &PoolConfig {
+                depositors,
+                deposit_cap: pool.deposit_cap,
+            })?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_pools")
+    )
+}
+
 pub fn execute_update_parameters(
     deps: DepsMut,
     _env: Env,
@@ -70,17 +127,20 @@ pub fn execute_update_parameters(
 
 pub fn execute_controller_vote(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     votes: Vec<(String, u16)>,
 ) -> Result<Response, ContractError> {
 
-    // only controller can vote
+    // only controller can vote, this overrides stakers' votes of the period
     let config = CONFIG.load(deps.storage)?;
     if info.sender != config.controller {
         return Err(ContractError::Unauthorized {});
     }
 
+    let period = get_period(env.block.time.seconds())?;
+    LAST_VOTE_PERIOD.save(deps.storage, &period)?;
+
     let vote_msg = config.astro_gov.controller_vote_msg(votes)?;
 
     Ok(Response::new()
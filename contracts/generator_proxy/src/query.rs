@@ -1,8 +1,10 @@
-use cosmwasm_std::{Deps, Env, StdResult};
+use cosmwasm_std::{Deps, Env, Order, StdResult};
+use cw_storage_plus::Bound;
+use astroport_governance::utils::{EPOCH_START, get_period, WEEK};
 use crate::bond::reconcile_to_user_info;
-use crate::model::{PoolInfo, RewardInfo, StakerInfo, StakerInfoResponse, StakingState, UserInfo, UserInfoResponse};
+use crate::model::{BoostInfo, PoolInfo, PoolResponse, RewardInfo, StakerInfo, StakerInfoResponse, StakingState, UnstakeRequestResponse, UnstakeRequestsResponse, UserInfo, UserInfoResponse};
 use crate::staking::{reconcile_staker_income, reconcile_to_staker_info};
-use crate::state::{CONFIG, POOL_INFO, REWARD_INFO, STAKER_INFO, STAKING_STATE, USER_INFO};
+use crate::state::{BOOST_INFO, CONFIG, POOL_CONFIG, POOL_INFO, REWARD_INFO, STAKER_INFO, STAKING_STATE, UNSTAKE_REQUESTS, USER_INFO};
 
 pub fn query_pool_info(
     deps: Deps,
@@ -31,6 +33,15 @@ pub fn query_user_info(
     Ok(user_info.to_response(&pool_info, total_bond_amount))
 }
 
+pub fn query_boost_info(
+    deps: Deps,
+    _env: Env,
+    lp_token: String,
+) -> StdResult<BoostInfo> {
+    let lp_token = deps.api.addr_validate(&lp_token)?;
+    Ok(BOOST_INFO.may_load(deps.storage, &lp_token)?.unwrap_or_default())
+}
+
 pub fn query_reward_info(
     deps: Deps,
     _env: Env,
@@ -40,6 +51,36 @@ pub fn query_reward_info(
     REWARD_INFO.load(deps.storage, &token)
 }
 
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+pub fn query_pools(
+    deps: Deps,
+    env: Env,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<PoolResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into_bytes()));
+
+    let config = CONFIG.load(deps.storage)?;
+    POOL_CONFIG
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (lp_token, pool_config) = item?;
+            let deposit_amount = config.generator.query_deposit(&deps.querier, &lp_token, &env.contract.address)?;
+            Ok(PoolResponse {
+                lp_token,
+                depositors: pool_config.depositors,
+                deposit_cap: pool_config.deposit_cap,
+                deposit_amount,
+            })
+        })
+        .collect()
+}
+
 pub fn query_staking_state(
     deps: Deps,
     _env: Env,
@@ -65,3 +106,35 @@ pub fn query_staker_info(
     let lock = config.astro_gov.query_lock(&deps.querier, env.contract.address)?;
     Ok(staker_info.to_response(&state, lock.amount))
 }
+
+pub fn query_unstake_requests(
+    deps: Deps,
+    env: Env,
+    user: String,
+) -> StdResult<UnstakeRequestsResponse> {
+    let user = deps.api.addr_validate(&user)?;
+    let state = STAKING_STATE.load(deps.storage)?;
+    let requests = UNSTAKE_REQUESTS.may_load(deps.storage, &user)?
+        .unwrap_or_default();
+
+    // pending requests wait for the next relock, which cannot happen before the current period
+    let period = get_period(env.block.time.seconds())?;
+    Ok(UnstakeRequestsResponse {
+        requests: requests.into_iter()
+            .map(|request| {
+                let claimable = request.is_unstaked(&state);
+                let claim_period = if claimable {
+                    request.unlock_period
+                } else {
+                    request.unlock_period.max(period)
+                };
+                UnstakeRequestResponse {
+                    amount: request.amount,
+                    unlock_period: request.unlock_period,
+                    claim_time: EPOCH_START + claim_period * WEEK,
+                    claimable,
+                }
+            })
+            .collect(),
+    })
+}
@@ -5,8 +5,9 @@ use astroport::querier::query_token_balance;
 use astroport_governance::utils::{get_period, WEEK};
 use baz::adapters::asset::AssetEx;
 use crate::error::ContractError;
-use crate::model::{CallbackMsg, Config, ExecuteMsg, RewardInfo, StakerInfo, StakingState};
-use crate::state::{CONFIG, REWARD_INFO, STAKER_INFO, STAKING_STATE};
+use crate::model::{CallbackMsg, Config, ExecuteMsg, RewardInfo, StakerInfo, StakingState, UnstakeRequest};
+use crate::state::{CONFIG, REWARD_INFO, STAKER_INFO, STAKING_STATE, UNSTAKE_REQUESTS};
+use crate::vote::update_vote_share;
 
 pub fn execute_stake(
     deps: DepsMut,
@@ -51,8 +52,10 @@ pub fn execute_stake(
     reconcile_to_staker_info(&state, &mut staker_info)?;
 
     let share = state.calc_bond_share(lock.amount, amount, false);
+    let prev_share = staker_info.bond_share;
     staker_info.bond_share += share;
     state.total_bond_share += share;
+    update_vote_share(deps.storage, &staker_addr, prev_share, staker_info.bond_share)?;
 
     STAKER_INFO.save(deps.storage, &staker_addr, &staker_info)?;
     STAKING_STATE.save(deps.storage, &state)?;
@@ -208,14 +211,26 @@ pub fn execute_request_unstake(
     reconcile_staker_income(&mut astro_reward, &mut state)?;
     reconcile_to_staker_info(&state, &mut staker_info)?;
     staker_info.update_staking(&state);
-    let lock = config.astro_gov.query_lock(&deps.querier, env.contract.address)?;
+    let lock = config.astro_gov.query_lock(&deps.querier, env.contract.address.clone())?;
     let share = state.calc_bond_share(lock.amount, amount, true);
+    let prev_share = staker_info.bond_share;
     staker_info.bond_share = staker_info.bond_share.checked_sub(share)?;
+    update_vote_share(deps.storage, &info.sender, prev_share, staker_info.bond_share)?;
     staker_info.unstaking_amount += amount;
     state.total_bond_share = state.total_bond_share.checked_sub(share)?;
     state.total_unstaking_amount += amount;
 
+    // the request is unstaked on the next relock, which can happen once the lock ends
+    let mut requests = UNSTAKE_REQUESTS.may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    requests.push(UnstakeRequest {
+        amount,
+        batch_period: state.unstaking_period,
+        unlock_period: cmp::max(lock.end, get_period(env.block.time.seconds())?),
+    });
+
     // save
+    UNSTAKE_REQUESTS.save(deps.storage, &info.sender, &requests)?;
     STAKER_INFO.save(deps.storage, &info.sender, &staker_info)?;
     STAKING_STATE.save(deps.storage, &state)?;
     REWARD_INFO.save(deps.storage, &config.astro_token, &astro_reward)?;
@@ -240,10 +255,31 @@ pub fn execute_withdraw_unstaked(
     // update
     staker_info.update_staking(&state);
     let amount = amount.unwrap_or(staker_info.unstaked_amount);
+    let prev_unstaked_amount = staker_info.unstaked_amount;
     staker_info.unstaked_amount = staker_info.unstaked_amount.checked_sub(amount)?;
     state.total_unstaked_amount = state.total_unstaked_amount.checked_sub(amount)?;
 
+    // withdraw from the oldest unstaked requests, after the amount unstaked before requests were tracked
+    let mut requests = UNSTAKE_REQUESTS.may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    let tracked_amount: Uint128 = requests.iter()
+        .filter(|it| it.is_unstaked(&state))
+        .map(|it| it.amount)
+        .sum();
+    let mut deduct_amount = amount.saturating_sub(prev_unstaked_amount.saturating_sub(tracked_amount));
+    for request in requests.iter_mut().filter(|it| it.is_unstaked(&state)) {
+        let deduct = cmp::min(request.amount, deduct_amount);
+        request.amount -= deduct;
+        deduct_amount -= deduct;
+    }
+    requests.retain(|it| !it.amount.is_zero());
+
     // save
+    if requests.is_empty() {
+        UNSTAKE_REQUESTS.remove(deps.storage, &info.sender);
+    } else {
+        UNSTAKE_REQUESTS.save(deps.storage, &info.sender, &requests)?;
+    }
     STAKER_INFO.save(deps.storage, &info.sender, &staker_info)?;
     STAKING_STATE.save(deps.storage, &state)?;
 
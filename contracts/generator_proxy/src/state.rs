@@ -1,14 +1,21 @@
 use astroport::common::OwnershipProposal;
-use cosmwasm_std::{Addr};
+use cosmwasm_std::{Addr, Uint128};
+use cw20::AllowanceResponse;
 use cw_storage_plus::{Item, Map};
-use crate::model::{Config, PoolInfo, RewardInfo, StakerInfo, StakingState, UserInfo};
+use crate::model::{BoostInfo, Config, PoolConfig, PoolInfo, RewardInfo, StakerInfo, StakingState, UnstakeRequest, UserInfo};
 
 /// Stores the contract config
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Stores the LP tokens allowed to be deposited, key = LP token
+pub const POOL_CONFIG: Map<&Addr, PoolConfig> = Map::new("pool_config");
+
 /// Stores pool info per LP token, key = LP token
 pub const POOL_INFO: Map<&Addr, PoolInfo> = Map::new("pool_info");
 
+/// Stores the accumulated ASTRO split per LP token, key = LP token
+pub const BOOST_INFO: Map<&Addr, BoostInfo> = Map::new("boost_info");
+
 /// Stores user info per user per LP token, key = LP token, User
 pub const USER_INFO: Map<(&Addr, &Addr), UserInfo> = Map::new("user_info");
 
@@ -21,5 +28,20 @@ pub const STAKING_STATE: Item<StakingState> = Item::new("staking_state");
 /// Stores staker info per user, key = User
 pub const STAKER_INFO: Map<&Addr, StakerInfo> = Map::new("staker_info");
 
+/// Stores the unstake requests per staker, oldest first, key = User
+pub const UNSTAKE_REQUESTS: Map<&Addr, Vec<UnstakeRequest>> = Map::new("unstake_requests");
+
+/// Stores the votes in bps per staker, key = User
+pub const USER_VOTES: Map<&Addr, Vec<(Addr, u16)>> = Map::new("user_votes");
+
+/// Stores the aggregated vote weight of stakers per LP token, key = LP token
+pub const POOL_VOTES: Map<&Addr, Uint128> = Map::new("pool_votes");
+
+/// Stores the last period the votes were submitted to the generator controller
+pub const LAST_VOTE_PERIOD: Item<u64> = Item::new("last_vote_period");
+
 /// Stores the latest proposal to change contract ownership
 pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// Stores the allowances of the staked xASTRO token, key = Owner, Spender
+pub const ALLOWANCES: Map<(&Addr, &Addr), AllowanceResponse> = Map::new("allowance");
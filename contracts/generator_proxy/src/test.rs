@@ -1,18 +1,20 @@
-use cosmwasm_std::{Addr, CosmosMsg, Decimal, from_binary, OwnedDeps, Response, StdError, Timestamp, to_binary, Uint128, WasmMsg};
+use cosmwasm_std::{Addr, CosmosMsg, Decimal, Empty, from_binary, OwnedDeps, Response, StdError, Timestamp, to_binary, Uint128, WasmMsg};
 use cosmwasm_std::testing::{MOCK_CONTRACT_ADDR, mock_env, mock_info, MockApi, MockStorage};
-use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw20::{BalanceResponse, Cw20ExecuteMsg, Cw20ReceiveMsg, TokenInfoResponse};
 use astroport::asset::{token_asset};
 use astroport::generator::{ExecuteMsg as GeneratorExecuteMsg, Cw20HookMsg as GeneratorCw20HookMsg, UserInfoV2, PendingTokenResponse};
 use astroport_governance::utils::{EPOCH_START, WEEK};
 use astroport_governance::voting_escrow::{Cw20HookMsg as VotingCw20HookMsg, ExecuteMsg as VotingExecuteMsg};
 use astroport_governance::escrow_fee_distributor::{ExecuteMsg as FeeExecuteMsg};
+use astroport_governance::generator_controller::{ExecuteMsg as ControllerExecuteMsg};
 use astroport::restricted_vector::RestrictedVector;
 use baz::adapters::generator::Generator;
 use crate::astro_gov::{AstroGov, AstroGovUnchecked, Lock};
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query};
 use crate::error::ContractError;
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
-use crate::model::{CallbackMsg, Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolInfo, QueryMsg, RewardInfo, StakerInfoResponse, StakingState, UserInfoResponse};
+use crate::model::{BoostInfo, CallbackMsg, Config, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolInfo, PoolItem, PoolResponse, PoolVotesResponse, QueryMsg, RewardInfo, StakerInfoResponse, StakingState, UnstakeRequestResponse, UnstakeRequestsResponse, UserInfo, UserInfoResponse, UserVotesResponse};
+use crate::state::{CONFIG, POOL_INFO, USER_INFO};
 
 const ASTRO_TOKEN: &str = "astro";
 const REWARD_TOKEN: &str = "reward";
@@ -24,6 +26,7 @@ const GENERATOR: &str = "generator";
 const CONTROLLER: &str = "controller";
 const FEE_COLLECTOR: &str = "fee_collector";
 const LP_TOKEN: &str = "lp_token";
+const DAY: u64 = 86400;
 
 const XASTRO_TOKEN: &str = "xastro";
 const VOTING_ESCROW: &str = "voting_escrow";
@@ -35,12 +38,15 @@ fn test() -> Result<(), ContractError> {
     let mut deps = mock_dependencies();
     create(&mut deps)?;
     config(&mut deps)?;
+    pools(&mut deps)?;
 
     deposit(&mut deps)?;
     claim_rewards(&mut deps)?;
     withdraw(&mut deps)?;
 
     stake(&mut deps)?;
+    transfer(&mut deps)?;
+    vote(&mut deps)?;
     unstake(&mut deps)?;
     claim_income(&mut deps)?;
 
@@ -49,6 +55,59 @@ fn test() -> Result<(), ContractError> {
     Ok(())
 }
 
+#[test]
+fn test_migrate() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    create(&mut deps)?;
+
+    // pool deposited before the allowlist
+    let lp_token = Addr::unchecked(LP_TOKEN);
+    let pool_info = PoolInfo::default();
+    POOL_INFO.save(deps.as_mut().storage, &lp_token, &pool_info)?;
+    for user in [USER1, USER2] {
+        USER_INFO.save(deps.as_mut().storage, (&lp_token, &Addr::unchecked(user)), &UserInfo::create(&pool_info))?;
+    }
+
+    migrate(deps.as_mut(), mock_env(), Empty {})?;
+
+    let msg = QueryMsg::Pools {
+        start_after: None,
+        limit: None,
+    };
+    let res: Vec<PoolResponse> = from_binary(&query(deps.as_ref(), mock_env(), msg)?)?;
+    assert_eq!(res, vec![
+        PoolResponse {
+            lp_token: Addr::unchecked(LP_TOKEN),
+            depositors: vec![Addr::unchecked(USER1), Addr::unchecked(USER2)],
+            deposit_cap: None,
+            deposit_amount: Uint128::zero(),
+        },
+    ]);
+
+    // token info is empty on contracts created before it
+    let mut config = CONFIG.load(deps.as_ref().storage)?;
+    config.name = String::new();
+    config.symbol = String::new();
+    CONFIG.save(deps.as_mut().storage, &config)?;
+
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::UpdateConfig {
+        controller: None,
+        boost_fee: None,
+        base_reward_rate: None,
+        name: Some("Staked xASTRO".to_string()),
+        symbol: Some("sxASTRO".to_string()),
+    };
+    execute(deps.as_mut(), mock_env(), info, msg)?;
+
+    let msg = QueryMsg::TokenInfo {};
+    let res: TokenInfoResponse = from_binary(&query(deps.as_ref(), mock_env(), msg)?)?;
+    assert_eq!(res.name, "Staked xASTRO");
+    assert_eq!(res.symbol, "sxASTRO");
+
+    Ok(())
+}
+
 fn assert_error(res: Result<Response, ContractError>, expected: &str) {
     match res {
         Err(ContractError::Std(StdError::GenericErr {
@@ -80,6 +139,9 @@ fn create(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result
         staker_rate: Decimal::percent(160),
         max_quota: Uint128::from(1000u128),
         boost_fee: Decimal::percent(20),
+        base_reward_rate: None,
+        name: "Staked xASTRO".to_string(),
+        symbol: "sxASTRO".to_string(),
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
     assert_error(res, "staker_rate cannot greater than 1");
@@ -99,6 +161,9 @@ fn create(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result
         staker_rate: Decimal::percent(50),
         max_quota: Uint128::from(1000u128),
         boost_fee: Decimal::percent(10),
+        base_reward_rate: None,
+        name: "Staked xASTRO".to_string(),
+        symbol: "sxASTRO".to_string(),
     };
     let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg);
     assert!(res.is_ok());
@@ -114,6 +179,9 @@ fn config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result
     let msg = ExecuteMsg::UpdateConfig {
         controller: None,
         boost_fee: Some(Decimal::percent(120)),
+        base_reward_rate: None,
+        name: None,
+        symbol: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert_error(res, "Unauthorized");
@@ -125,6 +193,19 @@ fn config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result
     let msg = ExecuteMsg::UpdateConfig {
         controller: None,
         boost_fee: Some(Decimal::percent(20)),
+        base_reward_rate: Some(Decimal::percent(140)),
+        name: None,
+        symbol: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "base_reward_rate cannot greater than 1");
+
+    let msg = ExecuteMsg::UpdateConfig {
+        controller: None,
+        boost_fee: Some(Decimal::percent(20)),
+        base_reward_rate: Some(Decimal::percent(40)),
+        name: None,
+        symbol: None,
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
     assert!(res.is_ok());
@@ -164,11 +245,100 @@ fn config(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result
         staker_rate: Decimal::percent(60),
         max_quota: Uint128::from(1000u128),
         boost_fee: Decimal::percent(20),
+        base_reward_rate: Decimal::percent(40),
+        name: "Staked xASTRO".to_string(),
+        symbol: "sxASTRO".to_string(),
     });
 
     Ok(())
 }
 
+fn pools(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(EPOCH_START);
+
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Deposit {})?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Deposit is not allowed");
+
+    let msg = ExecuteMsg::UpdatePools {
+        add: Some(vec![
+            PoolItem {
+                lp_token: LP_TOKEN.to_string(),
+                depositors: vec![USER1.to_string(), USER2.to_string()],
+                deposit_cap: Some(Uint128::from(1000u128)),
+            },
+            PoolItem {
+                lp_token: REWARD_TOKEN.to_string(),
+                depositors: vec![USER1.to_string()],
+                deposit_cap: None,
+            },
+        ]),
+        remove: None,
+    };
+    let info = mock_info(USER2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(USER1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    let msg = ExecuteMsg::UpdatePools {
+        add: None,
+        remove: Some(vec![REWARD_TOKEN.to_string()]),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(LP_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER3.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Deposit {})?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Deposit is not allowed");
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER1.to_string(),
+        amount: Uint128::from(1001u128),
+        msg: to_binary(&Cw20HookMsg::Deposit {})?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Exceed deposit cap, remaining cap is 1000");
+
+    let info = mock_info(REWARD_TOKEN, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER1.to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Deposit {})?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Deposit is not allowed");
+
+    let msg = QueryMsg::Pools {
+        start_after: None,
+        limit: None,
+    };
+    let res: Vec<PoolResponse> = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, vec![
+        PoolResponse {
+            lp_token: Addr::unchecked(LP_TOKEN),
+            depositors: vec![Addr::unchecked(USER1), Addr::unchecked(USER2)],
+            deposit_cap: Some(Uint128::from(1000u128)),
+            deposit_amount: Uint128::zero(),
+        },
+    ]);
+
+    Ok(())
+}
+
 fn deposit(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
     let mut env = mock_env();
     env.block.time = Timestamp::from_seconds(EPOCH_START);
@@ -368,6 +538,18 @@ fn deposit(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resul
         reconciled_amount: Uint128::from(10u128),
     });
 
+    let msg = QueryMsg::BoostInfo {
+        lp_token: LP_TOKEN.to_string(),
+    };
+    let res: BoostInfo = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, BoostInfo {
+        base_astro: Uint128::from(5u128),
+        boosted_astro: Uint128::from(5u128),
+        fee: Uint128::from(1u128),
+        staker_income: Uint128::from(2u128),
+        lp_reward: Uint128::from(2u128),
+    });
+
     let msg = QueryMsg::RewardInfo {
         token: REWARD_TOKEN.to_string(),
     };
@@ -452,6 +634,13 @@ fn deposit(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resul
         last_reconcile: 12345,
     });
 
+    let msg = QueryMsg::Pools {
+        start_after: None,
+        limit: None,
+    };
+    let res: Vec<PoolResponse> = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res[0].deposit_amount, Uint128::from(160u128));
+
     Ok(())
 }
 
@@ -936,6 +1125,213 @@ fn stake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<
     Ok(())
 }
 
+fn transfer(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(EPOCH_START);
+
+    let msg = QueryMsg::TokenInfo {};
+    let res: TokenInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, TokenInfoResponse {
+        name: "Staked xASTRO".to_string(),
+        symbol: "sxASTRO".to_string(),
+        decimals: 6u8,
+        total_supply: Uint128::from(900u128),
+    });
+
+    let info = mock_info(USER2, &[]);
+    let msg = ExecuteMsg::Transfer {
+        recipient: USER3.to_string(),
+        amount: Uint128::zero(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Invalid zero amount");
+
+    let msg = ExecuteMsg::Transfer {
+        recipient: USER3.to_string(),
+        amount: Uint128::from(40u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::Balance {
+        address: USER2.to_string(),
+    };
+    let res: BalanceResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.balance, Uint128::from(60u128));
+
+    let msg = QueryMsg::StakerInfo {
+        user: USER3.to_string(),
+    };
+    let res: StakerInfoResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, StakerInfoResponse {
+        bond_share: Uint128::from(40u128),
+        bond_amount: Uint128::from(40u128),
+        reward_index: Decimal::from_ratio(1375u128, 100000u128),
+        pending_reward: Uint128::zero(),
+        unstaking_amount: Uint128::zero(),
+        unstaked_amount: Uint128::zero(),
+        unstaking_period: 0
+    });
+
+    let msg = ExecuteMsg::TransferFrom {
+        owner: USER3.to_string(),
+        recipient: USER2.to_string(),
+        amount: Uint128::from(40u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "No allowance for this account");
+
+    let info = mock_info(USER3, &[]);
+    let allowance_msg = ExecuteMsg::IncreaseAllowance {
+        spender: USER2.to_string(),
+        amount: Uint128::from(40u128),
+        expires: None,
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), allowance_msg);
+    assert!(res.is_ok());
+
+    let info = mock_info(USER2, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::Balance {
+        address: USER2.to_string(),
+    };
+    let res: BalanceResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.balance, Uint128::from(100u128));
+
+    let msg = QueryMsg::Balance {
+        address: USER3.to_string(),
+    };
+    let res: BalanceResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.balance, Uint128::zero());
+
+    Ok(())
+}
+
+fn vote(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(EPOCH_START);
+
+    let info = mock_info(USER1, &[]);
+    let msg = ExecuteMsg::Vote {
+        votes: vec![(REWARD_TOKEN.to_string(), 5000u16)],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Cannot vote for reward");
+
+    let msg = ExecuteMsg::Vote {
+        votes: vec![(LP_TOKEN.to_string(), 5000u16), (LP_TOKEN.to_string(), 5000u16)],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Duplicated pool");
+
+    let msg = ExecuteMsg::Vote {
+        votes: vec![(LP_TOKEN.to_string(), 10001u16)],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Votes cannot exceed 10000 bps");
+
+    // the first day of the period is reserved for the controller
+    let msg = ExecuteMsg::SubmitVotes {};
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, &format!("Votes cannot be submitted until {}", EPOCH_START + DAY));
+
+    env.block.time = Timestamp::from_seconds(EPOCH_START + DAY);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "No votes to submit");
+
+    let msg = ExecuteMsg::Vote {
+        votes: vec![(LP_TOKEN.to_string(), 5000u16)],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::UserVotes {
+        user: USER1.to_string(),
+    };
+    let res: UserVotesResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, UserVotesResponse {
+        bond_share: Uint128::from(800u128),
+        votes: vec![(Addr::unchecked(LP_TOKEN), 5000u16, Uint128::from(400u128))],
+    });
+
+    // votes follow the share after transfer
+    let msg = ExecuteMsg::Transfer {
+        recipient: USER3.to_string(),
+        amount: Uint128::from(200u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::PoolVotes {};
+    let res: PoolVotesResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, PoolVotesResponse {
+        weights: vec![(Addr::unchecked(LP_TOKEN), Uint128::from(300u128))],
+        votes: vec![(LP_TOKEN.to_string(), 10000u16)],
+        last_vote_period: None,
+    });
+
+    let info = mock_info(USER3, &[]);
+    let msg = ExecuteMsg::Transfer {
+        recipient: USER1.to_string(),
+        amount: Uint128::from(200u128),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    let msg = ExecuteMsg::SubmitVotes {};
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(
+        res.messages.into_iter().map(|it| it.msg).collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: GENERATOR_CONTROLLER.to_string(),
+                msg: to_binary(&ControllerExecuteMsg::Vote {
+                    votes: vec![(LP_TOKEN.to_string(), 10000u16)],
+                })?,
+                funds: vec![],
+            }),
+        ]);
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "Votes are already submitted in this period");
+
+    // controller can override the votes at any time
+    let info = mock_info(CONTROLLER, &[]);
+    let controller_msg = ExecuteMsg::ControllerVote {
+        votes: vec![(REWARD_TOKEN.to_string(), 10000u16)],
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), controller_msg.clone());
+    assert!(res.is_ok());
+
+    env.block.time = Timestamp::from_seconds(EPOCH_START + WEEK);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, &format!("Votes cannot be submitted until {}", EPOCH_START + WEEK + DAY));
+
+    env.block.time = Timestamp::from_seconds(EPOCH_START + WEEK + DAY);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert!(res.is_ok());
+
+    let query_msg = QueryMsg::PoolVotes {};
+    let res: PoolVotesResponse = from_binary(&query(deps.as_ref(), env.clone(), query_msg)?)?;
+    assert_eq!(res.weights, vec![(Addr::unchecked(LP_TOKEN), Uint128::from(400u128))]);
+    assert_eq!(res.last_vote_period, Some(1));
+
+    // controller blocks stakers' votes by voting in its window
+    env.block.time = Timestamp::from_seconds(EPOCH_START + 2 * WEEK);
+    let info = mock_info(CONTROLLER, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, controller_msg);
+    assert!(res.is_ok());
+
+    env.block.time = Timestamp::from_seconds(EPOCH_START + 2 * WEEK + DAY);
+    let info = mock_info(USER1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Votes are already submitted in this period");
+
+    Ok(())
+}
+
 fn unstake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(), ContractError> {
     let mut env = mock_env();
     env.block.time = Timestamp::from_seconds(EPOCH_START);
@@ -1106,12 +1502,46 @@ fn unstake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resul
         unstaking_period: 1,
     });
 
+    let msg = QueryMsg::UnstakeRequests {
+        user: USER1.to_string(),
+    };
+    let res: UnstakeRequestsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res, UnstakeRequestsResponse {
+        requests: vec![
+            UnstakeRequestResponse {
+                amount: Uint128::from(500u128),
+                unlock_period: 1,
+                claim_time: EPOCH_START + WEEK,
+                claimable: true,
+            },
+            UnstakeRequestResponse {
+                amount: Uint128::from(300u128),
+                unlock_period: 2,
+                claim_time: EPOCH_START + 2 * WEEK,
+                claimable: false,
+            },
+        ],
+    });
+
     let msg = ExecuteMsg::WithdrawUnstaked {
         amount: Some(Uint128::from(800u128)),
     };
     let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
     assert_error(res, "Cannot Sub with 500 and 800");
 
+    let msg = ExecuteMsg::WithdrawUnstaked {
+        amount: Some(Uint128::from(200u128)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert!(res.is_ok());
+
+    let msg = QueryMsg::UnstakeRequests {
+        user: USER1.to_string(),
+    };
+    let res: UnstakeRequestsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.requests[0].amount, Uint128::from(300u128));
+    assert_eq!(res.requests[1].amount, Uint128::from(300u128));
+
     let msg = ExecuteMsg::WithdrawUnstaked {
         amount: None,
     };
@@ -1123,12 +1553,19 @@ fn unstake(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Resul
                 contract_addr: XASTRO_TOKEN.to_string(),
                 msg: to_binary(&Cw20ExecuteMsg::Transfer {
                     recipient: USER1.to_string(),
-                    amount: Uint128::from(500u128),
+                    amount: Uint128::from(300u128),
                 })?,
                 funds: vec![],
             }),
         ]);
 
+    let msg = QueryMsg::UnstakeRequests {
+        user: USER1.to_string(),
+    };
+    let res: UnstakeRequestsResponse = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
+    assert_eq!(res.requests.len(), 1);
+    assert!(!res.requests[0].claimable);
+
 
     let msg = QueryMsg::StakingState {};
     let res: StakingState = from_binary(&query(deps.as_ref(), env.clone(), msg)?)?;
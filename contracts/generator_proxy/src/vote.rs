@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128};
+use astroport_governance::utils::{get_period, EPOCH_START, WEEK};
+use crate::error::ContractError;
+use crate::model::{PoolVotesResponse, UserVotesResponse};
+use crate::state::{CONFIG, LAST_VOTE_PERIOD, POOL_CONFIG, POOL_VOTES, STAKER_INFO, USER_VOTES};
+
+const MAX_BPS: u16 = 10000;
+
+/// The seconds from the start of each period reserved for the controller to vote
+const CONTROLLER_VOTE_WINDOW: u64 = 86400;
+
+/// This structure holds the aggregated votes of the registered pools
+struct AggregatedVotes {
+    /// The aggregated weight of each pool
+    weights: Vec<(Addr, Uint128)>,
+    /// The weights converted to bps
+    votes: Vec<(String, u16)>,
+}
+
+fn vote_weight(share: Uint128, bps: u16) -> Uint128 {
+    share.multiply_ratio(bps, MAX_BPS)
+}
+
+/// Moves the aggregated weight of the user's votes from the previous share to the new share
+pub fn update_vote_share(
+    storage: &mut dyn Storage,
+    user: &Addr,
+    prev_share: Uint128,
+    new_share: Uint128,
+) -> StdResult<()> {
+    let votes = USER_VOTES.may_load(storage, user)?
+        .unwrap_or_default();
+    for (lp_token, bps) in votes {
+        add_pool_vote(storage, &lp_token, vote_weight(prev_share, bps), vote_weight(new_share, bps))?;
+    }
+
+    Ok(())
+}
+
+fn add_pool_vote(
+    storage: &mut dyn Storage,
+    lp_token: &Addr,
+    deduct_weight: Uint128,
+    add_weight: Uint128,
+) -> StdResult<()> {
+    let weight = POOL_VOTES.may_load(storage, lp_token)?
+        .unwrap_or_default()
+        .checked_sub(deduct_weight)?
+        + add_weight;
+    if weight.is_zero() {
+        POOL_VOTES.remove(storage, lp_token);
+    } else {
+        POOL_VOTES.save(storage, lp_token, &weight)?;
+    }
+
+    Ok(())
+}
+
+pub fn execute_vote(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    votes: Vec<(String, u16)>,
+) -> Result<Response, ContractError> {
+
+    // validate
+    let mut lp_tokens: HashSet<Addr> = HashSet::new();
+    let mut total_bps = 0u16;
+    let mut user_votes: Vec<(Addr, u16)> = vec![];
+    for (lp_token, bps) in votes {
+        let lp_token = deps.api.addr_validate(&lp_token)?;
+        if !POOL_CONFIG.has(deps.storage, &lp_token) {
+            return Err(StdError::generic_err(format!("Cannot vote for {}", lp_token)).into());
+        }
+        if !lp_tokens.insert(lp_token.clone()) {
+            return Err(StdError::generic_err("Duplicated pool").into());
+        }
+        total_bps = total_bps.checked_add(bps)
+            .filter(|it| *it <= MAX_BPS)
+            .ok_or_else(|| StdError::generic_err("Votes cannot exceed 10000 bps"))?;
+        user_votes.push((lp_token, bps));
+    }
+
+    // replace the previous votes
+    let bond_share = STAKER_INFO.may_load(deps.storage, &info.sender)?
+        .map(|it| it.bond_share)
+        .unwrap_or_default();
+    update_vote_share(deps.storage, &info.sender, bond_share, Uint128::zero())?;
+    for (lp_token, bps) in user_votes.iter() {
+        add_pool_vote(deps.storage, lp_token, Uint128::zero(), vote_weight(bond_share, *bps))?;
+    }
+    if user_votes.is_empty() {
+        USER_VOTES.remove(deps.storage, &info.sender);
+    } else {
+        USER_VOTES.save(deps.storage, &info.sender, &user_votes)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "vote")
+        .add_attribute("bond_share", bond_share)
+    )
+}
+
+/// Converts the aggregated weights of the registered pools to bps
+fn aggregate_votes(
+    deps: Deps,
+) -> StdResult<AggregatedVotes> {
+    let pool_votes = POOL_VOTES
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((lp_token, _)) => POOL_CONFIG.has(deps.storage, lp_token),
+            Err(_) => true,
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let total_weight: Uint128 = pool_votes.iter().map(|(_, weight)| *weight).sum();
+    let votes = pool_votes.iter()
+        .map(|(lp_token, weight)| {
+            let bps = weight.multiply_ratio(MAX_BPS, total_weight).u128() as u16;
+            (lp_token.to_string(), bps)
+        })
+        .filter(|(_, bps)| *bps > 0)
+        .collect();
+
+    Ok(AggregatedVotes {
+        weights: pool_votes,
+        votes,
+    })
+}
+
+pub fn execute_submit_votes(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+) -> Result<Response, ContractError> {
+
+    // stakers' votes can be submitted once per period after the controller window,
+    // unless the controller already voted
+    let period = get_period(env.block.time.seconds())?;
+    if let Some(last_vote_period) = LAST_VOTE_PERIOD.may_load(deps.storage)? {
+        if last_vote_period >= period {
+            return Err(ContractError::AlreadyVoted {});
+        }
+    }
+    let open_time = EPOCH_START + period * WEEK + CONTROLLER_VOTE_WINDOW;
+    if env.block.time.seconds() < open_time {
+        return Err(StdError::generic_err(format!("Votes cannot be submitted until {}", open_time)).into());
+    }
+
+    let AggregatedVotes { votes, .. } = aggregate_votes(deps.as_ref())?;
+    if votes.is_empty() {
+        return Err(StdError::generic_err("No votes to submit").into());
+    }
+
+    LAST_VOTE_PERIOD.save(deps.storage, &period)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let vote_msg = config.astro_gov.controller_vote_msg(votes)?;
+
+    Ok(Response::new()
+        .add_message(vote_msg)
+        .add_attribute("action", "submit_votes")
+        .add_attribute("period", period.to_string())
+    )
+}
+
+pub fn query_user_votes(
+    deps: Deps,
+    _env: Env,
+    user: String,
+) -> StdResult<UserVotesResponse> {
+    let user = deps.api.addr_validate(&user)?;
+    let bond_share = STAKER_INFO.may_load(deps.storage, &user)?
+        .map(|it| it.bond_share)
+        .unwrap_or_default();
+    let votes = USER_VOTES.may_load(deps.storage, &user)?
+        .unwrap_or_default();
+
+    Ok(UserVotesResponse {
+        bond_share,
+        votes: votes.into_iter()
+            .map(|(lp_token, bps)| (lp_token, bps, vote_weight(bond_share, bps)))
+            .collect(),
+    })
+}
+
+pub fn query_pool_votes(
+    deps: Deps,
+    _env: Env,
+) -> StdResult<PoolVotesResponse> {
+    let AggregatedVotes { weights, votes } = aggregate_votes(deps)?;
+
+    Ok(PoolVotesResponse {
+        weights,
+        votes,
+        last_vote_period: LAST_VOTE_PERIOD.may_load(deps.storage)?,
+    })
+}
@@ -121,7 +121,8 @@ fn receive_cw20(
                     contract: cfg.reward_contract_addr.to_string(),
                     amount: cw20_msg.amount,
                     msg: to_binary(&bazCw20HookMsg::Bond {
-                        staker_addr: None
+                        staker_addr: None,
+                        lock_duration: None,
                     })?,
                 })?,
             })));
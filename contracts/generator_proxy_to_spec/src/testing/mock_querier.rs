@@ -120,6 +120,7 @@ impl WasmMockQuerier {
                         pending_reward: self.reward_querier.pending_reward,
                         reward_index: Decimal::zero(),
                         staking_token: "baz0000".to_string(),
+                        stream_pending_rewards: vec![],
                     },
                 }))),
                 _ => match from_binary(msg).unwrap() {
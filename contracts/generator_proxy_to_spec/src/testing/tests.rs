@@ -96,6 +96,7 @@ fn test_deposit() {
                 amount: Uint128::from(100u128),
                 msg: to_binary(&bazCw20HookMsg::Bond {
                     staker_addr: None,
+                    lock_duration: None,
                 }).unwrap(),
             })
             .unwrap(),
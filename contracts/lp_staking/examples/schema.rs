@@ -2,8 +2,8 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use baz::lp_staking::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, RewardInfoResponse,
-    StateResponse,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, FundingResponse, InstantiateMsg, LocksResponse, QueryMsg,
+    RewardInfoResponse, StateResponse, UnbondingsResponse,
 };
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
@@ -20,4 +20,7 @@ fn main() {
     export_schema(&schema_for!(ConfigResponse), &out_dir);
     export_schema(&schema_for!(RewardInfoResponse), &out_dir);
     export_schema(&schema_for!(StateResponse), &out_dir);
+    export_schema(&schema_for!(LocksResponse), &out_dir);
+    export_schema(&schema_for!(UnbondingsResponse), &out_dir);
+    export_schema(&schema_for!(FundingResponse), &out_dir);
 }
@@ -2,23 +2,29 @@
 use cosmwasm_std::entry_point;
 
 use cosmwasm_std::{
-    from_binary, to_binary, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
-    MessageInfo, Response, StdError, StdResult, Uint128, WasmMsg,
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128,
 };
 
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt};
+use baz::adapters::asset::AssetEx;
 use baz::{lp_staking::{
     ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    RewardInfoResponse, StateResponse, RewardInfoResponseItem,
+    Lock, LockTier, LocksResponse, RewardInfoResponse, RewardStream, StateResponse,
+    RewardInfoResponseItem, Unbonding, UnbondingsResponse, FundingResponse, RewardFunding,
 }};
 
 use crate::{
     state::{
-        read_reward_info, Config, RewardInfo, State, CONFIG, STATE, REWARD_INFOS, query_rewards, OWNERSHIP_PROPOSAL
+        read_reward_info, Config, RewardInfo, State, CONFIG, STATE, REWARD_INFOS, query_rewards, OWNERSHIP_PROPOSAL,
+        LEGACY_CONFIG, UNBONDINGS, BOND_AMOUNTS, BOND_CHECKPOINT_HEIGHT, TOTAL_BOND_AMOUNT, lock_bonus, LockExpiry, LOCK_EXPIRIES,
+        PendingRewardBackfill, PENDING_REWARD_BACKFILL,
     },
     ownership::{claim_ownership, drop_ownership_proposal, propose_new_owner}, error::ContractError,
 };
 
-use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw20::Cw20ReceiveMsg;
+use cw_storage_plus::Bound;
 use std::collections::BTreeMap;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -28,14 +34,20 @@ pub fn instantiate(
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    msg.reward_token.check(deps.api)?;
+    msg.staking_token.check(deps.api)?;
 
     CONFIG.save(
         deps.storage,
         &Config {
             owner: deps.api.addr_validate(&msg.owner)?,
-            reward_token: deps.api.addr_validate(&msg.reward_token)?,
-            staking_token: deps.api.addr_validate(&msg.staking_token)?,
+            reward_token: msg.reward_token,
+            staking_token: msg.staking_token,
             distribution_schedule: msg.distribution_schedule,
+            reward_streams: vec![],
+            lock_tiers: vec![],
+            unbonding_period: 0,
+            min_fund_amount: Uint128::zero(),
         },
     )?;
 
@@ -45,6 +57,12 @@ pub fn instantiate(
             last_distributed: env.block.time.seconds(),
             total_bond_amount: Uint128::zero(),
             global_reward_index: Decimal::zero(),
+            stream_reward_indexes: vec![],
+            total_lock_bonus: Uint128::zero(),
+            total_unbonding_amount: Uint128::zero(),
+            total_pending_reward: Uint128::zero(),
+            stream_total_pending_rewards: vec![],
+            pending_reward_tracked: true,
         },
     )?;
 
@@ -53,15 +71,42 @@ pub fn instantiate(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response, ContractError> {
+    // the pending reward totals must not change until the backfill is done
+    if PENDING_REWARD_BACKFILL.may_load(deps.storage)?.is_some()
+        && !matches!(
+            msg,
+            ExecuteMsg::BackfillPendingRewards { .. }
+                | ExecuteMsg::ProposeNewOwner { .. }
+                | ExecuteMsg::DropOwnershipProposal {}
+                | ExecuteMsg::ClaimOwnership {}
+        )
+    {
+        return Err(ContractError::BackfillInProgress {});
+    }
+
     match msg {
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Bond {
+            staker_addr,
+            lock_duration,
+        } => bond_native(deps, env, info, staker_addr, lock_duration),
         ExecuteMsg::Unbond { amount } => unbond(deps, env, info, amount),
         ExecuteMsg::Withdraw {
             amount
         } => withdraw(deps, env, info, amount),
+        ExecuteMsg::ClaimUnbonded {} => claim_unbonded(deps, env, info),
+        ExecuteMsg::FundSchedule { start, end } => fund_schedule_native(deps, env, info, start, end),
         ExecuteMsg::UpdateConfig {
             distribution_schedule,
-        } => update_config(deps, env, info, distribution_schedule),
+            unbonding_period,
+            min_fund_amount,
+        } => update_config(deps, env, info, distribution_schedule, unbonding_period, min_fund_amount),
+        ExecuteMsg::UpdateRewardStream {
+            asset_info,
+            distribution_schedule,
+        } => update_reward_stream(deps, env, info, asset_info, distribution_schedule),
+        ExecuteMsg::UpdateLockTiers { lock_tiers } => update_lock_tiers(deps, info, lock_tiers),
+        ExecuteMsg::BackfillPendingRewards { limit } => backfill_pending_rewards(deps, info, limit),
         ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
             let config: Config = CONFIG.load(deps.storage)?;
 
@@ -105,36 +150,109 @@ pub fn receive_cw20(
     let config: Config = CONFIG.load(deps.storage)?;
 
     match from_binary(&cw20_msg.msg) {
-        Ok(Cw20HookMsg::Bond { staker_addr }) => {
+        Ok(Cw20HookMsg::Bond {
+            staker_addr,
+            lock_duration,
+        }) => {
             // only staking token contract can execute this message
-            if config.staking_token != info.sender {
+            if config.staking_token != (AssetInfo::Token { contract_addr: info.sender }) {
                 return Err(ContractError::Unauthorized {});
             }
 
             let cw20_sender = deps.api.addr_validate(&cw20_msg.sender)?.to_string();
-            bond(deps, env, staker_addr.unwrap_or(cw20_sender), cw20_msg.amount)
+            bond(
+                deps,
+                env,
+                staker_addr.unwrap_or(cw20_sender),
+                cw20_msg.amount,
+                lock_duration,
+            )
+        }
+        Ok(Cw20HookMsg::FundSchedule { start, end }) => {
+            let asset = AssetInfo::Token { contract_addr: info.sender }.with_balance(cw20_msg.amount);
+            fund_schedule(deps, env, asset, start, end)
         }
         Err(_) => Err(ContractError::InvalidMessage {}),
     }
 }
 
-pub fn bond(deps: DepsMut, env: Env, sender_addr: String, amount: Uint128) -> Result<Response, ContractError> {
+pub fn bond_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    staker_addr: Option<String>,
+    lock_duration: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    // only native staking token can be bonded with funds
+    let denom = match &config.staking_token {
+        AssetInfo::NativeToken { denom } => denom,
+        AssetInfo::Token { .. } => return Err(ContractError::InvalidMessage {}),
+    };
+    if info.funds.len() != 1 || &info.funds[0].denom != denom || info.funds[0].amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "must send {} only",
+            denom
+        ))));
+    }
+
+    let amount = info.funds[0].amount;
+    bond(
+        deps,
+        env,
+        staker_addr.unwrap_or_else(|| info.sender.to_string()),
+        amount,
+        lock_duration,
+    )
+}
+
+pub fn bond(
+    deps: DepsMut,
+    env: Env,
+    sender_addr: String,
+    amount: Uint128,
+    lock_duration: Option<u64>,
+) -> Result<Response, ContractError> {
     let sender_addr = deps.api.addr_validate(&sender_addr)?;
 
     let config: Config = CONFIG.load(deps.storage)?;
     let mut state: State = STATE.load(deps.storage)?;
     let mut reward_info: RewardInfo = read_reward_info(deps.storage, &sender_addr)?;
+    seed_bond_snapshot(deps.storage, &sender_addr, &reward_info)?;
 
     // Compute global reward & staker reward
-    compute_reward(&config, &mut state, env.block.time.seconds());
-    compute_staker_reward(&state, &mut reward_info)?;
+    update_reward(deps.storage, &config, &mut state, env.block.time.seconds())?;
+    compute_staker_reward(deps.storage, &state, &mut reward_info, &[])?;
 
     // Increase bond_amount
     increase_bond_amount(&mut state, &mut reward_info, amount);
 
+    // Lock the bonded amount with the multiplier of the lock tier
+    if let Some(lock_duration) = lock_duration {
+        let lock_tier = config
+            .lock_tiers
+            .iter()
+            .find(|it| it.duration == lock_duration)
+            .ok_or(ContractError::InvalidLockDuration {})?;
+        let lock = Lock {
+            amount,
+            multiplier: lock_tier.multiplier,
+            unlock_time: env.block.time.seconds() + lock_duration,
+        };
+        state.total_lock_bonus += lock_bonus(&lock);
+        LOCK_EXPIRIES.update(deps.storage, lock.unlock_time, |expiry| -> StdResult<_> {
+            let mut expiry = expiry.unwrap_or_default();
+            expiry.lock_bonus += lock_bonus(&lock);
+            Ok(expiry)
+        })?;
+        reward_info.locks.push(lock);
+    }
+
     // Store updated state with staker's reward_info
     REWARD_INFOS.save(deps.storage, &sender_addr, &reward_info)?;
     STATE.save(deps.storage, &state)?;
+    save_bond_snapshots(deps.storage, &env, &sender_addr, &reward_info, &state)?;
 
     Ok(Response::new().add_attributes(vec![
         ("action", "bond"),
@@ -153,17 +271,37 @@ pub fn unbond(deps: DepsMut, env: Env, info: MessageInfo, amount: Uint128) -> Re
     if reward_info.bond_amount < amount {
         return Err(ContractError::UnbondExceedBalance {});
     }
+    if reward_info.bond_amount - reward_info.locked_amount(env.block.time.seconds()) < amount {
+        return Err(ContractError::UnbondLocked {});
+    }
+    seed_bond_snapshot(deps.storage, &sender_addr, &reward_info)?;
 
     // Compute global reward & staker reward
-    compute_reward(&config, &mut state, env.block.time.seconds());
-    compute_staker_reward(&state, &mut reward_info)?;
+    update_reward(deps.storage, &config, &mut state, env.block.time.seconds())?;
+    compute_staker_reward(deps.storage, &state, &mut reward_info, &[])?;
 
     // Decrease bond_amount
     decrease_bond_amount(&mut state, &mut reward_info, amount)?;
 
+    // Queue the unbonded amount, it stops earning rewards but is paid after the unbonding period
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if config.unbonding_period == 0 {
+        messages.push(config.staking_token.with_balance(amount).transfer_msg(&sender_addr)?);
+    } else {
+        let mut unbondings = UNBONDINGS
+            .may_load(deps.storage, &sender_addr)?
+            .unwrap_or_default();
+        unbondings.push(Unbonding {
+            amount,
+            claimable_time: env.block.time.seconds() + config.unbonding_period,
+        });
+        UNBONDINGS.save(deps.storage, &sender_addr, &unbondings)?;
+        state.total_unbonding_amount += amount;
+    }
+
     // Store or remove updated rewards info
     // depends on the left pending reward and bond amount
-    if reward_info.pending_reward.is_zero() && reward_info.bond_amount.is_zero() {
+    if reward_info.is_empty() {
         REWARD_INFOS.remove(deps.storage, &sender_addr);
     } else {
         REWARD_INFOS.save(deps.storage, &sender_addr, &reward_info)?;
@@ -171,16 +309,10 @@ pub fn unbond(deps: DepsMut, env: Env, info: MessageInfo, amount: Uint128) -> Re
 
     // Store updated state
     STATE.save(deps.storage, &state)?;
+    save_bond_snapshots(deps.storage, &env, &sender_addr, &reward_info, &state)?;
 
     Ok(Response::new()
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.staking_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: sender_addr.to_string(),
-                amount,
-            })?,
-            funds: vec![],
-        })])
+        .add_messages(messages)
         .add_attributes(vec![
             ("action", "unbond"),
             ("owner", sender_addr.as_str()),
@@ -197,15 +329,35 @@ pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo, baz_amount: Option<U
     let mut reward_info = read_reward_info(deps.storage, &sender_addr)?;
 
     // Compute global reward & staker reward
-    compute_reward(&config, &mut state, env.block.time.seconds());
-    compute_staker_reward(&state, &mut reward_info)?;
+    update_reward(deps.storage, &config, &mut state, env.block.time.seconds())?;
+    compute_staker_reward(deps.storage, &state, &mut reward_info, &[])?;
 
     let amount = baz_amount.unwrap_or(reward_info.pending_reward);
     reward_info.pending_reward = reward_info.pending_reward.checked_sub(amount)?;
+    state.total_pending_reward = state.total_pending_reward.checked_sub(amount)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    if !amount.is_zero() {
+        messages.push(config.reward_token.with_balance(amount).transfer_msg(&sender_addr)?);
+    }
+
+    // pay out all pending rewards of the reward streams
+    for ((stream, pending_reward), total_pending_reward) in config
+        .reward_streams
+        .iter()
+        .zip(reward_info.stream_pending_rewards.iter_mut())
+        .zip(state.stream_total_pending_rewards.iter_mut())
+    {
+        if !pending_reward.is_zero() {
+            messages.push(stream.asset_info.with_balance(*pending_reward).transfer_msg(&sender_addr)?);
+            *total_pending_reward = total_pending_reward.checked_sub(*pending_reward)?;
+            *pending_reward = Uint128::zero();
+        }
+    }
 
     // Store or remove updated rewards info
     // depends on the left pending reward and bond amount
-    if reward_info.bond_amount.is_zero() && reward_info.pending_reward.is_zero() {
+    if reward_info.is_empty() {
         REWARD_INFOS.remove(deps.storage, &sender_addr);
     } else {
         REWARD_INFOS.save(deps.storage, &sender_addr, &reward_info)?;
@@ -215,14 +367,7 @@ pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo, baz_amount: Option<U
     STATE.save(deps.storage, &state)?;
 
     Ok(Response::new()
-        .add_messages(vec![CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: config.reward_token.to_string(),
-            msg: to_binary(&Cw20ExecuteMsg::Transfer {
-                recipient: sender_addr.to_string(),
-                amount,
-            })?,
-            funds: vec![],
-        })])
+        .add_messages(messages)
         .add_attributes(vec![
             ("action", "withdraw"),
             ("owner", sender_addr.as_str()),
@@ -230,11 +375,130 @@ pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo, baz_amount: Option<U
         ]))
 }
 
+// claim unbonded tokens whose unbonding period has passed
+pub fn claim_unbonded(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let sender_addr = info.sender;
+
+    let config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+    let mut unbondings = UNBONDINGS
+        .may_load(deps.storage, &sender_addr)?
+        .unwrap_or_default();
+
+    let mut amount = Uint128::zero();
+    unbondings.retain(|it| {
+        if it.claimable_time > env.block.time.seconds() {
+            return true;
+        }
+        amount += it.amount;
+        false
+    });
+
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    if unbondings.is_empty() {
+        UNBONDINGS.remove(deps.storage, &sender_addr);
+    } else {
+        UNBONDINGS.save(deps.storage, &sender_addr, &unbondings)?;
+    }
+
+    state.total_unbonding_amount = state.total_unbonding_amount.checked_sub(amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_message(config.staking_token.with_balance(amount).transfer_msg(&sender_addr)?)
+        .add_attributes(vec![
+            ("action", "claim_unbonded"),
+            ("owner", sender_addr.as_str()),
+            ("amount", amount.to_string().as_str()),
+        ]))
+}
+
+pub fn fund_schedule_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start: u64,
+    end: u64,
+) -> Result<Response, ContractError> {
+    if info.funds.len() != 1 {
+        return Err(ContractError::Std(StdError::generic_err("must send exactly one coin")));
+    }
+
+    let asset = AssetInfo::NativeToken {
+        denom: info.funds[0].denom.clone(),
+    }
+    .with_balance(info.funds[0].amount);
+    fund_schedule(deps, env, asset, start, end)
+}
+
+/// The maximum number of schedules not ended yet of the reward token or a reward stream
+const MAX_ACTIVE_SCHEDULES: usize = 10;
+
+// create a schedule of the reward token or a reward stream with the sent asset
+pub fn fund_schedule(
+    deps: DepsMut,
+    env: Env,
+    asset: Asset,
+    start: u64,
+    end: u64,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+
+    update_reward(deps.storage, &config, &mut state, env.block.time.seconds())?;
+
+    if end <= start || asset.amount.is_zero() {
+        return Err(ContractError::InvalidDistributionSchedule {});
+    }
+    if start <= state.last_distributed {
+        return Err(ContractError::DistributionScheduleStarted {});
+    }
+    if asset.amount < config.min_fund_amount {
+        return Err(ContractError::FundAmountTooSmall(config.min_fund_amount));
+    }
+
+    let distribution_schedule = if asset.info == config.reward_token {
+        &mut config.distribution_schedule
+    } else if let Some(stream) = config
+        .reward_streams
+        .iter_mut()
+        .find(|it| it.asset_info == asset.info)
+    {
+        &mut stream.distribution_schedule
+    } else {
+        return Err(ContractError::Std(StdError::generic_err(
+            "asset is not a reward of this contract",
+        )));
+    };
+
+    // schedules ended before last_distributed are fully distributed
+    distribution_schedule.retain(|it| it.1 > state.last_distributed);
+    if distribution_schedule.len() >= MAX_ACTIVE_SCHEDULES {
+        return Err(ContractError::TooManySchedules(MAX_ACTIVE_SCHEDULES));
+    }
+    distribution_schedule.push((start, end, asset.amount));
+
+    CONFIG.save(deps.storage, &config)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "fund_schedule"),
+        ("asset", asset.to_string().as_str()),
+        ("start", start.to_string().as_str()),
+        ("end", end.to_string().as_str()),
+    ]))
+}
+
 pub fn update_config(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     distribution_schedule: Option<Vec<(u64, u64, Uint128)>>,
+    unbonding_period: Option<u64>,
+    min_fund_amount: Option<Uint128>,
 ) -> Result<Response, ContractError> {
     let mut config: Config = CONFIG.load(deps.storage)?;
     let state: State = STATE.load(deps.storage)?;
@@ -244,8 +508,29 @@ pub fn update_config(
     }
 
     if let Some(distribution_schedule) = distribution_schedule {
-        assert_new_schedules(&config, &state, distribution_schedule.clone())?;
+        assert_new_schedules(&config.distribution_schedule, &state, distribution_schedule.clone())?;
         config.distribution_schedule = distribution_schedule;
+
+        let funding = compute_funding(
+            deps.as_ref(),
+            &env,
+            &config,
+            &state,
+            &config.reward_token,
+            &config.distribution_schedule,
+            state.total_pending_reward,
+        )?;
+        if !funding.unfunded_amount.is_zero() {
+            return Err(ContractError::ScheduleNotFunded {});
+        }
+    }
+
+    if let Some(unbonding_period) = unbonding_period {
+        config.unbonding_period = unbonding_period;
+    }
+
+    if let Some(min_fund_amount) = min_fund_amount {
+        config.min_fund_amount = min_fund_amount;
     }
 
     CONFIG.save(deps.storage, &config)?;
@@ -253,6 +538,126 @@ pub fn update_config(
     Ok(Response::new().add_attributes(vec![("action", "update_config")]))
 }
 
+pub fn update_reward_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset_info: AssetInfo,
+    distribution_schedule: Vec<(u64, u64, Uint128)>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+    let mut state: State = STATE.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    asset_info.check(deps.api)?;
+    if asset_info == config.reward_token {
+        return Err(ContractError::Std(StdError::generic_err(
+            "reward token is distributed by distribution_schedule",
+        )));
+    }
+
+    // bring the indexes up to date, so a new stream only distributes from now
+    update_reward(deps.storage, &config, &mut state, env.block.time.seconds())?;
+
+    let index = match config.reward_streams.iter().position(|it| it.asset_info == asset_info) {
+        Some(index) => {
+            let stream = &mut config.reward_streams[index];
+            assert_new_schedules(&stream.distribution_schedule, &state, distribution_schedule.clone())?;
+            stream.distribution_schedule = distribution_schedule;
+            index
+        }
+        None => {
+            assert_new_schedules(&[], &state, distribution_schedule.clone())?;
+            config.reward_streams.push(RewardStream {
+                asset_info: asset_info.clone(),
+                distribution_schedule,
+            });
+            state.stream_reward_indexes.push(Decimal::zero());
+            state.stream_total_pending_rewards.push(Uint128::zero());
+            config.reward_streams.len() - 1
+        }
+    };
+
+    let funding = compute_funding(
+        deps.as_ref(),
+        &env,
+        &config,
+        &state,
+        &asset_info,
+        &config.reward_streams[index].distribution_schedule,
+        state.stream_total_pending_rewards[index],
+    )?;
+    if !funding.unfunded_amount.is_zero() {
+        return Err(ContractError::ScheduleNotFunded {});
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "update_reward_stream"),
+        ("asset_info", asset_info.to_string().as_str()),
+    ]))
+}
+
+pub fn update_lock_tiers(
+    deps: DepsMut,
+    info: MessageInfo,
+    lock_tiers: Vec<LockTier>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for (i, lock_tier) in lock_tiers.iter().enumerate() {
+        if lock_tier.duration == 0
+            || lock_tier.multiplier < Decimal::one()
+            || lock_tiers[..i].iter().any(|it| it.duration == lock_tier.duration)
+        {
+            return Err(ContractError::InvalidLockTiers {});
+        }
+    }
+
+    // existing locks keep the multiplier they were created with
+    config.lock_tiers = lock_tiers;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "update_lock_tiers")]))
+}
+
+// checkpoint the bond amounts at the current height
+fn save_bond_snapshots(
+    storage: &mut dyn Storage,
+    env: &Env,
+    staker_addr: &Addr,
+    reward_info: &RewardInfo,
+    state: &State,
+) -> StdResult<()> {
+    BOND_AMOUNTS.save(storage, staker_addr, &reward_info.bond_amount, env.block.height)?;
+    TOTAL_BOND_AMOUNT.save(storage, &state.total_bond_amount, env.block.height)
+}
+
+/// Checkpoints the bond amount of a staker from before the upgrade at the upgrade height,
+/// so the amount is kept for the heights before the staker's first change
+fn seed_bond_snapshot(
+    storage: &mut dyn Storage,
+    staker_addr: &Addr,
+    reward_info: &RewardInfo,
+) -> StdResult<()> {
+    if reward_info.bond_amount.is_zero() || BOND_AMOUNTS.may_load(storage, staker_addr)?.is_some() {
+        return Ok(());
+    }
+    if let Some(height) = BOND_CHECKPOINT_HEIGHT.may_load(storage)? {
+        BOND_AMOUNTS.save(storage, staker_addr, &reward_info.bond_amount, height)?;
+    }
+    Ok(())
+}
+
 fn increase_bond_amount(state: &mut State, reward_info: &mut RewardInfo, amount: Uint128) {
     state.total_bond_amount += amount;
     reward_info.bond_amount += amount;
@@ -268,41 +673,211 @@ fn decrease_bond_amount(
     Ok(())
 }
 
+// compute distributed rewards and stop the bonus of the locks expiring on the way,
+// returns the lock expiries passed with the reward indexes at their unlock time
+fn compute_reward(
+    storage: &dyn Storage,
+    config: &Config,
+    state: &mut State,
+    time_seconds: u64,
+) -> StdResult<Vec<(u64, LockExpiry)>> {
+    let expiries = LOCK_EXPIRIES
+        .range(
+            storage,
+            Some(Bound::exclusive(state.last_distributed)),
+            Some(Bound::inclusive(time_seconds)),
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut passed_expiries = vec![];
+    for (unlock_time, mut expiry) in expiries {
+        distribute_reward(config, state, unlock_time);
+        state.total_lock_bonus = state.total_lock_bonus.checked_sub(expiry.lock_bonus)?;
+        expiry.reward_index = Some(state.global_reward_index);
+        expiry.stream_reward_indexes = state.stream_reward_indexes.clone();
+        passed_expiries.push((unlock_time, expiry));
+    }
+    distribute_reward(config, state, time_seconds);
+
+    Ok(passed_expiries)
+}
+
+// compute distributed rewards and store the reward indexes of the passed lock expiries
+fn update_reward(
+    storage: &mut dyn Storage,
+    config: &Config,
+    state: &mut State,
+    time_seconds: u64,
+) -> StdResult<()> {
+    for (unlock_time, expiry) in compute_reward(storage, config, state, time_seconds)? {
+        LOCK_EXPIRIES.save(storage, unlock_time, &expiry)?;
+    }
+    Ok(())
+}
+
 // compute distributed rewards and update global reward index
-fn compute_reward(config: &Config, state: &mut State, time_seconds: u64) {
-    if state.total_bond_amount.is_zero() {
+fn distribute_reward(config: &Config, state: &mut State, time_seconds: u64) {
+    state.stream_reward_indexes.resize(config.reward_streams.len(), Decimal::zero());
+    state.stream_total_pending_rewards.resize(config.reward_streams.len(), Uint128::zero());
+    let total_weighted_bond_amount = state.total_weighted_bond_amount();
+    if total_weighted_bond_amount.is_zero() {
         state.last_distributed = time_seconds;
         return;
     }
 
+    let distributed_amount = compute_distributed_amount(
+        &config.distribution_schedule,
+        state.last_distributed,
+        time_seconds,
+    );
+    for ((stream, reward_index), total_pending_reward) in config
+        .reward_streams
+        .iter()
+        .zip(state.stream_reward_indexes.iter_mut())
+        .zip(state.stream_total_pending_rewards.iter_mut())
+    {
+        let distributed_amount = compute_distributed_amount(
+            &stream.distribution_schedule,
+            state.last_distributed,
+            time_seconds,
+        );
+        *reward_index += Decimal::from_ratio(distributed_amount, total_weighted_bond_amount);
+        *total_pending_reward += distributed_amount;
+    }
+
+    state.last_distributed = time_seconds;
+    state.total_pending_reward += distributed_amount;
+    state.global_reward_index += Decimal::from_ratio(distributed_amount, total_weighted_bond_amount);
+}
+
+// compare the undistributed schedule and the pending reward against the contract balance
+fn compute_funding(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    state: &State,
+    asset_info: &AssetInfo,
+    distribution_schedule: &[(u64, u64, Uint128)],
+    total_pending_reward: Uint128,
+) -> StdResult<RewardFunding> {
+    let balance = asset_info.query_pool(&deps.querier, env.contract.address.clone())?;
+
+    // bonded and unbonding tokens are not available for rewards
+    let reserved_amount = if *asset_info == config.staking_token {
+        state.total_bond_amount + state.total_unbonding_amount
+    } else {
+        Uint128::zero()
+    };
+    let available_amount = balance
+        .saturating_sub(reserved_amount)
+        .saturating_sub(total_pending_reward);
+
+    let remaining_amount =
+        compute_distributed_amount(distribution_schedule, state.last_distributed, u64::MAX);
+
+    Ok(RewardFunding {
+        asset_info: asset_info.clone(),
+        remaining_amount,
+        unfunded_amount: remaining_amount.saturating_sub(available_amount),
+    })
+}
+
+// compute the amount distributed by the schedule from last_distributed to time_seconds
+fn compute_distributed_amount(
+    distribution_schedule: &[(u64, u64, Uint128)],
+    last_distributed: u64,
+    time_seconds: u64,
+) -> Uint128 {
     let mut distributed_amount: Uint128 = Uint128::zero();
-    for s in config.distribution_schedule.iter() {
-        if s.0 > time_seconds || s.1 < state.last_distributed {
+    for s in distribution_schedule.iter() {
+        if s.0 > time_seconds || s.1 < last_distributed {
             continue;
         }
 
-        let passed_time =
-            std::cmp::min(s.1, time_seconds) - std::cmp::max(s.0, state.last_distributed);
-
+        // the difference of the amounts distributed since the start rounds without dust,
+        // so a finished schedule has distributed all of its amount
         let time = s.1 - s.0;
-        let distribution_amount_per_second: Decimal = Decimal::from_ratio(s.2, time);
-        distributed_amount += distribution_amount_per_second * Uint128::from(passed_time as u128);
+        let distributed_until = |t: u64| s.2.multiply_ratio(t.clamp(s.0, s.1) - s.0, time);
+        distributed_amount +=
+            distributed_until(time_seconds) - distributed_until(last_distributed);
     }
 
-    state.last_distributed = time_seconds;
-    state.global_reward_index += Decimal::from_ratio(distributed_amount, state.total_bond_amount);
+    distributed_amount
 }
 
-// withdraw reward to pending reward
-fn compute_staker_reward(state: &State, reward_info: &mut RewardInfo) -> StdResult<()> {
-    let pending_reward = (reward_info.bond_amount * state.global_reward_index)
-        .checked_sub(reward_info.bond_amount * reward_info.reward_index)?;
+// withdraw reward to pending reward, expired locks earn their bonus until the unlock time and are released
+fn compute_staker_reward(
+    storage: &dyn Storage,
+    state: &State,
+    reward_info: &mut RewardInfo,
+    passed_expiries: &[(u64, LockExpiry)],
+) -> StdResult<()> {
+    let mut unlock_times: Vec<u64> = reward_info
+        .locks
+        .iter()
+        .map(|it| it.unlock_time)
+        .filter(|it| *it <= state.last_distributed)
+        .collect();
+    unlock_times.sort_unstable();
+    unlock_times.dedup();
+
+    for unlock_time in unlock_times {
+        let expiry = match passed_expiries.iter().find(|(time, _)| *time == unlock_time) {
+            Some((_, expiry)) => expiry.clone(),
+            None => LOCK_EXPIRIES.load(storage, unlock_time)?,
+        };
+        let reward_index = expiry
+            .reward_index
+            .ok_or_else(|| StdError::generic_err("lock expiry is not passed"))?;
+        settle_staker_reward(reward_info, reward_index, &expiry.stream_reward_indexes)?;
+        reward_info.locks.retain(|it| it.unlock_time != unlock_time);
+    }
+
+    settle_staker_reward(reward_info, state.global_reward_index, &state.stream_reward_indexes)
+}
+
+// withdraw reward to pending reward up to the given reward indexes
+fn settle_staker_reward(
+    reward_info: &mut RewardInfo,
+    global_reward_index: Decimal,
+    stream_reward_indexes: &[Decimal],
+) -> StdResult<()> {
+    let weighted_bond_amount = reward_info.weighted_bond_amount();
+    let pending_reward = (weighted_bond_amount * global_reward_index)
+        .checked_sub(weighted_bond_amount * reward_info.reward_index)?;
 
-    reward_info.reward_index = state.global_reward_index;
+    reward_info.reward_index = global_reward_index;
     reward_info.pending_reward += pending_reward;
+
+    // streams added after a lock expiry keep the indexes of the staker
+    let stream_count = stream_reward_indexes.len().max(reward_info.stream_reward_indexes.len());
+    reward_info.stream_reward_indexes.resize(stream_count, Decimal::zero());
+    reward_info.stream_pending_rewards.resize(stream_count, Uint128::zero());
+    for (i, global_reward_index) in stream_reward_indexes.iter().enumerate() {
+        let pending_reward = (weighted_bond_amount * *global_reward_index)
+            .checked_sub(weighted_bond_amount * reward_info.stream_reward_indexes[i])?;
+
+        reward_info.stream_reward_indexes[i] = *global_reward_index;
+        reward_info.stream_pending_rewards[i] += pending_reward;
+    }
     Ok(())
 }
 
+// pending rewards of the reward streams as assets
+fn stream_pending_rewards(config: &Config, reward_info: &RewardInfo) -> Vec<Asset> {
+    config
+        .reward_streams
+        .iter()
+        .enumerate()
+        .map(|(i, stream)| {
+            stream.asset_info.with_balance(
+                reward_info.stream_pending_rewards.get(i).cloned().unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -314,6 +889,15 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::AllRewardInfos { start_after, limit, time_seconds } => {
             to_binary(&query_all_reward_infos(deps, start_after, limit, time_seconds)?)
         },
+        QueryMsg::Locks { staker_addr } => to_binary(&query_locks(deps, staker_addr)?),
+        QueryMsg::Unbondings { staker_addr } => {
+            to_binary(&query_unbondings(deps, env, staker_addr)?)
+        },
+        QueryMsg::Funding {} => to_binary(&query_funding(deps, env)?),
+        QueryMsg::BondAmountAt { staker, height } => {
+            to_binary(&query_bond_amount_at(deps, staker, height)?)
+        },
+        QueryMsg::TotalBondAt { height } => to_binary(&query_total_bond_at(deps, height)?),
     }
 }
 
@@ -323,7 +907,13 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
         owner: config.owner.to_string(),
         reward_token: config.reward_token.to_string(),
         staking_token: config.staking_token.to_string(),
+        reward_token_info: config.reward_token,
+        staking_token_info: config.staking_token,
         distribution_schedule: config.distribution_schedule,
+        reward_streams: config.reward_streams,
+        lock_tiers: config.lock_tiers,
+        unbonding_period: config.unbonding_period,
+        min_fund_amount: config.min_fund_amount,
     };
 
     Ok(resp)
@@ -331,15 +921,102 @@ pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
 
 pub fn query_state(deps: Deps, _env: Env, time_seconds: Option<u64>) -> StdResult<StateResponse> {
     let mut state: State = STATE.load(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
     if let Some(time_seconds) = time_seconds {
-        let config = CONFIG.load(deps.storage)?;
-        compute_reward(&config, &mut state, time_seconds);
+        compute_reward(deps.storage, &config, &mut state, time_seconds)?;
     }
+    state.stream_reward_indexes.resize(config.reward_streams.len(), Decimal::zero());
 
     Ok(StateResponse {
         last_distributed: state.last_distributed,
         total_bond_amount: state.total_bond_amount,
         global_reward_index: state.global_reward_index,
+        total_weighted_bond_amount: state.total_weighted_bond_amount(),
+        stream_reward_indexes: state.stream_reward_indexes,
+    })
+}
+
+pub fn query_locks(deps: Deps, staker_addr: String) -> StdResult<LocksResponse> {
+    let staker_addr = deps.api.addr_validate(&staker_addr)?;
+    let reward_info: RewardInfo = read_reward_info(deps.storage, &staker_addr)?;
+
+    Ok(LocksResponse {
+        staker_addr: staker_addr.to_string(),
+        weighted_bond_amount: reward_info.weighted_bond_amount(),
+        locks: reward_info.locks,
+    })
+}
+
+pub fn query_bond_amount_at(deps: Deps, staker: String, height: u64) -> StdResult<Uint128> {
+    let staker_addr = deps.api.addr_validate(&staker)?;
+
+    // stakers without a checkpoint have not changed their bond amount since the checkpoints started
+    if BOND_AMOUNTS.may_load(deps.storage, &staker_addr)?.is_none() {
+        return Ok(read_reward_info(deps.storage, &staker_addr)?.bond_amount);
+    }
+
+    Ok(BOND_AMOUNTS
+        .may_load_at_height(deps.storage, &staker_addr, height)?
+        .unwrap_or_default())
+}
+
+pub fn query_total_bond_at(deps: Deps, height: u64) -> StdResult<Uint128> {
+    Ok(TOTAL_BOND_AMOUNT
+        .may_load_at_height(deps.storage, height)?
+        .unwrap_or_default())
+}
+
+pub fn query_funding(deps: Deps, env: Env) -> StdResult<FundingResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+    compute_reward(deps.storage, &config, &mut state, env.block.time.seconds())?;
+
+    let reward = compute_funding(
+        deps,
+        &env,
+        &config,
+        &state,
+        &config.reward_token,
+        &config.distribution_schedule,
+        state.total_pending_reward,
+    )?;
+    let reward_streams = config
+        .reward_streams
+        .iter()
+        .zip(state.stream_total_pending_rewards.iter())
+        .map(|(stream, total_pending_reward)| {
+            compute_funding(
+                deps,
+                &env,
+                &config,
+                &state,
+                &stream.asset_info,
+                &stream.distribution_schedule,
+                *total_pending_reward,
+            )
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FundingResponse {
+        reward,
+        reward_streams,
+    })
+}
+
+pub fn query_unbondings(deps: Deps, env: Env, staker_addr: String) -> StdResult<UnbondingsResponse> {
+    let staker_addr = deps.api.addr_validate(&staker_addr)?;
+    let unbondings = UNBONDINGS
+        .may_load(deps.storage, &staker_addr)?
+        .unwrap_or_default();
+
+    let (claimable, pending): (Vec<Unbonding>, Vec<Unbonding>) = unbondings
+        .into_iter()
+        .partition(|it| it.claimable_time <= env.block.time.seconds());
+
+    Ok(UnbondingsResponse {
+        staker_addr: staker_addr.to_string(),
+        pending,
+        claimable_amount: claimable.iter().map(|it| it.amount).sum(),
     })
 }
 
@@ -357,8 +1034,8 @@ pub fn query_reward_info(
         let config = CONFIG.load(deps.storage)?;
         let mut state = STATE.load(deps.storage)?;
 
-        compute_reward(&config, &mut state, time_seconds);
-        compute_staker_reward(&state, &mut reward_info)?;
+        let passed_expiries = compute_reward(deps.storage, &config, &mut state, time_seconds)?;
+        compute_staker_reward(deps.storage, &state, &mut reward_info, &passed_expiries)?;
     }
 
     let config: Config = CONFIG.load(deps.storage)?;
@@ -370,21 +1047,22 @@ pub fn query_reward_info(
             bond_amount: reward_info.bond_amount,
             pending_reward: reward_info.pending_reward,
             staking_token: config.staking_token.to_string(),
+            stream_pending_rewards: stream_pending_rewards(&config, &reward_info),
         }
     })
 }
 
 pub fn assert_new_schedules(
-    config: &Config,
+    existing_schedule: &[(u64, u64, Uint128)],
     state: &State,
     distribution_schedule: Vec<(u64, u64, Uint128)>,
 ) -> Result<(), ContractError> {
-    if distribution_schedule.len() < config.distribution_schedule.len() {
+    if distribution_schedule.len() < existing_schedule.len() {
         return Err(ContractError::InvalidDistributionSchedule {});
     }
 
     let mut existing_counts: BTreeMap<(u64, u64, Uint128), u32> = BTreeMap::new();
-    for schedule in config.distribution_schedule.clone() {
+    for schedule in existing_schedule.iter().cloned() {
         let counter = existing_counts.entry(schedule).or_insert(0);
         *counter += 1;
     }
@@ -427,11 +1105,14 @@ pub fn query_all_reward_infos(
     let mut results: Vec<RewardInfoResponse> = vec![];
     let config: Config = CONFIG.load(deps.storage)?;
     let mut state = STATE.load(deps.storage)?;
+    let passed_expiries = match time_seconds {
+        Some(time_seconds) => compute_reward(deps.storage, &config, &mut state, time_seconds)?,
+        None => vec![],
+    };
 
     for (addr, mut reward_info) in reward_infos {
-        if let Some(time_seconds) = time_seconds {
-            compute_reward(&config, &mut state, time_seconds);
-            compute_staker_reward(&state, &mut reward_info)?;
+        if time_seconds.is_some() {
+            compute_staker_reward(deps.storage, &state, &mut reward_info, &passed_expiries)?;
         }
 
         results.push(RewardInfoResponse {
@@ -440,7 +1121,8 @@ pub fn query_all_reward_infos(
                 staking_token: config.staking_token.to_string(),
                 bond_amount: reward_info.bond_amount,
                 reward_index: reward_info.reward_index,
-                pending_reward: reward_info.pending_reward
+                pending_reward: reward_info.pending_reward,
+                stream_pending_rewards: stream_pending_rewards(&config, &reward_info),
             }
         });
     }
@@ -449,6 +1131,110 @@ pub fn query_all_reward_infos(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    // convert cw20 token addresses of the legacy config into asset infos
+    if CONFIG.load(deps.storage).is_err() {
+        let legacy_config = LEGACY_CONFIG.load(deps.storage)?;
+        CONFIG.save(
+            deps.storage,
+            &Config {
+                owner: legacy_config.owner,
+                reward_token: AssetInfo::Token {
+                    contract_addr: legacy_config.reward_token,
+                },
+                staking_token: AssetInfo::Token {
+                    contract_addr: legacy_config.staking_token,
+                },
+                distribution_schedule: legacy_config.distribution_schedule,
+                reward_streams: legacy_config.reward_streams,
+                lock_tiers: vec![],
+                unbonding_period: 0,
+                min_fund_amount: Uint128::zero(),
+            },
+        )?;
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut state = STATE.load(deps.storage)?;
+
+    // start the total bond checkpoints from the current total
+    if TOTAL_BOND_AMOUNT.may_load(deps.storage)?.is_none() {
+        TOTAL_BOND_AMOUNT.save(deps.storage, &state.total_bond_amount, env.block.height)?;
+        BOND_CHECKPOINT_HEIGHT.save(deps.storage, &env.block.height)?;
+    }
+
+    state.stream_reward_indexes.resize(config.reward_streams.len(), Decimal::zero());
+    STATE.save(deps.storage, &state)?;
+
+    // stakers may hold rewards from before the totals were tracked, the owner sums them with
+    // BackfillPendingRewards
+    if !state.pending_reward_tracked && PENDING_REWARD_BACKFILL.may_load(deps.storage)?.is_none() {
+        PENDING_REWARD_BACKFILL.save(
+            deps.storage,
+            &PendingRewardBackfill {
+                start_after: None,
+                total_pending_reward: Uint128::zero(),
+                stream_total_pending_rewards: vec![Uint128::zero(); config.reward_streams.len()],
+            },
+        )?;
+    }
+
     Ok(Response::default())
 }
+
+const DEFAULT_BACKFILL_LIMIT: u32 = 100;
+
+/// Sums the pending rewards of the next page of stakers, and replaces the totals after the last page.
+/// Rewards are not distributed during the backfill, so the pending rewards are summed at the same index.
+pub fn backfill_pending_rewards(
+    deps: DepsMut,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut backfill = PENDING_REWARD_BACKFILL
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("No pending reward backfill in progress"))?;
+    let mut state: State = STATE.load(deps.storage)?;
+
+    let limit = limit.unwrap_or(DEFAULT_BACKFILL_LIMIT) as usize;
+    let start = backfill.start_after.as_ref().map(Bound::exclusive);
+    let reward_infos = REWARD_INFOS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    let count = reward_infos.len();
+    for (staker_addr, mut reward_info) in reward_infos {
+        compute_staker_reward(deps.storage, &state, &mut reward_info, &[])?;
+        backfill.total_pending_reward += reward_info.pending_reward;
+        for (total, pending_reward) in backfill
+            .stream_total_pending_rewards
+            .iter_mut()
+            .zip(reward_info.stream_pending_rewards.iter())
+        {
+            *total += *pending_reward;
+        }
+        backfill.start_after = Some(staker_addr);
+    }
+
+    let done = count < limit;
+    if done {
+        state.total_pending_reward = backfill.total_pending_reward;
+        state.stream_total_pending_rewards = backfill.stream_total_pending_rewards;
+        state.pending_reward_tracked = true;
+        STATE.save(deps.storage, &state)?;
+        PENDING_REWARD_BACKFILL.remove(deps.storage);
+    } else {
+        PENDING_REWARD_BACKFILL.save(deps.storage, &backfill)?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "backfill_pending_rewards"),
+        ("count", count.to_string().as_str()),
+        ("done", done.to_string().as_str()),
+    ]))
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError};
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 /// ## Description
@@ -22,6 +22,30 @@ pub enum ContractError {
 
     #[error("New distribution schedule already started")]
     DistributionScheduleStarted {},
+
+    #[error("Cannot unbond locked amount")]
+    UnbondLocked {},
+
+    #[error("No lock tier with this lock duration")]
+    InvalidLockDuration {},
+
+    #[error("Lock tiers must have unique positive durations and multipliers of at least 1")]
+    InvalidLockTiers {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("Contract balance does not cover the distribution schedule")]
+    ScheduleNotFunded {},
+
+    #[error("Fund amount must be at least {0}")]
+    FundAmountTooSmall(Uint128),
+
+    #[error("Cannot have more than {0} active distribution schedules")]
+    TooManySchedules(usize),
+
+    #[error("Pending rewards are being backfilled")]
+    BackfillInProgress {},
 }
 
 impl From<OverflowError> for ContractError {
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::marker::PhantomData;
 
 use schemars::JsonSchema;
@@ -6,9 +7,9 @@ use serde::{Deserialize, Serialize};
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
     from_binary, from_slice, to_binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
-    QuerierResult, QueryRequest, SystemError, SystemResult, WasmQuery,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
 };
-use cw20::MinterResponse;
+use cw20::{BalanceResponse, MinterResponse};
 
 /// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
 /// this uses our CustomQuerier.
@@ -29,6 +30,7 @@ pub fn mock_dependencies(
 pub struct WasmMockQuerier {
     base: MockQuerier<Empty>,
     minter_querier: MinterQuerier,
+    token_querier: TokenQuerier,
 }
 
 #[derive(Clone, Default)]
@@ -36,10 +38,16 @@ pub struct MinterQuerier {
     minter_addr: String,
 }
 
+#[derive(Clone, Default)]
+pub struct TokenQuerier {
+    balances: HashMap<String, HashMap<String, Uint128>>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     Minter {},
+    Balance { address: String },
 }
 
 impl Querier for WasmMockQuerier {
@@ -62,7 +70,7 @@ impl WasmMockQuerier {
     pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         match &request {
             QueryRequest::Wasm(WasmQuery::Smart {
-                contract_addr: _,
+                contract_addr,
                 msg,
             }) => match from_binary(msg) {
                 Ok(QueryMsg::Minter {}) => {
@@ -71,6 +79,18 @@ impl WasmMockQuerier {
                         cap: None,
                     })))
                 }
+                Ok(QueryMsg::Balance { address }) => {
+                    let balance = self
+                        .token_querier
+                        .balances
+                        .get(contract_addr)
+                        .and_then(|balances| balances.get(&address))
+                        .cloned()
+                        .unwrap_or_default();
+                    SystemResult::Ok(ContractResult::from(to_binary(&BalanceResponse {
+                        balance,
+                    })))
+                }
                 _ => panic!("query not mocked"),
             },
             _ => self.base.handle_query(request),
@@ -83,6 +103,21 @@ impl WasmMockQuerier {
         WasmMockQuerier {
             base,
             minter_querier: MinterQuerier::default(),
+            token_querier: TokenQuerier::default(),
+        }
+    }
+
+    // configure the token balances
+    pub fn with_token_balances(&mut self, balances: &[(&str, &[(&str, Uint128)])]) {
+        for (contract_addr, balances) in balances.iter() {
+            let contract_balances = self
+                .token_querier
+                .balances
+                .entry(contract_addr.to_string())
+                .or_default();
+            for (addr, balance) in balances.iter() {
+                contract_balances.insert(addr.to_string(), *balance);
+            }
         }
     }
 }
@@ -1,27 +1,126 @@
-use cw_storage_plus::{Item, Map, Bound};
+use cw_storage_plus::{Item, Map, Bound, SnapshotItem, SnapshotMap, Strategy};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{Decimal, StdResult, Storage, Uint128, Addr, Deps, Order};
 
+use astroport::asset::AssetInfo;
+use baz::lp_staking::{Lock, LockTier, RewardStream, Unbonding};
+
 use crate::ownership::OwnershipProposal;
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const STATE: Item<State> = Item::new("state");
 pub const REWARD_INFOS: Map<&Addr, RewardInfo> = Map::new("reward_infos");
+pub const UNBONDINGS: Map<&Addr, Vec<Unbonding>> = Map::new("unbondings");
+
+/// The locks expiring at each unlock time
+pub const LOCK_EXPIRIES: Map<u64, LockExpiry> = Map::new("lock_expiries");
+
+/// Checkpoints of the bond amount of each staker by block height
+pub const BOND_AMOUNTS: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "bond_amounts",
+    "bond_amounts__checkpoints",
+    "bond_amounts__changelog",
+    Strategy::EveryBlock,
+);
+
+/// The block height the bond checkpoints started at, set when migrating a contract with stakers
+pub const BOND_CHECKPOINT_HEIGHT: Item<u64> = Item::new("bond_checkpoint_height");
+
+/// Checkpoints of the total bond amount by block height
+pub const TOTAL_BOND_AMOUNT: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_bond_amount",
+    "total_bond_amount__checkpoints",
+    "total_bond_amount__changelog",
+    Strategy::EveryBlock,
+);
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
+    pub owner: Addr,
+    pub reward_token: AssetInfo,
+    pub staking_token: AssetInfo,
+    pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+    #[serde(default)]
+    pub reward_streams: Vec<RewardStream>,
+    #[serde(default)]
+    pub lock_tiers: Vec<LockTier>,
+    /// The seconds unbonded tokens wait before they can be claimed
+    #[serde(default)]
+    pub unbonding_period: u64,
+    /// The minimum amount to fund a schedule with, in the units of the funded asset
+    #[serde(default)]
+    pub min_fund_amount: Uint128,
+}
+
+/// This structure describes the config of the contract when both tokens were cw20.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
     pub owner: Addr,
     pub reward_token: Addr,
     pub staking_token: Addr,
     pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+    #[serde(default)]
+    pub reward_streams: Vec<RewardStream>,
 }
+
+/// Stores legacy config at the same key as [`CONFIG`]
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub last_distributed: u64,
     pub total_bond_amount: Uint128,
     pub global_reward_index: Decimal,
+    /// The global reward index of each reward stream, in the order of `Config::reward_streams`
+    #[serde(default)]
+    pub stream_reward_indexes: Vec<Decimal>,
+    /// The extra weight of all locks on top of their amount
+    #[serde(default)]
+    pub total_lock_bonus: Uint128,
+    /// The total amount waiting in the unbonding queues
+    #[serde(default)]
+    pub total_unbonding_amount: Uint128,
+    /// The reward distributed to stakers but not withdrawn yet
+    #[serde(default)]
+    pub total_pending_reward: Uint128,
+    /// The reward distributed to stakers but not withdrawn yet of each reward stream
+    #[serde(default)]
+    pub stream_total_pending_rewards: Vec<Uint128>,
+    /// Whether the pending reward totals include the rewards from before they were tracked
+    #[serde(default)]
+    pub pending_reward_tracked: bool,
+}
+
+impl State {
+    /// Returns the total bond amount weighted by the lock multipliers
+    pub fn total_weighted_bond_amount(&self) -> Uint128 {
+        self.total_bond_amount + self.total_lock_bonus
+    }
+}
+
+/// This structure holds the progress of summing the pending rewards of stakers into the totals
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct PendingRewardBackfill {
+    /// The last staker summed
+    pub start_after: Option<Addr>,
+    pub total_pending_reward: Uint128,
+    pub stream_total_pending_rewards: Vec<Uint128>,
+}
+
+/// Stores the pending reward backfill while it is in progress
+pub const PENDING_REWARD_BACKFILL: Item<PendingRewardBackfill> = Item::new("pending_reward_backfill");
+
+/// This structure describes the locks expiring at the same time.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct LockExpiry {
+    /// The extra weight of the locks on top of their amount
+    pub lock_bonus: Uint128,
+    /// The global reward index when the bonus stopped, set once the unlock time passed
+    pub reward_index: Option<Decimal>,
+    /// The global reward index of each reward stream when the bonus stopped
+    pub stream_reward_indexes: Vec<Decimal>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -29,6 +128,48 @@ pub struct RewardInfo {
     pub reward_index: Decimal,
     pub bond_amount: Uint128,
     pub pending_reward: Uint128,
+    /// The reward index of each reward stream, in the order of `Config::reward_streams`
+    #[serde(default)]
+    pub stream_reward_indexes: Vec<Decimal>,
+    /// The pending reward of each reward stream, in the order of `Config::reward_streams`
+    #[serde(default)]
+    pub stream_pending_rewards: Vec<Uint128>,
+    /// The locked parts of bond_amount
+    #[serde(default)]
+    pub locks: Vec<Lock>,
+}
+
+impl RewardInfo {
+    /// Returns true when nothing is bonded and no reward is pending
+    pub fn is_empty(&self) -> bool {
+        self.bond_amount.is_zero()
+            && self.pending_reward.is_zero()
+            && self.stream_pending_rewards.iter().all(|it| it.is_zero())
+    }
+
+    /// Returns the extra weight of the locks on top of their amount
+    pub fn lock_bonus(&self) -> Uint128 {
+        self.locks.iter().map(lock_bonus).sum()
+    }
+
+    /// Returns the bond amount weighted by the lock multipliers
+    pub fn weighted_bond_amount(&self) -> Uint128 {
+        self.bond_amount + self.lock_bonus()
+    }
+
+    /// Returns the amount which is still locked at the given time
+    pub fn locked_amount(&self, time_seconds: u64) -> Uint128 {
+        self.locks
+            .iter()
+            .filter(|it| it.unlock_time > time_seconds)
+            .map(|it| it.amount)
+            .sum()
+    }
+}
+
+/// Returns the extra weight of the lock on top of its amount
+pub fn lock_bonus(lock: &Lock) -> Uint128 {
+    lock.amount * (lock.multiplier - Decimal::one())
 }
 
 /// returns rewards owned by this owner
@@ -39,6 +180,9 @@ pub fn read_reward_info(storage: &dyn Storage, owner: &Addr) -> StdResult<Reward
             reward_index: Decimal::zero(),
             bond_amount: Uint128::zero(),
             pending_reward: Uint128::zero(),
+            stream_reward_indexes: vec![],
+            stream_pending_rewards: vec![],
+            locks: vec![],
         }),
     }
 }
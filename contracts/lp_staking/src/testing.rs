@@ -1,17 +1,19 @@
 use std::str::FromStr;
 
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query};
 use crate::error::ContractError;
 use crate::mock_querier::mock_dependencies;
-use crate::state::Config;
+use crate::state::{read_reward_info, REWARD_INFOS, STATE};
+use astroport::asset::{AssetInfo, AssetInfoExt};
 use baz::lp_staking::ExecuteMsg::UpdateConfig;
 use baz::lp_staking::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, RewardInfoResponse,
-    StateResponse, RewardInfoResponseItem,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, FundingResponse, InstantiateMsg, Lock, LockTier, LocksResponse,
+    MigrateMsg, QueryMsg, RewardInfoResponse, StateResponse, RewardFunding, RewardInfoResponseItem, Unbonding,
+    UnbondingsResponse,
 };
-use cosmwasm_std::testing::{mock_env, mock_info};
+use cosmwasm_std::testing::{mock_env, mock_info, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, to_binary, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg, Timestamp, Response,
+    from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, Deps, StdError, SubMsg, Uint128, WasmMsg, Timestamp, Response,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
@@ -32,8 +34,8 @@ fn proper_initialization() {
 
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
-        reward_token: "reward0000".to_string(),
-        staking_token: "staking0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
         distribution_schedule: vec![(100, 200, Uint128::from(1000000u128))],
     };
 
@@ -51,7 +53,13 @@ fn proper_initialization() {
             owner: "owner0000".to_string(),
             reward_token: "reward0000".to_string(),
             staking_token: "staking0000".to_string(),
+            reward_token_info: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+            staking_token_info: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
             distribution_schedule: vec![(100, 200, Uint128::from(1000000u128))],
+            reward_streams: vec![],
+            lock_tiers: vec![],
+            unbonding_period: 0,
+            min_fund_amount: Uint128::zero(),
         }
     );
 
@@ -69,7 +77,9 @@ fn proper_initialization() {
         StateResponse {
             last_distributed: mock_env().block.time.seconds(),
             total_bond_amount: Uint128::zero(),
+            total_weighted_bond_amount: Uint128::zero(),
             global_reward_index: Decimal::zero(),
+            stream_reward_indexes: vec![],
         }
     );
 }
@@ -79,8 +89,8 @@ fn test_bond_tokens() {
     let mut deps = mock_dependencies(&[]);
 
     let msg = InstantiateMsg {
-        reward_token: "reward0000".to_string(),
-        staking_token: "staking0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
         owner: "owner0000".to_string(),
         distribution_schedule: vec![
             (
@@ -102,7 +112,7 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None }).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
     });
 
     let info = mock_info("staking0000", &[]);
@@ -129,6 +139,7 @@ fn test_bond_tokens() {
                 reward_index: Decimal::zero(),
                 pending_reward: Uint128::zero(),
                 bond_amount: Uint128::from(100u128),
+                stream_pending_rewards: vec![],
         }
         }
     );
@@ -147,8 +158,10 @@ fn test_bond_tokens() {
         .unwrap(),
         StateResponse {
             total_bond_amount: Uint128::from(100u128),
+            total_weighted_bond_amount: Uint128::from(100u128),
             global_reward_index: Decimal::zero(),
             last_distributed: mock_env().block.time.seconds(),
+            stream_reward_indexes: vec![],
         }
     );
 
@@ -156,7 +169,7 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
     });
     env.block.time = env.block.time.plus_seconds(10);
 
@@ -182,6 +195,7 @@ fn test_bond_tokens() {
                 reward_index: Decimal::from_ratio(1000u128, 1u128),
                 pending_reward: Uint128::from(100000u128),
                 bond_amount: Uint128::from(200u128),
+                stream_pending_rewards: vec![],
             }
         }
     );
@@ -200,8 +214,10 @@ fn test_bond_tokens() {
         .unwrap(),
         StateResponse {
             total_bond_amount: Uint128::from(200u128),
+            total_weighted_bond_amount: Uint128::from(200u128),
             global_reward_index: Decimal::from_ratio(1000u128, 1u128),
             last_distributed: mock_env().block.time.seconds() + 10,
+            stream_reward_indexes: vec![],
         }
     );
 
@@ -209,7 +225,7 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: Some("addr0001".to_string()) }).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: Some("addr0001".to_string()), lock_duration: None }).unwrap(),
     });
 
     let info = mock_info("staking0000", &[]);
@@ -235,6 +251,7 @@ fn test_bond_tokens() {
                 reward_index: Decimal::from_ratio(1000u128, 1u128),
                 pending_reward: Uint128::zero(),
                 bond_amount: Uint128::from(100u128),
+                stream_pending_rewards: vec![],
         }
         }
     );
@@ -253,8 +270,10 @@ fn test_bond_tokens() {
         .unwrap(),
         StateResponse {
             total_bond_amount: Uint128::from(300u128),
+            total_weighted_bond_amount: Uint128::from(300u128),
             global_reward_index: Decimal::from_ratio(1000u128, 1u128),
             last_distributed: mock_env().block.time.seconds() + 10,
+            stream_reward_indexes: vec![],
         }
     );
 
@@ -282,7 +301,7 @@ fn test_bond_tokens() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
     });
 
     let info = mock_info("staking0001", &[]);
@@ -296,8 +315,8 @@ fn test_unbond() {
 
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
-        reward_token: "reward0000".to_string(),
-        staking_token: "staking0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
         distribution_schedule: vec![
             (12345, 12345 + 100, Uint128::from(1000000u128)),
             (12345 + 100, 12345 + 200, Uint128::from(10000000u128)),
@@ -311,7 +330,7 @@ fn test_unbond() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
     });
     let info = mock_info("staking0000", &[]);
     let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -352,8 +371,8 @@ fn test_compute_reward() {
 
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
-        reward_token: "reward0000".to_string(),
-        staking_token: "staking0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
         distribution_schedule: vec![
             (
                 mock_env().block.time.seconds(),
@@ -375,7 +394,7 @@ fn test_compute_reward() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
     });
     let info = mock_info("staking0000", &[]);
     let mut env = mock_env();
@@ -389,7 +408,7 @@ fn test_compute_reward() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
     });
     let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
 
@@ -412,6 +431,7 @@ fn test_compute_reward() {
                 reward_index: Decimal::from_ratio(10000u128, 1u128),
                 pending_reward: Uint128::from(1000000u128),
                 bond_amount: Uint128::from(200u128),
+                stream_pending_rewards: vec![],
             }
         }
     );
@@ -446,6 +466,7 @@ fn test_compute_reward() {
                 reward_index: Decimal::from_ratio(15000u64, 1u64),
                 pending_reward: Uint128::from(2000000u128),
                 bond_amount: Uint128::from(100u128),
+                stream_pending_rewards: vec![],
             }
         }
     );
@@ -471,6 +492,7 @@ fn test_compute_reward() {
                 reward_index: Decimal::from_ratio(25000u64, 1u64),
                 pending_reward: Uint128::from(3000000u128),
                 bond_amount: Uint128::from(100u128),
+                stream_pending_rewards: vec![],
             }
         }
     );
@@ -482,8 +504,8 @@ fn test_withdraw() {
 
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
-        reward_token: "reward0000".to_string(),
-        staking_token: "staking0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
         distribution_schedule: vec![
             (
                 mock_env().block.time.seconds(),
@@ -505,7 +527,7 @@ fn test_withdraw() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
     });
     let info = mock_info("staking0000", &[]);
     let mut env = mock_env();
@@ -540,7 +562,7 @@ fn test_withdraw() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0001".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
     });
     let info = mock_info("staking0000", &[]);
     let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
@@ -601,6 +623,7 @@ fn test_withdraw() {
                 reward_index: Decimal::from_ratio(60000u64, 1u64),
                 pending_reward: Uint128::from(4_999_800u128),
                 bond_amount: Uint128::from(100u128),
+                stream_pending_rewards: vec![],
             }
         }
     );
@@ -610,11 +633,15 @@ fn test_withdraw() {
 #[test]
 fn test_update_config() {
     let mut deps = mock_dependencies(&[]);
+    deps.querier.with_token_balances(&[(
+        "reward0000",
+        &[(MOCK_CONTRACT_ADDR, Uint128::from(1_000_000_000u128))],
+    )]);
 
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
-        reward_token: "reward0000".to_string(),
-        staking_token: "staking0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
         distribution_schedule: vec![
             (
                 mock_env().block.time.seconds(),
@@ -648,6 +675,8 @@ fn test_update_config() {
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
     let update_config = UpdateConfig {
+        unbonding_period: None,
+        min_fund_amount: None,
         distribution_schedule: Some(vec![(
             mock_env().block.time.seconds() + 300,
             mock_env().block.time.seconds() + 400,
@@ -664,7 +693,7 @@ fn test_update_config() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
     });
     let info = mock_info("staking0000", &[]);
     let mut env = mock_env();
@@ -693,6 +722,8 @@ fn test_update_config() {
     );
 
     let update_config = UpdateConfig {
+        unbonding_period: None,
+        min_fund_amount: None,
         distribution_schedule: Some(vec![
             (
                 mock_env().block.time.seconds(),
@@ -731,7 +762,7 @@ fn test_update_config() {
     let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
         sender: "addr0000".to_string(),
         amount: Uint128::from(100u128),
-        msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
     });
     let info = mock_info("staking0000", &[]);
     let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
@@ -749,6 +780,8 @@ fn test_update_config() {
 
     //cannot update previous scehdule
     let update_config = UpdateConfig {
+        unbonding_period: None,
+        min_fund_amount: None,
         distribution_schedule: Some(vec![
             (
                 mock_env().block.time.seconds(),
@@ -784,6 +817,8 @@ fn test_update_config() {
 
     //successful one
     let update_config = UpdateConfig {
+        unbonding_period: None,
+        min_fund_amount: None,
         distribution_schedule: Some(vec![
             (
                 mock_env().block.time.seconds(),
@@ -855,6 +890,8 @@ fn test_update_config() {
 
     //successful one
     let update_config = UpdateConfig {
+        unbonding_period: None,
+        min_fund_amount: None,
         distribution_schedule: Some(vec![
             (
                 mock_env().block.time.seconds(),
@@ -925,6 +962,8 @@ fn test_update_config() {
     );
 
     let update_config = UpdateConfig {
+        unbonding_period: None,
+        min_fund_amount: None,
         distribution_schedule: Some(vec![
             (
                 mock_env().block.time.seconds(),
@@ -994,6 +1033,8 @@ fn test_update_config() {
     );
 
     let update_config = UpdateConfig {
+        unbonding_period: None,
+        min_fund_amount: None,
         distribution_schedule: Some(vec![
             (
                 mock_env().block.time.seconds(),
@@ -1093,8 +1134,8 @@ fn test_query_all_rewards() {
 
     let msg = InstantiateMsg {
         owner: owner0000.to_string(),
-        reward_token: reward0000.to_string(),
-        staking_token: staking0000.to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked(reward0000) },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked(staking0000) },
         distribution_schedule: vec![
             (
                 mock_env().block.time.seconds(),
@@ -1118,7 +1159,7 @@ fn test_query_all_rewards() {
         let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
             sender: addr.to_string(),
             amount: Uint128::from(100u128),
-            msg: to_binary(&Cw20HookMsg::Bond {staker_addr: None}).unwrap(),
+            msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
         });
         let info = mock_info(staking0000, &[]);
         execute(deps.as_mut(), env.clone(), info, msg).unwrap();
@@ -1167,6 +1208,7 @@ fn test_query_all_rewards() {
                     reward_index: Decimal::from_str("0").unwrap(),
                     pending_reward: Uint128::from(0u128),
                     bond_amount: Uint128::from(100u128),
+                    stream_pending_rewards: vec![],
                 }
             },
             RewardInfoResponse {
@@ -1176,6 +1218,7 @@ fn test_query_all_rewards() {
                     reward_index: Decimal::from_str("0").unwrap(),
                     pending_reward: Uint128::from(0u128),
                     bond_amount: Uint128::from(100u128),
+                    stream_pending_rewards: vec![],
                 }
             },
             RewardInfoResponse {
@@ -1185,6 +1228,7 @@ fn test_query_all_rewards() {
                     reward_index: Decimal::from_str("0").unwrap(),
                     pending_reward: Uint128::from(0u128),
                     bond_amount: Uint128::from(100u128),
+                    stream_pending_rewards: vec![],
                 }
             },
             RewardInfoResponse {
@@ -1194,6 +1238,7 @@ fn test_query_all_rewards() {
                     reward_index: Decimal::from_str("0").unwrap(),
                     pending_reward: Uint128::from(0u128),
                     bond_amount: Uint128::from(100u128),
+                    stream_pending_rewards: vec![],
                 }
             },
             RewardInfoResponse {
@@ -1203,6 +1248,7 @@ fn test_query_all_rewards() {
                     reward_index: Decimal::from_str("1250").unwrap(),
                     pending_reward: Uint128::from(124900u128),
                     bond_amount: Uint128::from(100u128),
+                    stream_pending_rewards: vec![],
                 }
             },
         ]
@@ -1251,6 +1297,7 @@ fn test_query_all_rewards() {
                     reward_index: Decimal::from_str("13750").unwrap(),
                     pending_reward: Uint128::from(1374800u128),
                     bond_amount: Uint128::from(100u128),
+                    stream_pending_rewards: vec![],
                 }
             },
             RewardInfoResponse {
@@ -1260,6 +1307,7 @@ fn test_query_all_rewards() {
                     reward_index: Decimal::from_str("13750").unwrap(),
                     pending_reward: Uint128::from(1375000u128),
                     bond_amount: Uint128::from(100u128),
+                    stream_pending_rewards: vec![],
                 }
             },
         ]
@@ -1277,8 +1325,8 @@ fn owner() {
 
     let msg = InstantiateMsg {
         owner: USER_1.to_string(),
-        reward_token: "reward0000".to_string(),
-        staking_token: "staking0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
         distribution_schedule: vec![(100, 200, Uint128::from(1000000u128))],
     };
 
@@ -1374,7 +1422,999 @@ fn owner() {
     assert_eq!(0, res.messages.len());
 
     // query config
-    let config: Config =
+    let config: ConfigResponse =
         from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
     assert_eq!(OWNER, config.owner);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_reward_streams() {
+    let mut deps = mock_dependencies(&[Coin::new(200_000u128, "uluna")]);
+    deps.querier.with_token_balances(&[(
+        "partner0000",
+        &[(MOCK_CONTRACT_ADDR, Uint128::from(500_000u128))],
+    )]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint128::from(1_000_000u128),
+        )],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let partner_token = AssetInfo::Token {
+        contract_addr: Addr::unchecked("partner0000"),
+    };
+    let native_token = AssetInfo::NativeToken {
+        denom: "uluna".to_string(),
+    };
+
+    // only owner can add a stream
+    let msg = ExecuteMsg::UpdateRewardStream {
+        asset_info: partner_token.clone(),
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds() + 1,
+            mock_env().block.time.seconds() + 51,
+            Uint128::from(500_000u128),
+        )],
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // reward token cannot be a stream
+    let msg = ExecuteMsg::UpdateRewardStream {
+        asset_info: AssetInfo::Token {
+            contract_addr: Addr::unchecked("reward0000"),
+        },
+        distribution_schedule: vec![],
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "reward token is distributed by distribution_schedule");
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let mut env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 50 seconds passed, add a native stream
+    env.block.time = env.block.time.plus_seconds(50);
+    let msg = ExecuteMsg::UpdateRewardStream {
+        asset_info: native_token.clone(),
+        distribution_schedule: vec![(
+            env.block.time.seconds() + 1,
+            env.block.time.seconds() + 50,
+            Uint128::from(200_000u128),
+        )],
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 50 more seconds passed
+    env.block.time = env.block.time.plus_seconds(50);
+    assert_eq!(
+        from_binary::<RewardInfoResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::RewardInfo {
+                    staker_addr: "addr0000".to_string(),
+                    time_seconds: Some(env.block.time.seconds()),
+                },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        RewardInfoResponse {
+            staker_addr: "addr0000".to_string(),
+            reward_info: RewardInfoResponseItem {
+                staking_token: "staking0000".to_string(),
+                reward_index: Decimal::from_ratio(10000u128, 1u128),
+                pending_reward: Uint128::from(1_000_000u128),
+                bond_amount: Uint128::from(100u128),
+                stream_pending_rewards: vec![
+                    partner_token.with_balance(500_000u128),
+                    native_token.with_balance(200_000u128),
+                ],
+            }
+        }
+    );
+
+    // withdraw pays out every reward asset
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Withdraw { amount: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "reward0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(1_000_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "partner0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(500_000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: vec![Coin {
+                    denom: "uluna".to_string(),
+                    amount: Uint128::from(200_000u128),
+                }],
+            })),
+        ]
+    );
+
+    // existing stream schedule cannot be removed
+    let msg = ExecuteMsg::UpdateRewardStream {
+        asset_info: partner_token,
+        distribution_schedule: vec![],
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert_error(res, "Cannot update; the new schedule must support all of the previous schedule");
+}
+
+#[test]
+fn test_native_tokens() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: AssetInfo::NativeToken { denom: "uluna".to_string() },
+        staking_token: AssetInfo::NativeToken { denom: "ustake".to_string() },
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint128::from(1_000_000u128),
+        )],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // cw20 bond is not allowed for native staking token
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let info = mock_info("ustake", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Unauthorized");
+
+    // bond with wrong denom
+    let msg = ExecuteMsg::Bond { staker_addr: None, lock_duration: None };
+    let info = mock_info("addr0000", &[Coin::new(100u128, "uluna")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "must send ustake only");
+
+    // bond 100 tokens
+    let info = mock_info("addr0000", &[Coin::new(100u128, "ustake")]);
+    let mut env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 100 seconds passed
+    // 1,000,000 rewards distributed
+    env.block.time = env.block.time.plus_seconds(100);
+
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Withdraw { amount: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![Coin::new(1_000_000u128, "uluna")],
+        }))]
+    );
+
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Unbond { amount: Uint128::from(100u128) };
+    let res = execute(deps.as_mut(), env, info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+            to_address: "addr0000".to_string(),
+            amount: vec![Coin::new(100u128, "ustake")],
+        }))]
+    );
+}
+
+#[test]
+fn test_lock_tiers() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint128::from(1_000_000u128),
+        )],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // multiplier must be at least 1
+    let msg = ExecuteMsg::UpdateLockTiers {
+        lock_tiers: vec![LockTier { duration: 100, multiplier: Decimal::percent(50) }],
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Lock tiers must have unique positive durations and multipliers of at least 1");
+
+    let msg = ExecuteMsg::UpdateLockTiers {
+        lock_tiers: vec![LockTier { duration: 100, multiplier: Decimal::percent(200) }],
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // unknown lock duration
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: Some(50) }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "No lock tier with this lock duration");
+
+    // addr0000 bond 100 tokens unlocked, addr0001 bond 100 tokens locked at 2x
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let mut env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: Some(100) }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    assert_eq!(
+        from_binary::<LocksResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Locks { staker_addr: "addr0001".to_string() },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        LocksResponse {
+            staker_addr: "addr0001".to_string(),
+            weighted_bond_amount: Uint128::from(200u128),
+            locks: vec![Lock {
+                amount: Uint128::from(100u128),
+                multiplier: Decimal::percent(200),
+                unlock_time: env.block.time.seconds() + 100,
+            }],
+        }
+    );
+
+    // 50 seconds passed
+    // 500,000 rewards distributed, 1/3 to addr0000 and 2/3 to addr0001
+    env.block.time = env.block.time.plus_seconds(50);
+
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RewardInfo {
+                staker_addr: "addr0000".to_string(),
+                time_seconds: Some(env.block.time.seconds()),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_info.pending_reward, Uint128::from(166_666u128));
+
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RewardInfo {
+                staker_addr: "addr0001".to_string(),
+                time_seconds: Some(env.block.time.seconds()),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_info.pending_reward, Uint128::from(333_333u128));
+
+    // locked amount cannot be unbonded before expiry
+    let msg = ExecuteMsg::Unbond { amount: Uint128::from(100u128) };
+    let info = mock_info("addr0001", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "Cannot unbond locked amount");
+
+    // 50 seconds passed, lock expired
+    env.block.time = env.block.time.plus_seconds(50);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let res: StateResponse = from_binary(
+        &query(deps.as_ref(), env, QueryMsg::State { time_seconds: None }).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.total_bond_amount, Uint128::from(100u128));
+    assert_eq!(res.total_weighted_bond_amount, Uint128::from(100u128));
+}
+
+#[test]
+fn test_expired_lock_idle() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint128::from(1_000_000u128),
+        )],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::UpdateLockTiers {
+        lock_tiers: vec![LockTier { duration: 50, multiplier: Decimal::percent(200) }],
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // addr0000 bond 100 tokens unlocked, addr0001 bond 100 tokens locked at 2x for 50 seconds
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let mut env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: Some(50) }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 100 seconds passed without any action, the lock expired in the middle
+    // first 500,000 rewards split 1/3 and 2/3, next 500,000 split 1/2 and 1/2
+    env.block.time = env.block.time.plus_seconds(100);
+
+    let res: StateResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::State { time_seconds: Some(env.block.time.seconds()) },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.total_weighted_bond_amount, Uint128::from(200u128));
+
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RewardInfo {
+                staker_addr: "addr0000".to_string(),
+                time_seconds: Some(env.block.time.seconds()),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_info.pending_reward, Uint128::from(416_666u128));
+
+    let res: RewardInfoResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::RewardInfo {
+                staker_addr: "addr0001".to_string(),
+                time_seconds: Some(env.block.time.seconds()),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.reward_info.pending_reward, Uint128::from(583_333u128));
+
+    // the lock bonus is not paid after the unlock time
+    let info = mock_info("addr0001", &[]);
+    let msg = ExecuteMsg::Withdraw { amount: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0001".to_string(),
+                amount: Uint128::from(583_333u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let res: LocksResponse = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Locks { staker_addr: "addr0001".to_string() },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.weighted_bond_amount, Uint128::from(100u128));
+    assert_eq!(res.locks, vec![]);
+
+    let res: StateResponse = from_binary(
+        &query(deps.as_ref(), env, QueryMsg::State { time_seconds: None }).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.total_weighted_bond_amount, Uint128::from(200u128));
+}
+
+#[test]
+fn test_unbonding_period() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint128::from(1_000_000u128),
+        )],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = UpdateConfig {
+        distribution_schedule: None,
+        unbonding_period: Some(100),
+        min_fund_amount: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let mut env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // unbond 60 tokens after 10 seconds, nothing is transferred
+    env.block.time = env.block.time.plus_seconds(10);
+    let msg = ExecuteMsg::Unbond { amount: Uint128::from(60u128) };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(res.messages, vec![]);
+
+    // unbond the rest, unbonding tokens are not bonded anymore
+    env.block.time = env.block.time.plus_seconds(10);
+    let msg = ExecuteMsg::Unbond { amount: Uint128::from(40u128) };
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    let res: StateResponse = from_binary(
+        &query(deps.as_ref(), env.clone(), QueryMsg::State { time_seconds: None }).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.total_bond_amount, Uint128::zero());
+
+    // nothing is claimable before the unbonding period
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ClaimUnbonded {});
+    assert_error(res, "Nothing to claim");
+
+    // first unbonding matured
+    env.block.time = env.block.time.plus_seconds(90);
+    assert_eq!(
+        from_binary::<UnbondingsResponse>(
+            &query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::Unbondings { staker_addr: "addr0000".to_string() },
+            )
+            .unwrap()
+        )
+        .unwrap(),
+        UnbondingsResponse {
+            staker_addr: "addr0000".to_string(),
+            pending: vec![Unbonding {
+                amount: Uint128::from(40u128),
+                claimable_time: mock_env().block.time.seconds() + 120,
+            }],
+            claimable_amount: Uint128::from(60u128),
+        }
+    );
+
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), ExecuteMsg::ClaimUnbonded {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "staking0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(60u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    // claimed unbonding is removed from the queue
+    env.block.time = env.block.time.plus_seconds(10);
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::ClaimUnbonded {}).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "staking0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(40u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+}
+
+#[test]
+fn test_fund_schedule() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint128::from(1_000_000u128),
+        )],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // nothing is funded yet
+    let res: FundingResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Funding {}).unwrap()).unwrap();
+    assert_eq!(
+        res.reward,
+        RewardFunding {
+            asset_info: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+            remaining_amount: Uint128::from(1_000_000u128),
+            unfunded_amount: Uint128::from(1_000_000u128),
+        }
+    );
+
+    // owner cannot add an unfunded schedule
+    deps.querier.with_token_balances(&[(
+        "reward0000",
+        &[(MOCK_CONTRACT_ADDR, Uint128::from(1_000_000u128))],
+    )]);
+    let msg = UpdateConfig {
+        distribution_schedule: Some(vec![
+            (
+                mock_env().block.time.seconds(),
+                mock_env().block.time.seconds() + 100,
+                Uint128::from(1_000_000u128),
+            ),
+            (
+                mock_env().block.time.seconds() + 100,
+                mock_env().block.time.seconds() + 200,
+                Uint128::from(1_000_000u128),
+            ),
+        ]),
+        unbonding_period: None,
+        min_fund_amount: None,
+    };
+    let info = mock_info("owner0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "Contract balance does not cover the distribution schedule");
+
+    // schedule must not be started
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "sponsor0000".to_string(),
+        amount: Uint128::from(1_000_000u128),
+        msg: to_binary(&Cw20HookMsg::FundSchedule {
+            start: mock_env().block.time.seconds(),
+            end: mock_env().block.time.seconds() + 100,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "New distribution schedule already started");
+
+    // only reward assets can fund a schedule
+    let msg = ExecuteMsg::FundSchedule {
+        start: mock_env().block.time.seconds() + 100,
+        end: mock_env().block.time.seconds() + 200,
+    };
+    let info = mock_info("sponsor0000", &[Coin::new(1_000_000u128, "uluna")]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    assert_error(res, "asset is not a reward of this contract");
+
+    // sponsor funds a schedule
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "sponsor0000".to_string(),
+        amount: Uint128::from(1_000_000u128),
+        msg: to_binary(&Cw20HookMsg::FundSchedule {
+            start: mock_env().block.time.seconds() + 100,
+            end: mock_env().block.time.seconds() + 200,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    deps.querier.with_token_balances(&[(
+        "reward0000",
+        &[(MOCK_CONTRACT_ADDR, Uint128::from(2_000_000u128))],
+    )]);
+
+    let res: ConfigResponse =
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(
+        res.distribution_schedule,
+        vec![
+            (
+                mock_env().block.time.seconds(),
+                mock_env().block.time.seconds() + 100,
+                Uint128::from(1_000_000u128),
+            ),
+            (
+                mock_env().block.time.seconds() + 100,
+                mock_env().block.time.seconds() + 200,
+                Uint128::from(1_000_000u128),
+            ),
+        ]
+    );
+
+    // bond 100 tokens
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let mut env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // 150 seconds passed
+    // 1,500,000 rewards distributed but not withdrawn
+    env.block.time = env.block.time.plus_seconds(150);
+    deps.querier.with_token_balances(&[(
+        "reward0000",
+        &[(MOCK_CONTRACT_ADDR, Uint128::from(1_900_000u128))],
+    )]);
+    let res: FundingResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Funding {}).unwrap()).unwrap();
+    assert_eq!(res.reward.remaining_amount, Uint128::from(500_000u128));
+    assert_eq!(res.reward.unfunded_amount, Uint128::from(100_000u128));
+
+    // withdraw 1,500,000 rewards
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Withdraw { amount: None };
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+    deps.querier.with_token_balances(&[(
+        "reward0000",
+        &[(MOCK_CONTRACT_ADDR, Uint128::from(400_000u128))],
+    )]);
+    let res: FundingResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Funding {}).unwrap()).unwrap();
+    assert_eq!(res.reward.remaining_amount, Uint128::from(500_000u128));
+    assert_eq!(res.reward.unfunded_amount, Uint128::from(100_000u128));
+
+    // owner sets the minimum fund amount
+    let msg = UpdateConfig {
+        distribution_schedule: None,
+        unbonding_period: None,
+        min_fund_amount: Some(Uint128::from(1_000u128)),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let fund_msg = |start: u64, amount: u128| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "sponsor0000".to_string(),
+            amount: Uint128::from(amount),
+            msg: to_binary(&Cw20HookMsg::FundSchedule { start, end: start + 100 }).unwrap(),
+        })
+    };
+    let start = env.block.time.seconds() + 100;
+    let info = mock_info("reward0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), fund_msg(start, 999));
+    assert_error(res, "Fund amount must be at least 1000");
+
+    // the ended schedule is pruned, the active schedules are capped
+    for _ in 0..9 {
+        let _res = execute(deps.as_mut(), env.clone(), info.clone(), fund_msg(start, 1_000)).unwrap();
+    }
+    let res: ConfigResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(res.distribution_schedule.len(), 10);
+    assert_eq!(res.distribution_schedule[0].0, mock_env().block.time.seconds() + 100);
+    let res = execute(deps.as_mut(), env, info, fund_msg(start, 1_000));
+    assert_error(res, "Cannot have more than 10 active distribution schedules");
+}
+
+#[test]
+fn test_migrate_pending_rewards() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
+        distribution_schedule: vec![(
+            mock_env().block.time.seconds(),
+            mock_env().block.time.seconds() + 100,
+            Uint128::from(1_000_000u128),
+        )],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // bond 100 tokens twice, 500,000 rewards are pending in between
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let mut env = mock_env();
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    env.block.time = env.block.time.plus_seconds(50);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // the contract before the upgrade did not track the pending rewards
+    let mut state = STATE.load(deps.as_ref().storage).unwrap();
+    state.total_pending_reward = Uint128::zero();
+    state.stream_total_pending_rewards = vec![];
+    state.pending_reward_tracked = false;
+    STATE.save(deps.as_mut().storage, &state).unwrap();
+
+    let _res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+
+    // other messages are rejected until the backfill is done
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Withdraw { amount: None };
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "Pending rewards are being backfilled");
+
+    let msg = ExecuteMsg::BackfillPendingRewards { limit: Some(1) };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // the first page is full, the second page finishes the backfill
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    let state = STATE.load(deps.as_ref().storage).unwrap();
+    assert_eq!(state.total_pending_reward, Uint128::zero());
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    let state = STATE.load(deps.as_ref().storage).unwrap();
+    assert_eq!(state.total_pending_reward, Uint128::from(500_000u128));
+    assert!(state.pending_reward_tracked);
+
+    // the backfill runs only once
+    let _res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+    let res = execute(deps.as_mut(), env.clone(), info, msg);
+    assert_error(res, "No pending reward backfill in progress");
+
+    // the pending rewards are not available for the schedule
+    deps.querier.with_token_balances(&[(
+        "reward0000",
+        &[(MOCK_CONTRACT_ADDR, Uint128::from(900_000u128))],
+    )]);
+    let res: FundingResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Funding {}).unwrap()).unwrap();
+    assert_eq!(res.reward.remaining_amount, Uint128::from(500_000u128));
+    assert_eq!(res.reward.unfunded_amount, Uint128::from(100_000u128));
+
+    // withdraw the rewards
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::Withdraw { amount: None };
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let state = STATE.load(deps.as_ref().storage).unwrap();
+    assert_eq!(state.total_pending_reward, Uint128::zero());
+}
+
+#[test]
+fn test_bond_snapshots() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
+        distribution_schedule: vec![],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // addr0000 bond 100 tokens at height + 1
+    let mut env = mock_env();
+    env.block.height += 1;
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // addr0001 bond 50 tokens at height + 2
+    env.block.height += 1;
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    // addr0000 unbond 40 tokens at height + 3
+    env.block.height += 1;
+    let msg = ExecuteMsg::Unbond { amount: Uint128::from(40u128) };
+    let info = mock_info("addr0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+    let height = mock_env().block.height;
+    let bond_amount_at = |deps: Deps, staker: &str, height: u64| -> Uint128 {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::BondAmountAt { staker: staker.to_string(), height },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let total_bond_at = |deps: Deps, height: u64| -> Uint128 {
+        from_binary(&query(deps, mock_env(), QueryMsg::TotalBondAt { height }).unwrap()).unwrap()
+    };
+
+    assert_eq!(bond_amount_at(deps.as_ref(), "addr0000", height + 1), Uint128::zero());
+    assert_eq!(bond_amount_at(deps.as_ref(), "addr0000", height + 2), Uint128::from(100u128));
+    assert_eq!(bond_amount_at(deps.as_ref(), "addr0000", height + 4), Uint128::from(60u128));
+    assert_eq!(bond_amount_at(deps.as_ref(), "addr0001", height + 2), Uint128::zero());
+    assert_eq!(bond_amount_at(deps.as_ref(), "addr0001", height + 3), Uint128::from(50u128));
+
+    assert_eq!(total_bond_at(deps.as_ref(), height + 1), Uint128::zero());
+    assert_eq!(total_bond_at(deps.as_ref(), height + 2), Uint128::from(100u128));
+    assert_eq!(total_bond_at(deps.as_ref(), height + 3), Uint128::from(150u128));
+    assert_eq!(total_bond_at(deps.as_ref(), height + 4), Uint128::from(110u128));
+}
+
+#[test]
+fn test_migrate_bond_snapshots() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        reward_token: AssetInfo::Token { contract_addr: Addr::unchecked("reward0000") },
+        staking_token: AssetInfo::Token { contract_addr: Addr::unchecked("staking0000") },
+        distribution_schedule: vec![],
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // addr0000 bonded 100 tokens before the checkpoints existed
+    let staker_addr = Addr::unchecked("addr0000");
+    let mut reward_info = read_reward_info(deps.as_ref().storage, &staker_addr).unwrap();
+    reward_info.bond_amount = Uint128::from(100u128);
+    REWARD_INFOS.save(deps.as_mut().storage, &staker_addr, &reward_info).unwrap();
+    let mut state = STATE.load(deps.as_ref().storage).unwrap();
+    state.total_bond_amount = Uint128::from(100u128);
+    STATE.save(deps.as_mut().storage, &state).unwrap();
+
+    let mut env = mock_env();
+    let _res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+
+    // addr0001 bond 50 tokens at height + 1
+    env.block.height += 1;
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0001".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let info = mock_info("staking0000", &[]);
+    let _res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // addr0000 bond 30 more tokens at height + 2
+    env.block.height += 1;
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(30u128),
+        msg: to_binary(&Cw20HookMsg::Bond { staker_addr: None, lock_duration: None }).unwrap(),
+    });
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+
+    let height = mock_env().block.height;
+    let bond_amount_at = |deps: Deps, staker: &str, height: u64| -> Uint128 {
+        from_binary(
+            &query(
+                deps,
+                mock_env(),
+                QueryMsg::BondAmountAt { staker: staker.to_string(), height },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let total_bond_at = |deps: Deps, height: u64| -> Uint128 {
+        from_binary(&query(deps, mock_env(), QueryMsg::TotalBondAt { height }).unwrap()).unwrap()
+    };
+
+    assert_eq!(bond_amount_at(deps.as_ref(), "addr0000", height + 1), Uint128::from(100u128));
+    assert_eq!(bond_amount_at(deps.as_ref(), "addr0000", height + 2), Uint128::from(100u128));
+    assert_eq!(bond_amount_at(deps.as_ref(), "addr0000", height + 3), Uint128::from(130u128));
+    assert_eq!(bond_amount_at(deps.as_ref(), "addr0001", height + 1), Uint128::zero());
+    assert_eq!(bond_amount_at(deps.as_ref(), "addr0001", height + 2), Uint128::from(50u128));
+
+    assert_eq!(total_bond_at(deps.as_ref(), height + 1), Uint128::from(100u128));
+    assert_eq!(total_bond_at(deps.as_ref(), height + 2), Uint128::from(150u128));
+    assert_eq!(total_bond_at(deps.as_ref(), height + 3), Uint128::from(180u128));
+}
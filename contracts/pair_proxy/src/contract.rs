@@ -1,21 +1,37 @@
 use crate::error::ContractError;
-use crate::state::{Config, CONFIG};
+use crate::state::{
+    read_route_history, save_route_version, Config, Route, CONFIG, LEGACY_CONFIG,
+    OWNERSHIP_PROPOSAL,
+};
 use std::collections::HashSet;
 
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
 use astroport::factory::PairType;
-use astroport::pair::SimulationResponse;
 use cosmwasm_std::{
-    entry_point, from_binary, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, Fraction,
-    MessageInfo, Response, StdError, StdResult, Uint128,
+    entry_point, from_binary, to_binary, Addr, Api, Binary, CosmosMsg, Decimal, Deps, DepsMut,
+    Env, Fraction, MessageInfo, QuerierWrapper, Response, StdError, StdResult, Uint128,
 };
 use baz::pair_proxy::{
-    Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, MAX_ASSETS,
+    CallbackMsg, Cw20HookMsg, Dex, ExecuteMsg, Hop, InstantiateMsg, MigrateMsg, QueryMsg,
+    ReverseSimulationResponse, RouteInfo, RouteSplit, SimulationResponse, MAX_ASSETS, MAX_ROUTES,
+};
+use astroport::pair::{
+    ReverseSimulationResponse as PairReverseSimulationResponse,
+    SimulationResponse as PairSimulationResponse,
 };
 
-use astroport::asset::{Asset, AssetInfo, PairInfo};
+use astroport::asset::{Asset, AssetInfo, AssetInfoExt, PairInfo};
 use astroport::querier::query_token_precision;
 use cw20::Cw20ReceiveMsg;
-use baz::adapters::router::Router;
+use baz::adapters::asset::AssetEx;
+use baz::adapters::pair::Pair;
+use baz::adapters::router::{Router, SwapOperation};
+
+/// Number of parts the offer amount is divided into when it is split across routes
+const SPLIT_PARTS: u128 = 10;
+
+/// Maximum number of steps when searching the offer amount of hops simulated through the router
+const REVERSE_SEARCH_ITERATIONS: u8 = 64;
 
 /// ## Description
 /// Creates a new contract with the bazified parameters in the [`InstantiateMsg`].
@@ -28,56 +44,100 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
 
-    // Validate swap assets
-    let asset_len = msg.asset_infos.len();
-    if asset_len == 0 {
-        return Err(ContractError::MustProvideNAssets {});
+    // Validate swap routes
+    if msg.routes.is_empty() {
+        return Err(ContractError::MustProvideRoutes {});
     }
-    if asset_len > MAX_ASSETS {
-        return Err(ContractError::SwapLimitExceeded {});
+    if msg.routes.len() > MAX_ROUTES {
+        return Err(ContractError::RouteLimitExceeded {});
     }
 
-    let mut uniq = HashSet::new();
-    for asset_info in msg.asset_infos.iter() {
-        asset_info.check(deps.api)?;
-        if !uniq.insert(asset_info.to_string()) {
-            return Err(ContractError::DuplicatedAssets {});
+    let mut routes: Vec<Route> = vec![];
+    for route in msg.routes {
+        routes.push(validate_route(deps.api, route)?);
+    }
+
+    let offer_asset_info = routes[0].hops[0].offer_asset_info.clone();
+    let ask_asset_info = routes[0].hops[routes[0].hops.len() - 1].ask_asset_info.clone();
+    for route in routes.iter() {
+        if !route.hops[0].offer_asset_info.equal(&offer_asset_info)
+            || !route.hops[route.hops.len() - 1].ask_asset_info.equal(&ask_asset_info)
+        {
+            return Err(ContractError::InvalidRoute {});
         }
     }
 
     let offer_precision = if let Some(offer_precision) = msg.offer_precision {
         offer_precision
     } else {
-        query_token_precision(&deps.querier, &msg.asset_infos[0])?
+        query_token_precision(&deps.querier, &offer_asset_info)?
     };
     let ask_precision = if let Some(ask_precision) = msg.ask_precision {
         ask_precision
     } else {
-        query_token_precision(&deps.querier, &msg.asset_infos[msg.asset_infos.len() - 1])?
+        query_token_precision(&deps.querier, &ask_asset_info)?
     };
 
     let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
         pair_info: PairInfo {
             contract_addr: env.contract.address,
             liquidity_token: Addr::unchecked(""),
-            asset_infos: vec![
-                msg.asset_infos[0].clone(),
-                msg.asset_infos[msg.asset_infos.len() - 1].clone(),
-            ],
+            asset_infos: vec![offer_asset_info, ask_asset_info],
             pair_type: PairType::Custom("pair_proxy".to_string()),
         },
-        asset_infos: msg.asset_infos,
-        router: Router(deps.api.addr_validate(&msg.router)?),
-        router_type: msg.router_type,
+        routes,
         offer_precision,
         ask_precision,
     };
 
     CONFIG.save(deps.storage, &config)?;
+    save_route_version(deps.storage, &env.block, &config.routes)?;
 
     Ok(Response::new())
 }
 
+/// Validates the hops and the router of a swap route
+fn validate_route(api: &dyn Api, route: RouteInfo) -> Result<Route, ContractError> {
+    let hop_len = route.hops.len();
+    if hop_len == 0 {
+        return Err(ContractError::MustProvideNAssets {});
+    }
+    if hop_len >= MAX_ASSETS {
+        return Err(ContractError::SwapLimitExceeded {});
+    }
+
+    let mut uniq = HashSet::new();
+    let first_asset_info = &route.hops[0].offer_asset_info;
+    first_asset_info.check(api)?;
+    uniq.insert(first_asset_info.to_string());
+    for (index, hop) in route.hops.iter().enumerate() {
+        if index > 0 && !hop.offer_asset_info.equal(&route.hops[index - 1].ask_asset_info) {
+            return Err(ContractError::HopsNotConnected {});
+        }
+        hop.ask_asset_info.check(api)?;
+        if !uniq.insert(hop.ask_asset_info.to_string()) {
+            return Err(ContractError::DuplicatedAssets {});
+        }
+        if let Dex::Pair { contract_addr } = &hop.dex {
+            api.addr_validate(contract_addr.as_str())?;
+        }
+    }
+
+    let router = match route.router {
+        Some(router) => Some(Router(api.addr_validate(&router)?)),
+        None => None,
+    };
+    if router.is_none() && route.hops.iter().any(|hop| hop.swap_operation().is_some()) {
+        return Err(ContractError::MissingRouter {});
+    }
+
+    Ok(Route {
+        router,
+        hops: route.hops,
+    })
+}
+
 /// ## Description
 /// Exposes execute functions available in the contract.
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -91,6 +151,7 @@ pub fn execute(
         ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
         ExecuteMsg::Swap {
             offer_asset,
+            ask_asset_info,
             belief_price,
             max_spread,
             to,
@@ -111,14 +172,118 @@ pub fn execute(
                 info.clone(),
                 info.sender,
                 offer_asset,
+                ask_asset_info,
                 belief_price,
                 max_spread,
                 to_addr,
             )
         }
+        ExecuteMsg::Callback(msg) => handle_callback(deps, env, info, msg),
+        ExecuteMsg::UpdateRoute { index, route } => update_route(deps, env, info, index, route),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        }
     }
 }
 
+/// ## Description
+/// Replaces, appends or removes the route at the given index. A new route must swap a whole offer token
+/// through its router or pairs in simulation. Every change is saved as a new version of the routes.
+pub fn update_route(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    index: u32,
+    route: Option<RouteInfo>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let index = index as usize;
+    match route {
+        Some(route) => {
+            let route = validate_route(deps.api, route)?;
+            if !route.hops[0]
+                .offer_asset_info
+                .equal(&config.pair_info.asset_infos[0])
+                || !route.hops[route.hops.len() - 1]
+                    .ask_asset_info
+                    .equal(&config.pair_info.asset_infos[1])
+            {
+                return Err(ContractError::InvalidRoute {});
+            }
+
+            let offer_amount = Uint128::from(10u128.pow(config.offer_precision as u32));
+            let res = simulate_hops(&deps.querier, route.router.as_ref(), &route.hops, offer_amount)
+                .map_err(|err| ContractError::RouteSimulationFailed {
+                    reason: err.to_string(),
+                })?;
+            if res.return_amount.is_zero() {
+                return Err(ContractError::RouteSimulationFailed {
+                    reason: "route returns nothing".to_string(),
+                });
+            }
+
+            if index < config.routes.len() {
+                config.routes[index] = route;
+            } else if index == config.routes.len() {
+                if config.routes.len() >= MAX_ROUTES {
+                    return Err(ContractError::RouteLimitExceeded {});
+                }
+                config.routes.push(route);
+            } else {
+                return Err(ContractError::InvalidRouteIndex {});
+            }
+        }
+        None => {
+            if index >= config.routes.len() {
+                return Err(ContractError::InvalidRouteIndex {});
+            }
+            if config.routes.len() == 1 {
+                return Err(ContractError::MustProvideRoutes {});
+            }
+            config.routes.remove(index);
+        }
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+    let version = save_route_version(deps.storage, &env.block, &config.routes)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_route")
+        .add_attribute("version", version.to_string()))
+}
+
 /// ## Description
 /// Receives a message of type [`Cw20ReceiveMsg`] and processes it depending on the received template.
 /// If the template is not found in the received message, then an [`ContractError`] is returned,
@@ -132,6 +297,7 @@ pub fn receive_cw20(
     let contract_addr = info.sender.clone();
     match from_binary(&cw20_msg.msg) {
         Ok(Cw20HookMsg::Swap {
+            ask_asset_info,
             belief_price,
             max_spread,
             to,
@@ -151,6 +317,7 @@ pub fn receive_cw20(
                     info: AssetInfo::Token { contract_addr },
                     amount: cw20_msg.amount,
                 },
+                ask_asset_info,
                 belief_price,
                 max_spread,
                 to_addr,
@@ -166,10 +333,11 @@ pub fn receive_cw20(
 #[allow(clippy::too_many_arguments)]
 pub fn swap(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     sender: Addr,
     offer_asset: Asset,
+    ask_asset_info: Option<AssetInfo>,
     belief_price: Option<Decimal>,
     max_spread: Option<Decimal>,
     to: Option<Addr>,
@@ -178,56 +346,512 @@ pub fn swap(
 
     let config = CONFIG.load(deps.storage)?;
 
-    let (operations, offer_precision, ask_precision) =
-        if offer_asset.info.equal(&config.asset_infos[0]) {
-            (
-                config
-                    .router_type
-                    .create_swap_operations(&config.asset_infos)?,
-                config.offer_precision,
-                config.ask_precision,
-            )
-        } else if offer_asset
-            .info
-            .equal(&config.asset_infos[config.asset_infos.len() - 1])
-        {
-            let asset_infos: Vec<AssetInfo> = config.asset_infos.into_iter().rev().collect();
-            (
-                config.router_type.create_swap_operations(&asset_infos)?,
-                config.ask_precision,
-                config.offer_precision,
-            )
-        } else {
+    let forward = config
+        .is_forward(&offer_asset.info)
+        .ok_or(ContractError::InvalidAsset {})?;
+    let (offer_precision, ask_precision, return_asset_info) = if forward {
+        (config.offer_precision, config.ask_precision, config.pair_info.asset_infos[1].clone())
+    } else {
+        (config.ask_precision, config.offer_precision, config.pair_info.asset_infos[0].clone())
+    };
+    if let Some(ask_asset_info) = ask_asset_info {
+        if !ask_asset_info.equal(&return_asset_info) {
             return Err(ContractError::InvalidAsset {});
-        };
+        }
+    }
+    let routes = route_hops(&config, forward);
+
+    // a single route takes the whole amount without simulating
+    let splits: Vec<(usize, Uint128)> = if routes.len() == 1 {
+        vec![(0, offer_asset.amount)]
+    } else {
+        split_offer(&deps.querier, &routes, offer_asset.amount)?
+            .into_iter()
+            .map(|it| (it.route as usize, it.offer_amount))
+            .collect()
+    };
 
     let to = to.unwrap_or(sender);
-    let minimum_receive = match (belief_price, max_spread) {
-        (Some(belief_price), Some(max_spread)) => {
-            let minimum_receive = compute_minimum_receive(
-                offer_asset.amount,
-                belief_price,
+    let mut messages: Vec<CosmosMsg> = vec![];
+    // routes which cannot send the output to the receiver directly are collected by this contract
+    let mut collect_output = false;
+    let mut output_minimum_receive: Option<Uint128> = None;
+    for (index, offer_amount) in splits {
+        if offer_amount.is_zero() {
+            continue;
+        }
+
+        let minimum_receive = match (belief_price, max_spread) {
+            (Some(belief_price), Some(max_spread)) => {
+                let minimum_receive = compute_minimum_receive(
+                    offer_amount,
+                    belief_price,
+                    max_spread,
+                    offer_precision,
+                    ask_precision,
+                );
+                Some(minimum_receive)
+            }
+            (_, _) => None,
+        };
+        let (route, hops) = &routes[index];
+        let offer_asset = offer_asset.info.with_balance(offer_amount);
+        if segment_len(hops) == hops.len() && hops[0].swap_operation().is_some() {
+            messages.push(segment_msg(
+                route.router.as_ref(),
+                hops,
+                offer_asset,
+                minimum_receive,
+                &to,
                 max_spread,
-                offer_precision,
-                ask_precision,
-            );
-            Some(minimum_receive)
+            )?);
+        } else {
+            messages.extend(swap_hops_msgs(
+                &deps.querier,
+                &env,
+                route.router.as_ref(),
+                hops,
+                offer_asset,
+                max_spread,
+            )?);
+            collect_output = true;
+            output_minimum_receive = minimum_receive
+                .map(|amount| amount + output_minimum_receive.unwrap_or_default());
         }
-        (_, _) => None,
-    };
-    let message = config.router.execute_swap_operations_msg(
-        offer_asset,
-        operations,
-        minimum_receive,
-        Some(to),
-        max_spread,
-    )?;
+    }
+
+    if collect_output {
+        let balance = return_asset_info.query_pool(&deps.querier, &env.contract.address)?;
+        messages.push(
+            CallbackMsg::TransferOutput {
+                prev_balance: return_asset_info.with_balance(balance),
+                minimum_receive: output_minimum_receive,
+                to: to.to_string(),
+            }
+            .into_cosmos_msg(&env.contract.address)?,
+        );
+    }
 
     Ok(Response::new()
-        .add_message(message)
+        .add_messages(messages)
         .add_attribute("action", "swap"))
 }
 
+/// # Description
+/// Handle the callbacks describes in the [`CallbackMsg`]. Returns an [`ContractError`] on failure, otherwise returns the [`Response`]
+pub fn handle_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: CallbackMsg,
+) -> Result<Response, ContractError> {
+    // Callback functions can only be called by this contract itself
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    match msg {
+        CallbackMsg::SwapHops {
+            router,
+            hops,
+            prev_balance,
+            max_spread,
+        } => {
+            let balance = prev_balance
+                .info
+                .query_pool(&deps.querier, &env.contract.address)?;
+            let offer_asset = prev_balance
+                .info
+                .with_balance(balance.checked_sub(prev_balance.amount)?);
+            let router = router.map(Router);
+            let messages = swap_hops_msgs(
+                &deps.querier,
+                &env,
+                router.as_ref(),
+                &hops,
+                offer_asset,
+                max_spread,
+            )?;
+
+            Ok(Response::new()
+                .add_messages(messages)
+                .add_attribute("action", "swap_hops"))
+        }
+        CallbackMsg::TransferOutput {
+            prev_balance,
+            minimum_receive,
+            to,
+        } => {
+            let balance = prev_balance
+                .info
+                .query_pool(&deps.querier, &env.contract.address)?;
+            let amount = balance.checked_sub(prev_balance.amount)?;
+            if let Some(minimum_receive) = minimum_receive {
+                if amount < minimum_receive {
+                    return Err(ContractError::AssertionMinimumReceive {
+                        minimum_receive,
+                        amount,
+                    });
+                }
+            }
+
+            let mut messages: Vec<CosmosMsg> = vec![];
+            if !amount.is_zero() {
+                let to = deps.api.addr_validate(&to)?;
+                messages.push(prev_balance.info.with_balance(amount).transfer_msg(&to)?);
+            }
+
+            Ok(Response::new()
+                .add_messages(messages)
+                .add_attribute("action", "transfer_output")
+                .add_attribute("amount", amount))
+        }
+    }
+}
+
+/// Returns each route with its hops in the given direction
+fn route_hops(config: &Config, forward: bool) -> Vec<(&Route, Vec<Hop>)> {
+    config
+        .routes
+        .iter()
+        .map(|route| (route, route.hops(forward)))
+        .collect()
+}
+
+/// Returns the number of hops in the first segment, which is either a single hop calling the pair
+/// directly, or the longest run of hops swapped through the router in one message
+fn segment_len(hops: &[Hop]) -> usize {
+    match hops.iter().position(|hop| hop.swap_operation().is_none()) {
+        Some(0) => 1,
+        Some(len) => len,
+        None => hops.len(),
+    }
+}
+
+/// Returns the router operations of the hops
+fn swap_operations(hops: &[Hop]) -> Vec<SwapOperation> {
+    hops.iter().filter_map(|hop| hop.swap_operation()).collect()
+}
+
+fn get_router(router: Option<&Router>) -> StdResult<&Router> {
+    router.ok_or_else(|| StdError::generic_err("Router is not set"))
+}
+
+/// Creates the message swapping the offer asset through a segment of hops
+fn segment_msg(
+    router: Option<&Router>,
+    segment: &[Hop],
+    offer_asset: Asset,
+    minimum_receive: Option<Uint128>,
+    to: &Addr,
+    max_spread: Option<Decimal>,
+) -> StdResult<CosmosMsg> {
+    match &segment[0].dex {
+        Dex::Pair { contract_addr } => Pair(contract_addr.clone()).swap_msg(
+            &offer_asset,
+            Some(segment[0].ask_asset_info.clone()),
+            None,
+            max_spread,
+            Some(to.to_string()),
+        ),
+        _ => get_router(router)?.execute_swap_operations_msg(
+            offer_asset,
+            swap_operations(segment),
+            minimum_receive,
+            Some(to.clone()),
+            max_spread,
+        ),
+    }
+}
+
+/// Creates the messages swapping the offer asset through the first segment of the hops back to this contract,
+/// followed by a callback swapping the received amount through the remaining hops
+fn swap_hops_msgs(
+    querier: &QuerierWrapper,
+    env: &Env,
+    router: Option<&Router>,
+    hops: &[Hop],
+    offer_asset: Asset,
+    max_spread: Option<Decimal>,
+) -> StdResult<Vec<CosmosMsg>> {
+    let (segment, tail) = hops.split_at(segment_len(hops));
+    let mut messages = vec![segment_msg(
+        router,
+        segment,
+        offer_asset,
+        None,
+        &env.contract.address,
+        max_spread,
+    )?];
+
+    if let Some(next_hop) = tail.first() {
+        let balance = next_hop
+            .offer_asset_info
+            .query_pool(querier, &env.contract.address)?;
+        messages.push(
+            CallbackMsg::SwapHops {
+                router: router.map(|it| it.0.clone()),
+                hops: tail.to_vec(),
+                prev_balance: next_hop.offer_asset_info.with_balance(balance),
+                max_spread,
+            }
+            .into_cosmos_msg(&env.contract.address)?,
+        );
+    }
+
+    Ok(messages)
+}
+
+/// Returns the number of hops in the first simulation step, which is either a single hop simulated
+/// on its pair, or the longest run of hops without a pair simulated through the router
+fn simulation_len(hops: &[Hop]) -> usize {
+    match hops.iter().position(|hop| hop.simulation_pair().is_some()) {
+        Some(0) => 1,
+        Some(len) => len,
+        None => hops.len(),
+    }
+}
+
+/// Returns whether the spread and commission of the hops are unknown, because the router
+/// simulates some of them without a pair
+fn spread_unknown(hops: &[Hop]) -> bool {
+    hops.iter().any(|hop| hop.simulation_pair().is_none())
+}
+
+/// Converts the spread and commission of each step to the final ask amount at the rate of the
+/// remaining steps, and adds them up. Each step is a tuple of (ask amount, spread, commission).
+fn total_spread_and_commission(
+    steps: &[(Uint128, Uint128, Uint128)],
+    ask_amount: Uint128,
+) -> (Uint128, Uint128) {
+    steps
+        .iter()
+        .filter(|(step_ask_amount, _, _)| !step_ask_amount.is_zero())
+        .fold(
+            (Uint128::zero(), Uint128::zero()),
+            |(spread_amount, commission_amount), (step_ask_amount, spread, commission)| {
+                (
+                    spread_amount + spread.multiply_ratio(ask_amount, *step_ask_amount),
+                    commission_amount + commission.multiply_ratio(ask_amount, *step_ask_amount),
+                )
+            },
+        )
+}
+
+/// Simulates swapping the offer amount through the hops.
+/// Hops simulated through the router do not report spread and commission.
+fn simulate_hops(
+    querier: &QuerierWrapper,
+    router: Option<&Router>,
+    hops: &[Hop],
+    offer_amount: Uint128,
+) -> StdResult<PairSimulationResponse> {
+    let mut amount = offer_amount;
+    let mut steps: Vec<(Uint128, Uint128, Uint128)> = vec![];
+    let mut hops = hops;
+    while !hops.is_empty() {
+        let (step, tail) = hops.split_at(simulation_len(hops));
+        let res = match step[0].simulation_pair() {
+            Some(pair) => Pair(pair).simulate(
+                querier,
+                &step[0].offer_asset_info.with_balance(amount),
+                Some(step[0].ask_asset_info.clone()),
+            )?,
+            None => PairSimulationResponse {
+                return_amount: get_router(router)?
+                    .simulate(querier, amount, swap_operations(step))?
+                    .amount,
+                spread_amount: Uint128::zero(),
+                commission_amount: Uint128::zero(),
+            },
+        };
+        amount = res.return_amount;
+        steps.push((res.return_amount, res.spread_amount, res.commission_amount));
+        hops = tail;
+    }
+
+    let (spread_amount, commission_amount) = total_spread_and_commission(&steps, amount);
+    Ok(PairSimulationResponse {
+        return_amount: amount,
+        spread_amount,
+        commission_amount,
+    })
+}
+
+/// Returns the offer amount required to receive the ask amount through the hops.
+/// Hops simulated through the router do not report spread and commission.
+fn reverse_simulate_hops(
+    querier: &QuerierWrapper,
+    router: Option<&Router>,
+    hops: &[Hop],
+    ask_amount: Uint128,
+) -> StdResult<PairReverseSimulationResponse> {
+    let mut amount = ask_amount;
+    let mut steps: Vec<(Uint128, Uint128, Uint128)> = vec![];
+    let mut hops = hops;
+    while !hops.is_empty() {
+        let step_len = match hops.iter().rev().position(|hop| hop.simulation_pair().is_some()) {
+            Some(0) => 1,
+            Some(len) => len,
+            None => hops.len(),
+        };
+        let (head, step) = hops.split_at(hops.len() - step_len);
+        let res = match step[0].simulation_pair() {
+            Some(pair) => Pair(pair).reverse_simulate(
+                querier,
+                &step[0].ask_asset_info.with_balance(amount),
+                Some(step[0].offer_asset_info.clone()),
+            )?,
+            None => PairReverseSimulationResponse {
+                offer_amount: search_offer_amount(querier, get_router(router)?, step, amount)?,
+                spread_amount: Uint128::zero(),
+                commission_amount: Uint128::zero(),
+            },
+        };
+        steps.push((amount, res.spread_amount, res.commission_amount));
+        amount = res.offer_amount;
+        hops = head;
+    }
+
+    let (spread_amount, commission_amount) = total_spread_and_commission(&steps, ask_amount);
+    Ok(PairReverseSimulationResponse {
+        offer_amount: amount,
+        spread_amount,
+        commission_amount,
+    })
+}
+
+/// Searches the smallest offer amount which the router swaps to at least the ask amount
+fn search_offer_amount(
+    querier: &QuerierWrapper,
+    router: &Router,
+    hops: &[Hop],
+    ask_amount: Uint128,
+) -> StdResult<Uint128> {
+    let operations = swap_operations(hops);
+    let simulate = |offer_amount: Uint128| {
+        router
+            .simulate(querier, offer_amount, operations.clone())
+            .map(|it| it.amount)
+            .unwrap_or_default()
+    };
+
+    // doubles the offer amount until it is enough
+    let mut low = Uint128::zero();
+    let mut high = ask_amount;
+    let mut iterations = 0u8;
+    while simulate(high) < ask_amount {
+        iterations += 1;
+        if iterations >= REVERSE_SEARCH_ITERATIONS {
+            return Err(StdError::generic_err("Not enough liquidity to receive the ask amount"));
+        }
+        low = high;
+        high = high.checked_mul(Uint128::from(2u8))?;
+    }
+
+    for _ in 0..REVERSE_SEARCH_ITERATIONS {
+        if high - low <= Uint128::from(1u8) {
+            break;
+        }
+        let mid = low + (high - low) / Uint128::from(2u8);
+        if simulate(mid) >= ask_amount {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+
+    Ok(high)
+}
+
+/// ## Description
+/// Splits the offer amount across the routes to maximize the return amount.
+/// The amount is divided into [`SPLIT_PARTS`] parts, and each part goes to the route with the highest
+/// marginal return, which is the best split when the return of each route is concave in the offer amount.
+/// A route whose simulation fails is treated as returning nothing.
+fn split_offer(
+    querier: &QuerierWrapper,
+    routes: &[(&Route, Vec<Hop>)],
+    offer_amount: Uint128,
+) -> StdResult<Vec<RouteSplit>> {
+    let mut splits: Vec<RouteSplit> = routes
+        .iter()
+        .enumerate()
+        .map(|(index, (_, hops))| RouteSplit {
+            route: index as u32,
+            offer_amount: Uint128::zero(),
+            return_amount: Uint128::zero(),
+            spread_amount: Uint128::zero(),
+            commission_amount: Uint128::zero(),
+            spread_unknown: spread_unknown(hops),
+        })
+        .collect();
+
+    if let [(route, hops)] = routes {
+        let res = simulate_hops(querier, route.router.as_ref(), hops, offer_amount)?;
+        splits[0].offer_amount = offer_amount;
+        splits[0].return_amount = res.return_amount;
+        splits[0].spread_amount = res.spread_amount;
+        splits[0].commission_amount = res.commission_amount;
+        return Ok(splits);
+    }
+
+    let part_amount = offer_amount / Uint128::from(SPLIT_PARTS);
+    let parts = if part_amount.is_zero() {
+        vec![offer_amount]
+    } else {
+        let mut parts = vec![part_amount; SPLIT_PARTS as usize];
+        parts[SPLIT_PARTS as usize - 1] += offer_amount - part_amount * Uint128::from(SPLIT_PARTS);
+        parts
+    };
+
+    // the last simulation of each route with its offer amount
+    let mut simulated: Vec<Option<(Uint128, PairSimulationResponse)>> = vec![None; routes.len()];
+    for part in parts {
+        let mut best: Option<(usize, Uint128)> = None;
+        for (index, (route, hops)) in routes.iter().enumerate() {
+            let amount = splits[index].offer_amount + part;
+            let cached_return_amount = simulated[index]
+                .as_ref()
+                .filter(|(simulated_amount, _)| *simulated_amount == amount)
+                .map(|(_, res)| res.return_amount);
+            let return_amount = match cached_return_amount {
+                Some(return_amount) => return_amount,
+                None => {
+                    let res = simulate_hops(querier, route.router.as_ref(), hops, amount)
+                        .unwrap_or(PairSimulationResponse {
+                            return_amount: Uint128::zero(),
+                            spread_amount: Uint128::zero(),
+                            commission_amount: Uint128::zero(),
+                        });
+                    let return_amount = res.return_amount;
+                    simulated[index] = Some((amount, res));
+                    return_amount
+                }
+            };
+
+            let marginal = return_amount.saturating_sub(splits[index].return_amount);
+            let is_better = match best {
+                Some((_, best_marginal)) => marginal > best_marginal,
+                None => true,
+            };
+            if is_better {
+                best = Some((index, marginal));
+            }
+        }
+
+        if let Some((index, _)) = best {
+            if let Some((_, res)) = &simulated[index] {
+                splits[index].offer_amount += part;
+                splits[index].return_amount = res.return_amount;
+                splits[index].spread_amount = res.spread_amount;
+                splits[index].commission_amount = res.commission_amount;
+            }
+        }
+    }
+
+    Ok(splits)
+}
+
 /// Computes minimum return amount from belief price and max spread
 fn compute_minimum_receive(
     offer_amount: Uint128,
@@ -251,55 +875,140 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Pair {} => to_binary(&CONFIG.load(deps.storage)?.pair_info),
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
-        QueryMsg::Simulation { offer_asset, .. } => {
-            to_binary(&query_simulation(deps, offer_asset)?)
+        QueryMsg::Simulation {
+            offer_asset,
+            ask_asset_info,
+        } => to_binary(&query_simulation(deps, offer_asset, ask_asset_info)?),
+        QueryMsg::ReverseSimulation {
+            offer_asset_info,
+            ask_asset,
+        } => to_binary(&query_reverse_simulation(deps, ask_asset, offer_asset_info)?),
+        QueryMsg::RouteHistory { start_after, limit } => {
+            to_binary(&read_route_history(deps.storage, start_after, limit)?)
         }
     }
 }
 
 /// ## Description
 /// Returns information about a swap simulation in a [`SimulationResponse`] object.
-pub fn query_simulation(deps: Deps, offer_asset: Asset) -> StdResult<SimulationResponse> {
+pub fn query_simulation(
+    deps: Deps,
+    offer_asset: Asset,
+    ask_asset_info: Option<AssetInfo>,
+) -> StdResult<SimulationResponse> {
     let config = CONFIG.load(deps.storage)?;
 
-    let (operations, _, _) = if offer_asset.info.equal(&config.asset_infos[0]) {
-        (
-            config
-                .router_type
-                .create_swap_operations(&config.asset_infos)?,
-            config.offer_precision,
-            config.ask_precision,
-        )
-    } else if offer_asset
-        .info
-        .equal(&config.asset_infos[config.asset_infos.len() - 1])
-    {
-        let asset_infos: Vec<AssetInfo> = config.asset_infos.into_iter().rev().collect();
-        (
-            config.router_type.create_swap_operations(&asset_infos)?,
-            config.ask_precision,
-            config.offer_precision,
-        )
-    } else {
-        return Err(StdError::generic_err("Invalid asset"));
-    };
+    let forward = config
+        .is_forward(&offer_asset.info)
+        .ok_or_else(|| StdError::generic_err("Invalid asset"))?;
+    if let Some(ask_asset_info) = ask_asset_info {
+        if !ask_asset_info.equal(&config.pair_info.asset_infos[if forward { 1 } else { 0 }]) {
+            return Err(StdError::generic_err("Invalid ask asset"));
+        }
+    }
 
-    let simulate_operations_response =
-        config
-            .router
-            .simulate(&deps.querier, offer_asset.amount, operations)?;
+    let routes = route_hops(&config, forward);
+    let splits: Vec<RouteSplit> = split_offer(&deps.querier, &routes, offer_asset.amount)?
+        .into_iter()
+        .filter(|it| !it.offer_amount.is_zero())
+        .collect();
 
     Ok(SimulationResponse {
-        return_amount: simulate_operations_response.amount,
-        spread_amount: Uint128::zero(),
-        commission_amount: Uint128::zero(),
+        return_amount: splits.iter().map(|it| it.return_amount).sum(),
+        spread_amount: splits.iter().map(|it| it.spread_amount).sum(),
+        commission_amount: splits.iter().map(|it| it.commission_amount).sum(),
+        spread_unknown: splits.iter().any(|it| it.spread_unknown),
+        splits,
     })
 }
 
+/// ## Description
+/// Returns the offer amount required to receive the ask asset in a [`ReverseSimulationResponse`] object.
+/// The whole amount goes through the route requiring the least offer amount.
+pub fn query_reverse_simulation(
+    deps: Deps,
+    ask_asset: Asset,
+    offer_asset_info: Option<AssetInfo>,
+) -> StdResult<ReverseSimulationResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let forward = !config
+        .is_forward(&ask_asset.info)
+        .ok_or_else(|| StdError::generic_err("Invalid asset"))?;
+    if let Some(offer_asset_info) = offer_asset_info {
+        if !offer_asset_info.equal(&config.pair_info.asset_infos[if forward { 0 } else { 1 }]) {
+            return Err(StdError::generic_err("Invalid offer asset"));
+        }
+    }
+
+    let mut best: Option<RouteSplit> = None;
+    let mut last_err: Option<StdError> = None;
+    for (index, (route, hops)) in route_hops(&config, forward).iter().enumerate() {
+        match reverse_simulate_hops(&deps.querier, route.router.as_ref(), hops, ask_asset.amount) {
+            Ok(res) => {
+                let is_better = match &best {
+                    Some(best) => res.offer_amount < best.offer_amount,
+                    None => true,
+                };
+                if is_better {
+                    best = Some(RouteSplit {
+                        route: index as u32,
+                        offer_amount: res.offer_amount,
+                        return_amount: ask_asset.amount,
+                        spread_amount: res.spread_amount,
+                        commission_amount: res.commission_amount,
+                        spread_unknown: spread_unknown(hops),
+                    });
+                }
+            }
+            Err(err) => last_err = Some(err),
+        }
+    }
+
+    match best {
+        Some(split) => Ok(ReverseSimulationResponse {
+            offer_amount: split.offer_amount,
+            spread_amount: split.spread_amount,
+            commission_amount: split.commission_amount,
+            spread_unknown: split.spread_unknown,
+            splits: vec![split],
+        }),
+        None => Err(last_err.unwrap_or_else(|| StdError::generic_err("No route"))),
+    }
+}
+
 /// ## Description
 /// Used for contract migration. Returns a default object of type [`Response`].
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    if CONFIG.load(deps.storage).is_err() {
+        let owner = msg
+            .owner
+            .ok_or_else(|| StdError::generic_err("owner is required"))?;
+        let legacy_config = LEGACY_CONFIG.load(deps.storage)?;
+        let config = Config {
+            owner: deps.api.addr_validate(&owner)?,
+            pair_info: legacy_config.pair_info,
+            routes: vec![Route {
+                router: Some(legacy_config.router),
+                hops: legacy_config
+                    .asset_infos
+                    .windows(2)
+                    .map(|asset_infos| Hop {
+                        offer_asset_info: asset_infos[0].clone(),
+                        ask_asset_info: asset_infos[1].clone(),
+                        dex: Dex::from(&legacy_config.router_type),
+                        pair_addr: None,
+                    })
+                    .collect(),
+            }],
+            offer_precision: legacy_config.offer_precision,
+            ask_precision: legacy_config.ask_precision,
+        };
+        CONFIG.save(deps.storage, &config)?;
+        save_route_version(deps.storage, &env.block, &config.routes)?;
+    }
+
     Ok(Response::default())
 }
 
@@ -1,4 +1,4 @@
-use cosmwasm_std::{OverflowError, StdError};
+use cosmwasm_std::{OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 /// ## Description
@@ -23,6 +23,29 @@ pub enum ContractError {
     #[error("The limit exceeded of swap assets!")]
     SwapLimitExceeded {},
 
+    #[error("Must provide at least 1 route!")]
+    MustProvideRoutes {},
+
+    #[error("The limit exceeded of swap routes!")]
+    RouteLimitExceeded {},
+
+    #[error("Routes must share the same offer and ask assets")]
+    InvalidRoute {},
+
+    #[error("Each hop must offer the ask asset of the previous hop")]
+    HopsNotConnected {},
+
+    #[error("Router is required to swap through the router")]
+    MissingRouter {},
+
+    #[error("Invalid route index")]
+    InvalidRouteIndex {},
+
+    #[error("Route simulation failed: {reason}")]
+    RouteSimulationFailed { reason: String },
+
+    #[error("Assertion failed; minimum receive amount: {minimum_receive}, actual amount: {amount}")]
+    AssertionMinimumReceive { minimum_receive: Uint128, amount: Uint128 },
 }
 
 impl From<OverflowError> for ContractError {
@@ -4,8 +4,11 @@ use cosmwasm_std::testing::{MockApi, MockStorage};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use astroport::asset::{token_asset, AssetInfo, PairInfo};
+use astroport::asset::{token_asset, Asset, AssetInfo, PairInfo};
+use astroport::pair::{ReverseSimulationResponse, SimulationResponse};
 use astroport::generator::{PendingTokenResponse};
+use baz::adapters::router::{SimulateSwapOperationsResponse, SwapOperation};
+use baz::helper::ScalingUint128;
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
     let custom_querier: WasmMockQuerier = WasmMockQuerier::new();
@@ -24,7 +27,8 @@ const REWARD_TOKEN: &str = "reward";
 pub struct WasmMockQuerier {
     balances: HashMap<(String, String), Uint128>,
     raw: HashMap<(String, Binary), Binary>,
-    pairs: HashMap<Vec<u8>, PairInfo>
+    pairs: HashMap<Vec<u8>, PairInfo>,
+    liquidities: HashMap<String, Uint128>,
 }
 
 impl WasmMockQuerier {
@@ -33,10 +37,16 @@ impl WasmMockQuerier {
             balances: HashMap::new(),
             raw: HashMap::new(),
             pairs: HashMap::new(),
+            liquidities: HashMap::new(),
         }
     }
 
-    pub fn _set_balance(&mut self, token: String, addr: String, amount: Uint128) {
+    /// Simulates a router or pair as a constant product pool with the given liquidity on both sides
+    pub fn set_liquidity(&mut self, contract_addr: String, liquidity: Uint128) {
+        self.liquidities.insert(contract_addr, liquidity);
+    }
+
+    pub fn set_balance(&mut self, token: String, addr: String, amount: Uint128) {
         self.balances.insert((token, addr), amount);
     }
 
@@ -52,6 +62,28 @@ impl WasmMockQuerier {
         self.pairs.get(&pair_key(&asset_infos))
     }
 
+    fn simulate(&self, contract_addr: &str, offer_amount: Uint128) -> Uint128 {
+        let liquidity = self
+            .liquidities
+            .get(contract_addr)
+            .cloned()
+            .unwrap_or_default();
+        if liquidity.is_zero() {
+            return Uint128::zero();
+        }
+        offer_amount.multiply_ratio(liquidity, liquidity + offer_amount)
+    }
+
+    fn reverse_simulate(&self, contract_addr: &str, ask_amount: Uint128) -> Uint128 {
+        let liquidity = match self.liquidities.get(contract_addr) {
+            Some(v) => *v,
+            None => {
+                panic!("No liquidity")
+            }
+        };
+        ask_amount.multiply_ratio_and_ceil(liquidity, liquidity - ask_amount)
+    }
+
     fn execute_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
         let result = match request {
             QueryRequest::Bank(BankQuery::Balance {
@@ -126,6 +158,36 @@ impl WasmMockQuerier {
 
              to_binary(&pair_info)
              },
+             MockQueryMsg::SimulateSwapOperations {
+                offer_amount,
+                ..
+            } => {
+                to_binary(&SimulateSwapOperationsResponse {
+                    amount: self.simulate(contract_addr, offer_amount),
+                })
+            },
+             MockQueryMsg::Simulation {
+                offer_asset,
+                ..
+            } => {
+                let return_amount = self.simulate(contract_addr, offer_asset.amount);
+                to_binary(&SimulationResponse {
+                    return_amount,
+                    spread_amount: offer_asset.amount - return_amount,
+                    commission_amount: Uint128::zero(),
+                })
+            },
+             MockQueryMsg::ReverseSimulation {
+                ask_asset,
+                ..
+            } => {
+                let offer_amount = self.reverse_simulate(contract_addr, ask_asset.amount);
+                to_binary(&ReverseSimulationResponse {
+                    offer_amount,
+                    spread_amount: offer_amount - ask_asset.amount,
+                    commission_amount: Uint128::zero(),
+                })
+            },
              MockQueryMsg::TokenInfo {
             } => {
                 to_binary(&cw20::TokenInfoResponse {
@@ -157,6 +219,18 @@ enum MockQueryMsg {
         asset_infos: [AssetInfo; 2],
     },
     TokenInfo {},
+    SimulateSwapOperations {
+        offer_amount: Uint128,
+        operations: Vec<SwapOperation>,
+    },
+    Simulation {
+        offer_asset: Asset,
+        ask_asset_info: Option<AssetInfo>,
+    },
+    ReverseSimulation {
+        offer_asset_info: Option<AssetInfo>,
+        ask_asset: Asset,
+    },
 }
 
 impl Querier for WasmMockQuerier {
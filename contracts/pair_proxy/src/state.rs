@@ -1,13 +1,130 @@
-use cw_storage_plus::Item;
+use cw_storage_plus::{Bound, Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use astroport::asset::{AssetInfo, PairInfo};
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::{Addr, BlockInfo, Order, StdResult, Storage};
 use baz::adapters::router::{Router, RouterType};
+use baz::pair_proxy::Hop;
 
 /// ## Description
 /// This structure describes the main control config of pair.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
+    pub owner: Addr,
+    pub pair_info: PairInfo,
+    pub routes: Vec<Route>,
+    pub offer_precision: u8,
+    pub ask_precision: u8,
+}
+
+impl Config {
+    /// Returns true if the asset is the first asset of the routes,
+    /// false if it is the last asset, or None if it is neither
+    pub fn is_forward(&self, offer_asset_info: &AssetInfo) -> Option<bool> {
+        if offer_asset_info.equal(&self.pair_info.asset_infos[0]) {
+            Some(true)
+        } else if offer_asset_info.equal(&self.pair_info.asset_infos[1]) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// ## Description
+/// This structure describes a candidate swap route.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Route {
+    pub router: Option<Router>,
+    pub hops: Vec<Hop>,
+}
+
+impl Route {
+    /// Returns the hops of the route in the given direction
+    pub fn hops(&self, forward: bool) -> Vec<Hop> {
+        if forward {
+            self.hops.clone()
+        } else {
+            self.hops.iter().rev().map(|hop| hop.reverse()).collect()
+        }
+    }
+}
+
+/// ## Description
+/// Stores config at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// ## Description
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+
+/// ## Description
+/// This structure describes the routes from the block they were set.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RouteVersion {
+    pub version: u64,
+    pub routes: Vec<Route>,
+    pub height: u64,
+    pub time_seconds: u64,
+}
+
+/// ## Description
+/// Stores every version of the routes by version number
+pub const ROUTE_HISTORY: Map<u64, RouteVersion> = Map::new("route_history");
+
+/// ## Description
+/// Saves the routes as the next version. Returns the new version number.
+pub fn save_route_version(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    routes: &[Route],
+) -> StdResult<u64> {
+    let last_version = ROUTE_HISTORY
+        .keys(storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .unwrap_or_default();
+    let version = last_version + 1;
+    ROUTE_HISTORY.save(
+        storage,
+        version,
+        &RouteVersion {
+            version,
+            routes: routes.to_vec(),
+            height: block.height,
+            time_seconds: block.time.seconds(),
+        },
+    )?;
+
+    Ok(version)
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns the versions of the routes after the given version, oldest first
+pub fn read_route_history(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<RouteVersion>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    ROUTE_HISTORY
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, version)| version))
+        .collect()
+}
+
+/// ## Description
+/// This structure describes the config of the contract when it had a single route and no owner.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LegacyConfig {
     pub pair_info: PairInfo,
     pub asset_infos: Vec<AssetInfo>,
     pub router: Router,
@@ -17,5 +134,5 @@ pub struct Config {
 }
 
 /// ## Description
-/// Stores config at the given key
-pub const CONFIG: Item<Config> = Item::new("config");
+/// Stores legacy config at the same key as [`CONFIG`]
+pub const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
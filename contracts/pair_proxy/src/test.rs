@@ -1,26 +1,36 @@
 use astroport::asset::{Asset, AssetInfo, PairInfo};
 use astroport::factory::PairType;
+use astroport::pair::Cw20HookMsg as PairCw20HookMsg;
 use astroport::router::{
     Cw20HookMsg as RouterCw20HookMsg, ExecuteMsg as RouterExecuteMsg, SwapOperation,
 };
 use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    to_binary, Addr, Coin, CosmosMsg, Decimal, OwnedDeps, Response, StdError, Uint128, WasmMsg,
+    attr, from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal, OwnedDeps, Response,
+    StdError, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
-use baz::adapters::router::{Router, RouterType};
-use baz::pair_proxy::{Cw20HookMsg, ExecuteMsg, InstantiateMsg};
+use baz::adapters::router::{
+    Cw20HookMsg as BazRouterCw20HookMsg, Router, RouterType, SwapOperation as BazSwapOperation,
+};
+use baz::pair_proxy::{
+    CallbackMsg, Cw20HookMsg, Dex, ExecuteMsg, Hop, InstantiateMsg, MigrateMsg, QueryMsg,
+    ReverseSimulationResponse, RouteInfo, RouteSplit, SimulationResponse,
+};
 
-use crate::contract::{execute, instantiate};
+use crate::contract::{execute, instantiate, migrate, query};
 use crate::error::ContractError;
 use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
-use crate::state::{Config, CONFIG};
+use crate::state::{Config, LegacyConfig, Route, RouteVersion, CONFIG, LEGACY_CONFIG};
 
 const USER_1: &str = "user_1";
 const USER_2: &str = "user_2";
 const ROUTER: &str = "router";
+const ROUTER_2: &str = "router_2";
 const TOKEN_1: &str = "token_1";
 const TOKEN_2: &str = "token_2";
+const TOKEN_3: &str = "token_3";
+const PAIR: &str = "pair";
 const IBC_TOKEN: &str = "ibc/stablecoin";
 
 #[test]
@@ -32,6 +42,710 @@ fn test() -> Result<(), ContractError> {
     Ok(())
 }
 
+#[test]
+fn test_split_routes() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    deps.querier.set_liquidity(ROUTER.to_string(), Uint128::from(1_000_000u128));
+    deps.querier.set_liquidity(ROUTER_2.to_string(), Uint128::from(3_000_000u128));
+
+    let env = mock_env();
+    let info = mock_info(USER_1, &[]);
+    let instantiate_msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        routes: vec![
+            route_info(&[TOKEN_1, TOKEN_2, IBC_TOKEN], ROUTER, RouterType::AstroSwap),
+            route_info(&[TOKEN_1, IBC_TOKEN], ROUTER_2, RouterType::TerraSwap),
+        ],
+        offer_precision: None,
+        ask_precision: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, instantiate_msg)?;
+
+    // the deeper route takes the larger part
+    let res: SimulationResponse = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Simulation {
+            offer_asset: Asset {
+                info: asset_info(TOKEN_1),
+                amount: Uint128::from(1_000_000u128),
+            },
+            ask_asset_info: None,
+        },
+    )?)?;
+    assert_eq!(
+        res,
+        SimulationResponse {
+            return_amount: Uint128::from(798336u128),
+            spread_amount: Uint128::zero(),
+            commission_amount: Uint128::zero(),
+            spread_unknown: true,
+            splits: vec![
+                RouteSplit {
+                    route: 0,
+                    offer_amount: Uint128::from(300000u128),
+                    return_amount: Uint128::from(230769u128),
+                    spread_amount: Uint128::zero(),
+                    commission_amount: Uint128::zero(),
+                    spread_unknown: true,
+                },
+                RouteSplit {
+                    route: 1,
+                    offer_amount: Uint128::from(700000u128),
+                    return_amount: Uint128::from(567567u128),
+                    spread_amount: Uint128::zero(),
+                    commission_amount: Uint128::zero(),
+                    spread_unknown: true,
+                },
+            ],
+        }
+    );
+
+    // each part is swapped on its own route with its share of the minimum receive
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1_000_000u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: Some(Decimal::percent(1)),
+            to: None,
+        })?,
+    });
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_1.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: ROUTER.to_string(),
+                    amount: Uint128::from(300000u128),
+                    msg: to_binary(&BazRouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: vec![
+                            BazSwapOperation::AstroSwap {
+                                offer_asset_info: asset_info(TOKEN_1),
+                                ask_asset_info: asset_info(TOKEN_2),
+                            },
+                            BazSwapOperation::AstroSwap {
+                                offer_asset_info: asset_info(TOKEN_2),
+                                ask_asset_info: asset_info(IBC_TOKEN),
+                            },
+                        ],
+                        minimum_receive: Some(Uint128::from(297000u128)),
+                        to: Some(Addr::unchecked(USER_1)),
+                        max_spread: Some(Decimal::percent(1))
+                    })?,
+                })?,
+                funds: vec![],
+            }),
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_1.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: ROUTER_2.to_string(),
+                    amount: Uint128::from(700000u128),
+                    msg: to_binary(&BazRouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: vec![BazSwapOperation::TerraSwap {
+                            offer_asset_info: asset_info(TOKEN_1),
+                            ask_asset_info: asset_info(IBC_TOKEN),
+                        }],
+                        minimum_receive: Some(Uint128::from(693000u128)),
+                        to: Some(Addr::unchecked(USER_1)),
+                        max_spread: Some(Decimal::percent(1))
+                    })?,
+                })?,
+                funds: vec![],
+            }),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_mixed_route() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    deps.querier.set_liquidity(ROUTER.to_string(), Uint128::from(1_000_000u128));
+    deps.querier.set_liquidity(PAIR.to_string(), Uint128::from(1_000_000u128));
+
+    let env = mock_env();
+    let info = mock_info(USER_1, &[]);
+    let hops = vec![
+        Hop {
+            offer_asset_info: asset_info(TOKEN_1),
+            ask_asset_info: asset_info(TOKEN_2),
+            dex: Dex::AstroSwap,
+            pair_addr: None,
+        },
+        Hop {
+            offer_asset_info: asset_info(TOKEN_2),
+            ask_asset_info: asset_info(TOKEN_3),
+            dex: Dex::TerraSwap,
+            pair_addr: None,
+        },
+        Hop {
+            offer_asset_info: asset_info(TOKEN_3),
+            ask_asset_info: asset_info(IBC_TOKEN),
+            dex: Dex::Pair {
+                contract_addr: Addr::unchecked(PAIR),
+            },
+            pair_addr: None,
+        },
+    ];
+
+    let instantiate_msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        routes: vec![RouteInfo {
+            router: None,
+            hops: hops.clone(),
+        }],
+        offer_precision: None,
+        ask_precision: None,
+    };
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg);
+    assert_error(res, "Router is required to swap through the router");
+
+    let instantiate_msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        routes: vec![RouteInfo {
+            router: Some(ROUTER.to_string()),
+            hops: vec![hops[0].clone(), hops[2].clone()],
+        }],
+        offer_precision: None,
+        ask_precision: None,
+    };
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg);
+    assert_error(res, "Each hop must offer the ask asset of the previous hop");
+
+    let instantiate_msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        routes: vec![RouteInfo {
+            router: Some(ROUTER.to_string()),
+            hops: hops.clone(),
+        }],
+        offer_precision: None,
+        ask_precision: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, instantiate_msg)?;
+
+    // router hops are simulated together, then the pair hop with its spread
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Simulation {
+            offer_asset: Asset {
+                info: asset_info(TOKEN_1),
+                amount: Uint128::from(1000u128),
+            },
+            ask_asset_info: Some(asset_info(TOKEN_2)),
+        },
+    );
+    assert_eq!(res, Err(StdError::generic_err("Invalid ask asset")));
+
+    let res: SimulationResponse = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Simulation {
+            offer_asset: Asset {
+                info: asset_info(TOKEN_1),
+                amount: Uint128::from(1000u128),
+            },
+            ask_asset_info: Some(asset_info(IBC_TOKEN)),
+        },
+    )?)?;
+    assert_eq!(
+        res,
+        SimulationResponse {
+            return_amount: Uint128::from(998u128),
+            spread_amount: Uint128::from(1u128),
+            commission_amount: Uint128::zero(),
+            spread_unknown: true,
+            splits: vec![RouteSplit {
+                route: 0,
+                offer_amount: Uint128::from(1000u128),
+                return_amount: Uint128::from(998u128),
+                spread_amount: Uint128::from(1u128),
+                commission_amount: Uint128::zero(),
+                spread_unknown: true,
+            }],
+        }
+    );
+
+    // the pair hop is reverse simulated on the pair, then the router hops are searched
+    let res: ReverseSimulationResponse = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::ReverseSimulation {
+            offer_asset_info: None,
+            ask_asset: Asset {
+                info: asset_info(IBC_TOKEN),
+                amount: Uint128::from(998u128),
+            },
+        },
+    )?)?;
+    assert_eq!(
+        res,
+        ReverseSimulationResponse {
+            offer_amount: Uint128::from(1000u128),
+            spread_amount: Uint128::from(1u128),
+            commission_amount: Uint128::zero(),
+            spread_unknown: true,
+            splits: vec![RouteSplit {
+                route: 0,
+                offer_amount: Uint128::from(1000u128),
+                return_amount: Uint128::from(998u128),
+                spread_amount: Uint128::from(1u128),
+                commission_amount: Uint128::zero(),
+                spread_unknown: true,
+            }],
+        }
+    );
+
+    // router hops are swapped in one message, the pair hop in a callback
+    let info = mock_info(TOKEN_1, &[]);
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: USER_1.to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
+            belief_price: Some(Decimal::percent(100)),
+            max_spread: Some(Decimal::percent(1)),
+            to: Some(USER_2.to_string()),
+        })?,
+    });
+    let res = execute(deps.as_mut(), env.clone(), info, msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: TOKEN_1.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Send {
+                    contract: ROUTER.to_string(),
+                    amount: Uint128::from(1000u128),
+                    msg: to_binary(&BazRouterCw20HookMsg::ExecuteSwapOperations {
+                        operations: vec![
+                            BazSwapOperation::AstroSwap {
+                                offer_asset_info: asset_info(TOKEN_1),
+                                ask_asset_info: asset_info(TOKEN_2),
+                            },
+                            BazSwapOperation::TerraSwap {
+                                offer_asset_info: asset_info(TOKEN_2),
+                                ask_asset_info: asset_info(TOKEN_3),
+                            },
+                        ],
+                        minimum_receive: None,
+                        to: Some(Addr::unchecked(MOCK_CONTRACT_ADDR)),
+                        max_spread: Some(Decimal::percent(1))
+                    })?,
+                })?,
+                funds: vec![],
+            }),
+            CallbackMsg::SwapHops {
+                router: Some(Addr::unchecked(ROUTER)),
+                hops: vec![hops[2].clone()],
+                prev_balance: Asset {
+                    info: asset_info(TOKEN_3),
+                    amount: Uint128::zero(),
+                },
+                max_spread: Some(Decimal::percent(1)),
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+            CallbackMsg::TransferOutput {
+                prev_balance: Asset {
+                    info: asset_info(IBC_TOKEN),
+                    amount: Uint128::zero(),
+                },
+                minimum_receive: Some(Uint128::from(990u128)),
+                to: USER_2.to_string(),
+            }
+            .into_cosmos_msg(&Addr::unchecked(MOCK_CONTRACT_ADDR))?,
+        ]
+    );
+
+    // callbacks can only be called by the contract
+    let msg = ExecuteMsg::Callback(CallbackMsg::SwapHops {
+        router: Some(Addr::unchecked(ROUTER)),
+        hops: vec![hops[2].clone()],
+        prev_balance: Asset {
+            info: asset_info(TOKEN_3),
+            amount: Uint128::zero(),
+        },
+        max_spread: Some(Decimal::percent(1)),
+    });
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // the amount received from the router is swapped on the pair
+    deps.querier.set_balance(
+        TOKEN_3.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(999u128),
+    );
+    let res = execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: TOKEN_3.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: PAIR.to_string(),
+                amount: Uint128::from(999u128),
+                msg: to_binary(&PairCw20HookMsg::Swap {
+                    ask_asset_info: Some(asset_info(IBC_TOKEN)),
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(1)),
+                    to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                })?,
+            })?,
+            funds: vec![],
+        })]
+    );
+
+    // the output is checked against the minimum receive
+    let msg = ExecuteMsg::Callback(CallbackMsg::TransferOutput {
+        prev_balance: Asset {
+            info: asset_info(IBC_TOKEN),
+            amount: Uint128::zero(),
+        },
+        minimum_receive: Some(Uint128::from(990u128)),
+        to: USER_2.to_string(),
+    });
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(980u128),
+    );
+    let res = execute(deps.as_mut(), env.clone(), mock_info(MOCK_CONTRACT_ADDR, &[]), msg.clone());
+    assert_error(res, "Assertion failed; minimum receive amount: 990, actual amount: 980");
+
+    deps.querier.set_balance(
+        IBC_TOKEN.to_string(),
+        MOCK_CONTRACT_ADDR.to_string(),
+        Uint128::from(998u128),
+    );
+    let res = execute(deps.as_mut(), env, mock_info(MOCK_CONTRACT_ADDR, &[]), msg)?;
+    assert_eq!(
+        res.messages
+            .into_iter()
+            .map(|it| it.msg)
+            .collect::<Vec<CosmosMsg>>(),
+        [CosmosMsg::Bank(BankMsg::Send {
+            to_address: USER_2.to_string(),
+            amount: vec![Coin {
+                denom: IBC_TOKEN.to_string(),
+                amount: Uint128::from(998u128),
+            }],
+        })]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_router_hop_pairs() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    deps.querier.set_liquidity(ROUTER.to_string(), Uint128::from(1_000_000u128));
+    deps.querier.set_liquidity(PAIR.to_string(), Uint128::from(1_000_000u128));
+
+    let env = mock_env();
+    let info = mock_info(USER_1, &[]);
+    let instantiate_msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        routes: vec![RouteInfo {
+            router: Some(ROUTER.to_string()),
+            hops: vec![
+                Hop {
+                    offer_asset_info: asset_info(TOKEN_1),
+                    ask_asset_info: asset_info(TOKEN_2),
+                    dex: Dex::AstroSwap,
+                    pair_addr: Some(Addr::unchecked(PAIR)),
+                },
+                Hop {
+                    offer_asset_info: asset_info(TOKEN_2),
+                    ask_asset_info: asset_info(IBC_TOKEN),
+                    dex: Dex::TerraSwap,
+                    pair_addr: Some(Addr::unchecked(PAIR)),
+                },
+            ],
+        }],
+        offer_precision: None,
+        ask_precision: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), info, instantiate_msg)?;
+
+    // router hops with a pair are simulated on the pair, so the spread is known
+    let res: SimulationResponse = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Simulation {
+            offer_asset: Asset {
+                info: asset_info(TOKEN_1),
+                amount: Uint128::from(1000u128),
+            },
+            ask_asset_info: None,
+        },
+    )?)?;
+    assert_eq!(res.return_amount, Uint128::from(998u128));
+    assert_eq!(res.spread_amount, Uint128::from(1u128));
+    assert!(!res.spread_unknown);
+    assert!(!res.splits[0].spread_unknown);
+
+    let res: ReverseSimulationResponse = from_binary(&query(
+        deps.as_ref(),
+        env,
+        QueryMsg::ReverseSimulation {
+            offer_asset_info: None,
+            ask_asset: Asset {
+                info: asset_info(IBC_TOKEN),
+                amount: Uint128::from(998u128),
+            },
+        },
+    )?)?;
+    assert_eq!(res.offer_amount, Uint128::from(1000u128));
+    assert!(!res.spread_unknown);
+
+    Ok(())
+}
+
+#[test]
+fn test_update_route() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    deps.querier.set_liquidity(ROUTER.to_string(), Uint128::from(1_000_000u128));
+
+    let env = mock_env();
+    let info = mock_info(USER_1, &[]);
+    let instantiate_msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        routes: vec![route_info(&[TOKEN_1, TOKEN_2, IBC_TOKEN], ROUTER, RouterType::AstroSwap)],
+        offer_precision: None,
+        ask_precision: None,
+    };
+    instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg)?;
+    let first_route = CONFIG.load(deps.as_ref().storage)?.routes[0].clone();
+
+    // only the owner can update routes
+    let msg = ExecuteMsg::UpdateRoute {
+        index: 1,
+        route: Some(route_info(&[TOKEN_1, IBC_TOKEN], ROUTER_2, RouterType::TerraSwap)),
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+
+    // the new route must swap in simulation
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "Route simulation failed: route returns nothing");
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::UpdateRoute {
+            index: 1,
+            route: Some(route_info(&[TOKEN_1, TOKEN_2], ROUTER, RouterType::AstroSwap)),
+        },
+    );
+    assert_error(res, "Routes must share the same offer and ask assets");
+
+    deps.querier.set_liquidity(ROUTER_2.to_string(), Uint128::from(1_000_000u128));
+    let mut env = mock_env();
+    env.block.height += 1;
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.attributes,
+        vec![attr("action", "update_route"), attr("version", "2")]
+    );
+    let second_route = CONFIG.load(deps.as_ref().storage)?.routes[1].clone();
+    assert_eq!(second_route.router, Some(Router(Addr::unchecked(ROUTER_2))));
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::UpdateRoute {
+            index: 3,
+            route: None,
+        },
+    );
+    assert_error(res, "Invalid route index");
+
+    // removing the first route leaves the second
+    env.block.height += 1;
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::UpdateRoute {
+            index: 0,
+            route: None,
+        },
+    )?;
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::UpdateRoute {
+            index: 0,
+            route: None,
+        },
+    );
+    assert_error(res, "Must provide at least 1 route!");
+
+    // every change is kept in the history
+    let res: Vec<RouteVersion> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RouteHistory {
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    let start_height = mock_env().block.height;
+    let time_seconds = mock_env().block.time.seconds();
+    assert_eq!(
+        res,
+        vec![
+            RouteVersion {
+                version: 1,
+                routes: vec![first_route.clone()],
+                height: start_height,
+                time_seconds,
+            },
+            RouteVersion {
+                version: 2,
+                routes: vec![first_route, second_route.clone()],
+                height: start_height + 1,
+                time_seconds,
+            },
+            RouteVersion {
+                version: 3,
+                routes: vec![second_route],
+                height: start_height + 2,
+                time_seconds,
+            },
+        ]
+    );
+
+    let res: Vec<RouteVersion> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RouteHistory {
+            start_after: Some(1),
+            limit: Some(1),
+        },
+    )?)?;
+    assert_eq!(res.iter().map(|it| it.version).collect::<Vec<u64>>(), vec![2]);
+
+    // ownership moves in two steps
+    let msg = ExecuteMsg::ProposeNewOwner {
+        owner: USER_2.to_string(),
+        expires_in: 100,
+    };
+    let res = execute(deps.as_mut(), env.clone(), mock_info(USER_2, &[]), msg.clone());
+    assert_error(res, "Unauthorized");
+    execute(deps.as_mut(), env.clone(), mock_info(USER_1, &[]), msg)?;
+    execute(deps.as_mut(), env, mock_info(USER_2, &[]), ExecuteMsg::ClaimOwnership {})?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.owner, Addr::unchecked(USER_2));
+
+    Ok(())
+}
+
+#[test]
+fn test_migrate() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    let pair_info = PairInfo {
+        asset_infos: vec![asset_info(TOKEN_1), asset_info(IBC_TOKEN)],
+        contract_addr: Addr::unchecked(MOCK_CONTRACT_ADDR),
+        liquidity_token: Addr::unchecked(""),
+        pair_type: PairType::Custom("pair_proxy".to_string()),
+    };
+    LEGACY_CONFIG.save(
+        deps.as_mut().storage,
+        &LegacyConfig {
+            pair_info: pair_info.clone(),
+            asset_infos: vec![asset_info(TOKEN_1), asset_info(TOKEN_2), asset_info(IBC_TOKEN)],
+            router: Router(Addr::unchecked(ROUTER)),
+            router_type: RouterType::TerraSwap,
+            offer_precision: 6,
+            ask_precision: 8,
+        },
+    )?;
+
+    // the legacy contract has no owner
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg { owner: None });
+    assert_eq!(res, Err(StdError::generic_err("owner is required")));
+
+    migrate(
+        deps.as_mut(),
+        env.clone(),
+        MigrateMsg {
+            owner: Some(USER_1.to_string()),
+        },
+    )?;
+    let route = Route {
+        router: Some(Router(Addr::unchecked(ROUTER))),
+        hops: vec![
+            Hop {
+                offer_asset_info: asset_info(TOKEN_1),
+                ask_asset_info: asset_info(TOKEN_2),
+                dex: Dex::TerraSwap,
+                pair_addr: None,
+            },
+            Hop {
+                offer_asset_info: asset_info(TOKEN_2),
+                ask_asset_info: asset_info(IBC_TOKEN),
+                dex: Dex::TerraSwap,
+                pair_addr: None,
+            },
+        ],
+    };
+    assert_eq!(
+        CONFIG.load(deps.as_ref().storage)?,
+        Config {
+            owner: Addr::unchecked(USER_1),
+            pair_info,
+            routes: vec![route.clone()],
+            offer_precision: 6,
+            ask_precision: 8,
+        }
+    );
+
+    // the migrated route is the first version of the history
+    let res: Vec<RouteVersion> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::RouteHistory {
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(
+        res,
+        vec![RouteVersion {
+            version: 1,
+            routes: vec![route],
+            height: env.block.height,
+            time_seconds: env.block.time.seconds(),
+        }]
+    );
+
+    // migrating again keeps the config
+    migrate(deps.as_mut(), env, MigrateMsg { owner: None })?;
+    assert_eq!(CONFIG.load(deps.as_ref().storage)?.owner, Addr::unchecked(USER_1));
+
+    Ok(())
+}
+
 fn assert_error(res: Result<Response, ContractError>, expected: &str) {
     match res {
         Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
@@ -40,6 +754,33 @@ fn assert_error(res: Result<Response, ContractError>, expected: &str) {
     }
 }
 
+fn asset_info(name: &str) -> AssetInfo {
+    if name == IBC_TOKEN {
+        AssetInfo::NativeToken {
+            denom: name.to_string(),
+        }
+    } else {
+        AssetInfo::Token {
+            contract_addr: Addr::unchecked(name),
+        }
+    }
+}
+
+fn route_info(assets: &[&str], router: &str, router_type: RouterType) -> RouteInfo {
+    RouteInfo {
+        router: Some(router.to_string()),
+        hops: assets
+            .windows(2)
+            .map(|it| Hop {
+                offer_asset_info: asset_info(it[0]),
+                ask_asset_info: asset_info(it[1]),
+                dex: Dex::from(&router_type),
+                pair_addr: None,
+            })
+            .collect(),
+    }
+}
+
 fn create(
     deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
 ) -> Result<(), ContractError> {
@@ -47,9 +788,20 @@ fn create(
     let info = mock_info(USER_1, &[]);
 
     let instantiate_msg = InstantiateMsg {
-        asset_infos: vec![],
-        router: ROUTER.to_string(),
-        router_type: RouterType::AstroSwap,
+        owner: USER_1.to_string(),
+        routes: vec![],
+        offer_precision: None,
+        ask_precision: None,
+    };
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg);
+    assert_error(res, "Must provide at least 1 route!");
+
+    let instantiate_msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        routes: vec![RouteInfo {
+            router: Some(ROUTER.to_string()),
+            hops: vec![],
+        }],
         offer_precision: None,
         ask_precision: None,
     };
@@ -57,16 +809,8 @@ fn create(
     assert_error(res, "Must provide at least 2 assets!");
 
     let instantiate_msg = InstantiateMsg {
-        asset_infos: vec![
-            AssetInfo::Token {
-                contract_addr: Addr::unchecked(TOKEN_1),
-            },
-            AssetInfo::Token {
-                contract_addr: Addr::unchecked(TOKEN_1),
-            },
-        ],
-        router: ROUTER.to_string(),
-        router_type: RouterType::AstroSwap,
+        owner: USER_1.to_string(),
+        routes: vec![route_info(&[TOKEN_1, TOKEN_1], ROUTER, RouterType::AstroSwap)],
         offer_precision: None,
         ask_precision: None,
     };
@@ -74,19 +818,20 @@ fn create(
     assert_error(res, "Duplicated assets in asset infos");
 
     let instantiate_msg = InstantiateMsg {
-        asset_infos: vec![
-            AssetInfo::Token {
-                contract_addr: Addr::unchecked(TOKEN_1),
-            },
-            AssetInfo::Token {
-                contract_addr: Addr::unchecked(TOKEN_2),
-            },
-            AssetInfo::NativeToken {
-                denom: IBC_TOKEN.to_string(),
-            },
+        owner: USER_1.to_string(),
+        routes: vec![
+            route_info(&[TOKEN_1, TOKEN_2, IBC_TOKEN], ROUTER, RouterType::AstroSwap),
+            route_info(&[TOKEN_1, TOKEN_2], ROUTER_2, RouterType::TerraSwap),
         ],
-        router: ROUTER.to_string(),
-        router_type: RouterType::AstroSwap,
+        offer_precision: None,
+        ask_precision: None,
+    };
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), instantiate_msg);
+    assert_error(res, "Routes must share the same offer and ask assets");
+
+    let instantiate_msg = InstantiateMsg {
+        owner: USER_1.to_string(),
+        routes: vec![route_info(&[TOKEN_1, TOKEN_2, IBC_TOKEN], ROUTER, RouterType::AstroSwap)],
         offer_precision: None,
         ask_precision: None,
     };
@@ -97,6 +842,7 @@ fn create(
     assert_eq!(
         config,
         Config {
+            owner: Addr::unchecked(USER_1),
             pair_info: PairInfo {
                 asset_infos: vec![
                     AssetInfo::Token {
@@ -110,19 +856,23 @@ fn create(
                 liquidity_token: Addr::unchecked(""),
                 pair_type: PairType::Custom("pair_proxy".to_string())
             },
-            asset_infos: vec![
-                AssetInfo::Token {
-                    contract_addr: Addr::unchecked(TOKEN_1),
-                },
-                AssetInfo::Token {
-                    contract_addr: Addr::unchecked(TOKEN_2),
-                },
-                AssetInfo::NativeToken {
-                    denom: IBC_TOKEN.to_string(),
-                },
-            ],
-            router: Router(Addr::unchecked(ROUTER)),
-            router_type: RouterType::AstroSwap,
+            routes: vec![Route {
+                router: Some(Router(Addr::unchecked(ROUTER))),
+                hops: vec![
+                    Hop {
+                        offer_asset_info: asset_info(TOKEN_1),
+                        ask_asset_info: asset_info(TOKEN_2),
+                        dex: Dex::AstroSwap,
+                        pair_addr: None,
+                    },
+                    Hop {
+                        offer_asset_info: asset_info(TOKEN_2),
+                        ask_asset_info: asset_info(IBC_TOKEN),
+                        dex: Dex::AstroSwap,
+                        pair_addr: None,
+                    },
+                ],
+            }],
             offer_precision: 6,
             ask_precision: 6
         }
@@ -139,6 +889,7 @@ fn swap(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
         sender: USER_1.to_string(),
         amount: Uint128::from(100u128),
         msg: to_binary(&Cw20HookMsg::Swap {
+            ask_asset_info: None,
             belief_price: Some(Decimal::percent(100)),
             max_spread: Some(Decimal::percent(1)),
             to: Some(USER_2.to_string()),
@@ -198,6 +949,7 @@ fn swap(deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> Result<(
             },
             amount: Uint128::from(100u128),
         },
+        ask_asset_info: None,
         belief_price: Some(Decimal::percent(100)),
         max_spread: Some(Decimal::percent(1)),
         to: None,
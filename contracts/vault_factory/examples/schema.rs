@@ -0,0 +1,20 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{remove_schemas, schema_for, export_schema};
+
+use baz::vault_factory::{ExecuteMsg, InstantiateMsg, QueryMsg, VaultInfo};
+use baz_vault_factory::state::Config;
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(VaultInfo), &out_dir);
+    export_schema(&schema_for!(Config), &out_dir);
+}
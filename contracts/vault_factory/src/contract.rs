@@ -0,0 +1,438 @@
+use crate::error::ContractError;
+use crate::state::{Config, PendingVault, CONFIG, OWNERSHIP_PROPOSAL, PENDING_VAULT, VAULTS};
+
+use astroport::asset::AssetInfo;
+use astroport::common::{claim_ownership, drop_ownership_proposal, propose_new_owner};
+use cosmwasm_std::{
+    attr, entry_point, to_binary, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Reply, Response, StdError, StdResult, SubMsg, WasmMsg,
+};
+use cw_storage_plus::Bound;
+use cw_utils::parse_reply_instantiate_data;
+
+use baz::adapters::pair::Pair;
+use baz::astroport_farm::{
+    ExecuteMsg as FarmExecuteMsg, InstantiateMsg as FarmInstantiateMsg,
+};
+use baz::compound_proxy::{
+    validate_commission, InstantiateMsg as CompoundProxyInstantiateMsg,
+};
+use baz::vault_factory::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, VaultContract, VaultInfo,
+};
+
+/// Reply ID of the compound_proxy instantiation
+const INSTANTIATE_COMPOUND_PROXY_REPLY_ID: u64 = 1;
+/// Reply ID of the astroport_farm instantiation
+const INSTANTIATE_FARM_REPLY_ID: u64 = 2;
+
+/// ## Description
+/// Validates that decimal value is in the range 0 to 1
+fn validate_percentage(value: Decimal, field: &str) -> StdResult<Decimal> {
+    if value > Decimal::one() {
+        Err(StdError::generic_err(field.to_string() + " must be 0 to 1"))
+    } else {
+        Ok(value)
+    }
+}
+
+/// ## Description
+/// Creates a new contract with the bazified parameters in the [`InstantiateMsg`].
+/// Returns the [`Response`] with the bazified attributes if the operation was successful, or a [`ContractError`] if the contract was not created.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let config = Config {
+        owner: deps.api.addr_validate(&msg.owner)?,
+        farm_code_id: msg.farm_code_id,
+        compound_proxy_code_id: msg.compound_proxy_code_id,
+        staking_contract: deps.api.addr_validate(&msg.staking_contract)?,
+        base_reward_token: deps.api.addr_validate(&msg.base_reward_token)?,
+        controller: deps.api.addr_validate(&msg.controller)?,
+        fee: validate_percentage(msg.fee, "fee")?,
+        fee_collector: deps.api.addr_validate(&msg.fee_collector)?,
+        commission_bps: validate_commission(msg.commission_bps)?,
+        slippage_tolerance: validate_percentage(msg.slippage_tolerance, "slippage_tolerance")?,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default())
+}
+
+/// ## Description
+/// Exposes execute functions available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::UpdateConfig {
+            farm_code_id,
+            compound_proxy_code_id,
+            staking_contract,
+            base_reward_token,
+            controller,
+            fee,
+            fee_collector,
+            commission_bps,
+            slippage_tolerance,
+        } => {
+            let mut config: Config = CONFIG.load(deps.storage)?;
+
+            if info.sender != config.owner {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            if let Some(farm_code_id) = farm_code_id {
+                config.farm_code_id = farm_code_id;
+            }
+
+            if let Some(compound_proxy_code_id) = compound_proxy_code_id {
+                config.compound_proxy_code_id = compound_proxy_code_id;
+            }
+
+            if let Some(staking_contract) = staking_contract {
+                config.staking_contract = deps.api.addr_validate(&staking_contract)?;
+            }
+
+            if let Some(base_reward_token) = base_reward_token {
+                config.base_reward_token = deps.api.addr_validate(&base_reward_token)?;
+            }
+
+            if let Some(controller) = controller {
+                config.controller = deps.api.addr_validate(&controller)?;
+            }
+
+            if let Some(fee) = fee {
+                config.fee = validate_percentage(fee, "fee")?;
+            }
+
+            if let Some(fee_collector) = fee_collector {
+                config.fee_collector = deps.api.addr_validate(&fee_collector)?;
+            }
+
+            if let Some(commission_bps) = commission_bps {
+                config.commission_bps = validate_commission(commission_bps)?;
+            }
+
+            if let Some(slippage_tolerance) = slippage_tolerance {
+                config.slippage_tolerance =
+                    validate_percentage(slippage_tolerance, "slippage_tolerance")?;
+            }
+
+            CONFIG.save(deps.storage, &config)?;
+
+            Ok(Response::new().add_attributes(vec![attr("action", "update_config")]))
+        }
+        ExecuteMsg::CreateVault {
+            pair,
+            name,
+            symbol,
+            commission_bps,
+            pair_proxies,
+        } => create_vault(deps, env, info, pair, name, symbol, commission_bps, pair_proxies),
+        ExecuteMsg::PropagateConfig { start_after, limit } => {
+            propagate_config(deps, info, start_after, limit)
+        }
+        ExecuteMsg::ExecuteVault {
+            liquidity_token,
+            target,
+            msg,
+        } => execute_vault(deps, info, liquidity_token, target, msg),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            propose_new_owner(
+                deps,
+                info,
+                env,
+                owner,
+                expires_in,
+                config.owner,
+                OWNERSHIP_PROPOSAL,
+            )
+            .map_err(|e| e.into())
+        }
+        ExecuteMsg::DropOwnershipProposal {} => {
+            let config: Config = CONFIG.load(deps.storage)?;
+
+            drop_ownership_proposal(deps, info, config.owner, OWNERSHIP_PROPOSAL)
+                .map_err(|e| e.into())
+        }
+        ExecuteMsg::ClaimOwnership {} => {
+            claim_ownership(deps, info, env, OWNERSHIP_PROPOSAL, |deps, new_owner| {
+                CONFIG.update::<_, StdError>(deps.storage, |mut v| {
+                    v.owner = new_owner;
+                    Ok(v)
+                })?;
+
+                Ok(())
+            })
+            .map_err(|e| e.into())
+        }
+    }
+}
+
+/// ## Description
+/// Instantiates compound_proxy for the pair. The farm is instantiated when the compound_proxy address is known.
+/// Both contracts are owned by this contract, and the owner is set as their admin for migrations.
+/// The owner reaches their owner-only messages through [`ExecuteMsg::ExecuteVault`].
+#[allow(clippy::too_many_arguments)]
+fn create_vault(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pair: String,
+    name: String,
+    symbol: String,
+    commission_bps: Option<u64>,
+    pair_proxies: Vec<(AssetInfo, String)>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let pair_info = Pair(deps.api.addr_validate(&pair)?).query_pair_info(&deps.querier)?;
+    if VAULTS.has(deps.storage, &pair_info.liquidity_token) {
+        return Err(ContractError::VaultExists(pair_info.liquidity_token.to_string()));
+    }
+
+    PENDING_VAULT.save(
+        deps.storage,
+        &PendingVault {
+            pair: pair_info.contract_addr.clone(),
+            liquidity_token: pair_info.liquidity_token.clone(),
+            name,
+            symbol: symbol.clone(),
+            compound_proxy: None,
+        },
+    )?;
+
+    let instantiate_compound_proxy = WasmMsg::Instantiate {
+        admin: Some(config.owner.to_string()),
+        code_id: config.compound_proxy_code_id,
+        msg: to_binary(&CompoundProxyInstantiateMsg {
+            owner: env.contract.address.to_string(),
+            pair_contract: pair_info.contract_addr.to_string(),
+            commission_bps: validate_commission(commission_bps.unwrap_or(config.commission_bps))?,
+            pair_proxies,
+            slippage_tolerance: config.slippage_tolerance,
+            price_guard: None,
+        })?,
+        funds: vec![],
+        label: format!("{} compound proxy", symbol),
+    };
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_on_success(
+            instantiate_compound_proxy,
+            INSTANTIATE_COMPOUND_PROXY_REPLY_ID,
+        ))
+        .add_attributes(vec![
+            attr("action", "create_vault"),
+            attr("pair", pair_info.contract_addr),
+            attr("liquidity_token", pair_info.liquidity_token),
+        ]))
+}
+
+/// ## Description
+/// Sends controller, fee and fee collector in the config to the farms of the vaults.
+fn propagate_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let vaults = query_vaults(deps.as_ref(), start_after, limit)?;
+    let messages = vaults
+        .iter()
+        .map(|vault| {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: vault.farm.to_string(),
+                msg: to_binary(&FarmExecuteMsg::UpdateConfig {
+                    compound_proxy: None,
+                    controller: Some(config.controller.to_string()),
+                    fee: Some(config.fee),
+                    fee_collector: Some(config.fee_collector.to_string()),
+                })?,
+                funds: vec![],
+            }))
+        })
+        .collect::<StdResult<Vec<CosmosMsg>>>()?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(vec![
+            attr("action", "propagate_config"),
+            attr("count", vaults.len().to_string()),
+        ]))
+}
+
+/// ## Description
+/// Forwards a message to the farm or compound_proxy of a vault, so the owner can reach their owner-only messages.
+fn execute_vault(
+    deps: DepsMut,
+    info: MessageInfo,
+    liquidity_token: String,
+    target: VaultContract,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let config: Config = CONFIG.load(deps.storage)?;
+
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let liquidity_token = deps.api.addr_validate(&liquidity_token)?;
+    let vault = VAULTS.load(deps.storage, &liquidity_token)?;
+    let contract_addr = match target {
+        VaultContract::Farm => vault.farm,
+        VaultContract::CompoundProxy => vault.compound_proxy,
+    };
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg,
+            funds: vec![],
+        })
+        .add_attributes(vec![
+            attr("action", "execute_vault"),
+            attr("contract", contract_addr),
+        ]))
+}
+
+/// ## Description
+/// Handles the replies of the vault contract instantiations.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        INSTANTIATE_COMPOUND_PROXY_REPLY_ID => {
+            let res = parse_reply_instantiate_data(msg)
+                .map_err(|e| StdError::generic_err(e.to_string()))?;
+            let compound_proxy = deps.api.addr_validate(&res.contract_address)?;
+
+            let config: Config = CONFIG.load(deps.storage)?;
+            let mut pending_vault = PENDING_VAULT.load(deps.storage)?;
+            pending_vault.compound_proxy = Some(compound_proxy.clone());
+            PENDING_VAULT.save(deps.storage, &pending_vault)?;
+
+            let instantiate_farm = WasmMsg::Instantiate {
+                admin: Some(config.owner.to_string()),
+                code_id: config.farm_code_id,
+                msg: to_binary(&FarmInstantiateMsg {
+                    owner: env.contract.address.to_string(),
+                    staking_contract: config.staking_contract.to_string(),
+                    compound_proxy: compound_proxy.to_string(),
+                    controller: config.controller.to_string(),
+                    fee: config.fee,
+                    fee_collector: config.fee_collector.to_string(),
+                    liquidity_token: pending_vault.liquidity_token.to_string(),
+                    base_reward_token: config.base_reward_token.to_string(),
+                    pair: pending_vault.pair.to_string(),
+                    name: pending_vault.name,
+                    symbol: pending_vault.symbol.clone(),
+                })?,
+                funds: vec![],
+                label: format!("{} farm", pending_vault.symbol),
+            };
+
+            Ok(Response::new()
+                .add_submessage(SubMsg::reply_on_success(
+                    instantiate_farm,
+                    INSTANTIATE_FARM_REPLY_ID,
+                ))
+                .add_attribute("compound_proxy", compound_proxy))
+        }
+        INSTANTIATE_FARM_REPLY_ID => {
+            let res = parse_reply_instantiate_data(msg)
+                .map_err(|e| StdError::generic_err(e.to_string()))?;
+            let farm = deps.api.addr_validate(&res.contract_address)?;
+
+            let pending_vault = PENDING_VAULT.load(deps.storage)?;
+            PENDING_VAULT.remove(deps.storage);
+
+            let compound_proxy = pending_vault
+                .compound_proxy
+                .ok_or_else(|| StdError::generic_err("compound proxy is not instantiated"))?;
+            VAULTS.save(
+                deps.storage,
+                &pending_vault.liquidity_token,
+                &VaultInfo {
+                    pair: pending_vault.pair,
+                    liquidity_token: pending_vault.liquidity_token.clone(),
+                    farm: farm.clone(),
+                    compound_proxy,
+                },
+            )?;
+
+            Ok(Response::new()
+                .add_attribute("farm", farm)
+                .add_attribute("liquidity_token", pending_vault.liquidity_token))
+        }
+        _ => Err(StdError::generic_err("invalid reply id").into()),
+    }
+}
+
+/// ## Description
+/// Exposes all the queries available in the contract.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Vault { liquidity_token } => {
+            let liquidity_token = deps.api.addr_validate(&liquidity_token)?;
+            to_binary(&VAULTS.load(deps.storage, &liquidity_token)?)
+        }
+        QueryMsg::Vaults { start_after, limit } => {
+            to_binary(&query_vaults(deps, start_after, limit)?)
+        }
+    }
+}
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// ## Description
+/// Returns the list of vaults ordered by LP token address.
+fn query_vaults(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<VaultInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    VAULTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
+/// ## Description
+/// Used for contract migration. Returns a default object of type [`Response`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    Ok(Response::default())
+}
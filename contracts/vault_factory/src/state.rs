@@ -0,0 +1,59 @@
+use astroport::common::OwnershipProposal;
+use cosmwasm_std::{Addr, Decimal};
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use baz::vault_factory::VaultInfo;
+
+/// This structure stores the template config used to create vaults.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    /// Address that's allowed to create vaults and update config
+    pub owner: Addr,
+    /// The code ID of the astroport_farm contract
+    pub farm_code_id: u64,
+    /// The code ID of the compound_proxy contract
+    pub compound_proxy_code_id: u64,
+    /// The LP staking generator contract address
+    pub staking_contract: Addr,
+    /// The base reward token contract address
+    pub base_reward_token: Addr,
+    /// The controller address to execute compound
+    pub controller: Addr,
+    /// The performance fee
+    pub fee: Decimal,
+    /// The fee collector contract address
+    pub fee_collector: Addr,
+    /// The default swap commission of the pairs, in bps
+    pub commission_bps: u64,
+    /// The default slippage tolerance when providing LP
+    pub slippage_tolerance: Decimal,
+}
+
+/// Stores the contract configuration at the given key
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// This structure holds the vault being instantiated between the replies
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingVault {
+    /// The pair contract address
+    pub pair: Addr,
+    /// The LP token contract address
+    pub liquidity_token: Addr,
+    /// The name of the farm token
+    pub name: String,
+    /// The symbol of the farm token
+    pub symbol: String,
+    /// The compound_proxy contract address, set after it is instantiated
+    pub compound_proxy: Option<Addr>,
+}
+
+/// Stores the vault being instantiated
+pub const PENDING_VAULT: Item<PendingVault> = Item::new("pending_vault");
+
+/// Stores the vaults by LP token address
+pub const VAULTS: Map<&Addr, VaultInfo> = Map::new("vaults");
+
+/// Stores the latest proposal to change contract ownership
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
@@ -0,0 +1,379 @@
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, OwnedDeps, Reply, Response,
+    StdError, SubMsg, SubMsgResponse, SubMsgResult, WasmMsg,
+};
+
+use baz::astroport_farm::{ExecuteMsg as FarmExecuteMsg, InstantiateMsg as FarmInstantiateMsg};
+use baz::compound_proxy::{
+    ExecuteMsg as CompoundProxyExecuteMsg, InstantiateMsg as CompoundProxyInstantiateMsg,
+};
+use baz::vault_factory::{ExecuteMsg, InstantiateMsg, QueryMsg, VaultContract, VaultInfo};
+
+use crate::contract::{execute, instantiate, query, reply};
+use crate::error::ContractError;
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
+use crate::state::{Config, PENDING_VAULT};
+
+const OWNER: &str = "owner";
+const USER_1: &str = "user_1";
+const STAKING: &str = "staking";
+const REWARD_TOKEN: &str = "astro";
+const CONTROLLER: &str = "controller";
+const FEE_COLLECTOR: &str = "fee_collector";
+const FARM_CODE_ID: u64 = 11;
+const COMPOUND_PROXY_CODE_ID: u64 = 12;
+
+#[test]
+fn test() -> Result<(), ContractError> {
+    let mut deps = mock_dependencies(&[]);
+    create(&mut deps)?;
+    config(&mut deps)?;
+    create_vault(&mut deps)?;
+    propagate_config(&mut deps)?;
+    execute_vault(&mut deps)?;
+
+    Ok(())
+}
+
+fn assert_error(res: Result<Response, ContractError>, expected: &str) {
+    match res {
+        Err(ContractError::Std(StdError::GenericErr { msg, .. })) => assert_eq!(expected, msg),
+        Err(err) => assert_eq!(expected, format!("{}", err)),
+        _ => panic!("Expected exception"),
+    }
+}
+
+/// Builds a successful instantiate reply carrying the new contract address
+fn instantiate_reply(id: u64, contract_addr: &str) -> Reply {
+    let mut data = vec![0x0a, contract_addr.len() as u8];
+    data.extend(contract_addr.as_bytes());
+    Reply {
+        id,
+        result: SubMsgResult::Ok(SubMsgResponse {
+            events: vec![],
+            data: Some(Binary(data)),
+        }),
+    }
+}
+
+fn create(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+    let info = mock_info(USER_1, &[]);
+
+    let mut msg = InstantiateMsg {
+        owner: OWNER.to_string(),
+        farm_code_id: FARM_CODE_ID,
+        compound_proxy_code_id: COMPOUND_PROXY_CODE_ID,
+        staking_contract: STAKING.to_string(),
+        base_reward_token: REWARD_TOKEN.to_string(),
+        controller: CONTROLLER.to_string(),
+        fee: Decimal::percent(5),
+        fee_collector: FEE_COLLECTOR.to_string(),
+        commission_bps: 30,
+        slippage_tolerance: Decimal::percent(1),
+    };
+
+    msg.commission_bps = 10000;
+    let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+    assert_error(res, "commission rate must be 0 to 9999");
+
+    msg.commission_bps = 30;
+    instantiate(deps.as_mut(), env.clone(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env, QueryMsg::Config {})?)?;
+    assert_eq!(
+        config,
+        Config {
+            owner: Addr::unchecked(OWNER),
+            farm_code_id: FARM_CODE_ID,
+            compound_proxy_code_id: COMPOUND_PROXY_CODE_ID,
+            staking_contract: Addr::unchecked(STAKING),
+            base_reward_token: Addr::unchecked(REWARD_TOKEN),
+            controller: Addr::unchecked(CONTROLLER),
+            fee: Decimal::percent(5),
+            fee_collector: Addr::unchecked(FEE_COLLECTOR),
+            commission_bps: 30,
+            slippage_tolerance: Decimal::percent(1),
+        }
+    );
+
+    Ok(())
+}
+
+fn config(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let msg = ExecuteMsg::UpdateConfig {
+        farm_code_id: None,
+        compound_proxy_code_id: None,
+        staking_contract: None,
+        base_reward_token: None,
+        controller: None,
+        fee: Some(Decimal::percent(10)),
+        fee_collector: None,
+        commission_bps: None,
+        slippage_tolerance: None,
+    };
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+
+    let config: Config = from_binary(&query(deps.as_ref(), env, QueryMsg::Config {})?)?;
+    assert_eq!(config.fee, Decimal::percent(10));
+
+    Ok(())
+}
+
+fn create_vault(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let msg = ExecuteMsg::CreateVault {
+        pair: "pair_contract".to_string(),
+        name: "Farm LP".to_string(),
+        symbol: "FLP".to_string(),
+        commission_bps: None,
+        pair_proxies: vec![],
+    };
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone())?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            WasmMsg::Instantiate {
+                admin: Some(OWNER.to_string()),
+                code_id: COMPOUND_PROXY_CODE_ID,
+                msg: to_binary(&CompoundProxyInstantiateMsg {
+                    owner: MOCK_CONTRACT_ADDR.to_string(),
+                    pair_contract: "pair_contract".to_string(),
+                    commission_bps: 30,
+                    pair_proxies: vec![],
+                    slippage_tolerance: Decimal::percent(1),
+                    price_guard: None,
+                })?,
+                funds: vec![],
+                label: "FLP compound proxy".to_string(),
+            },
+            1
+        )]
+    );
+
+    let res = reply(
+        deps.as_mut(),
+        env.clone(),
+        instantiate_reply(1, "compound_proxy"),
+    )?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            WasmMsg::Instantiate {
+                admin: Some(OWNER.to_string()),
+                code_id: FARM_CODE_ID,
+                msg: to_binary(&FarmInstantiateMsg {
+                    owner: MOCK_CONTRACT_ADDR.to_string(),
+                    staking_contract: STAKING.to_string(),
+                    compound_proxy: "compound_proxy".to_string(),
+                    controller: CONTROLLER.to_string(),
+                    fee: Decimal::percent(10),
+                    fee_collector: FEE_COLLECTOR.to_string(),
+                    liquidity_token: "liquidity_token".to_string(),
+                    base_reward_token: REWARD_TOKEN.to_string(),
+                    pair: "pair_contract".to_string(),
+                    name: "Farm LP".to_string(),
+                    symbol: "FLP".to_string(),
+                })?,
+                funds: vec![],
+                label: "FLP farm".to_string(),
+            },
+            2
+        )]
+    );
+
+    reply(deps.as_mut(), env.clone(), instantiate_reply(2, "farm"))?;
+    assert!(PENDING_VAULT.may_load(deps.as_ref().storage)?.is_none());
+
+    let vault_info = VaultInfo {
+        pair: Addr::unchecked("pair_contract"),
+        liquidity_token: Addr::unchecked("liquidity_token"),
+        farm: Addr::unchecked("farm"),
+        compound_proxy: Addr::unchecked("compound_proxy"),
+    };
+    let res: VaultInfo = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Vault {
+            liquidity_token: "liquidity_token".to_string(),
+        },
+    )?)?;
+    assert_eq!(res, vault_info);
+
+    // a pair can only have one vault
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+    assert_error(res, "Vault already exists for liquidity_token");
+
+    let msg = ExecuteMsg::CreateVault {
+        pair: "pair_contract_2".to_string(),
+        name: "Farm LP 2".to_string(),
+        symbol: "FLP2".to_string(),
+        commission_bps: Some(50),
+        pair_proxies: vec![],
+    };
+    execute(deps.as_mut(), env.clone(), info, msg)?;
+    reply(
+        deps.as_mut(),
+        env.clone(),
+        instantiate_reply(1, "compound_proxy_2"),
+    )?;
+    reply(deps.as_mut(), env.clone(), instantiate_reply(2, "farm_2"))?;
+
+    let res: Vec<VaultInfo> = from_binary(&query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Vaults {
+            start_after: None,
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(
+        res,
+        vec![
+            vault_info,
+            VaultInfo {
+                pair: Addr::unchecked("pair_contract_2"),
+                liquidity_token: Addr::unchecked("liquidity_token_2"),
+                farm: Addr::unchecked("farm_2"),
+                compound_proxy: Addr::unchecked("compound_proxy_2"),
+            },
+        ]
+    );
+
+    let res: Vec<VaultInfo> = from_binary(&query(
+        deps.as_ref(),
+        env,
+        QueryMsg::Vaults {
+            start_after: Some("liquidity_token".to_string()),
+            limit: None,
+        },
+    )?)?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].farm, Addr::unchecked("farm_2"));
+
+    Ok(())
+}
+
+fn propagate_config(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let msg = ExecuteMsg::PropagateConfig {
+        start_after: None,
+        limit: None,
+    };
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env, info, msg)?;
+    let update_config = to_binary(&FarmExecuteMsg::UpdateConfig {
+        compound_proxy: None,
+        controller: Some(CONTROLLER.to_string()),
+        fee: Some(Decimal::percent(10)),
+        fee_collector: Some(FEE_COLLECTOR.to_string()),
+    })?;
+    assert_eq!(
+        res.messages,
+        vec![
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "farm".to_string(),
+                msg: update_config.clone(),
+                funds: vec![],
+            })),
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "farm_2".to_string(),
+                msg: update_config,
+                funds: vec![],
+            })),
+        ]
+    );
+
+    Ok(())
+}
+
+fn execute_vault(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+) -> Result<(), ContractError> {
+    let env = mock_env();
+
+    let propose_new_owner = to_binary(&FarmExecuteMsg::ProposeNewOwner {
+        owner: "new_owner".to_string(),
+        expires_in: 86400,
+    })?;
+    let msg = ExecuteMsg::ExecuteVault {
+        liquidity_token: "liquidity_token".to_string(),
+        target: VaultContract::Farm,
+        msg: propose_new_owner.clone(),
+    };
+
+    let info = mock_info(USER_1, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info, msg.clone());
+    assert_error(res, "Unauthorized");
+
+    let info = mock_info(OWNER, &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "farm".to_string(),
+            msg: propose_new_owner,
+            funds: vec![],
+        }))]
+    );
+
+    let propose_new_owner = to_binary(&CompoundProxyExecuteMsg::ProposeNewOwner {
+        owner: "new_owner".to_string(),
+        expires_in: 86400,
+    })?;
+    let msg = ExecuteMsg::ExecuteVault {
+        liquidity_token: "liquidity_token_2".to_string(),
+        target: VaultContract::CompoundProxy,
+        msg: propose_new_owner.clone(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg)?;
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "compound_proxy_2".to_string(),
+            msg: propose_new_owner,
+            funds: vec![],
+        }))]
+    );
+
+    // unknown vault
+    let msg = ExecuteMsg::ExecuteVault {
+        liquidity_token: "liquidity_token_3".to_string(),
+        target: VaultContract::Farm,
+        msg: to_binary(&FarmExecuteMsg::ClaimOwnership {})?,
+    };
+    let res = execute(deps.as_mut(), env, info, msg);
+    assert!(res.is_err());
+
+    Ok(())
+}
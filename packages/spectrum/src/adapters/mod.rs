@@ -2,3 +2,4 @@ pub mod asset;
 pub mod pair;
 pub mod router;
 pub mod generator;
+pub mod oracle;
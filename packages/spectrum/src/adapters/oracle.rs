@@ -0,0 +1,23 @@
+use cosmwasm_std::{Addr, QuerierWrapper, StdResult, Uint128, Uint256};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use astroport::asset::AssetInfo;
+use astroport::oracle::QueryMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Oracle(pub Addr);
+
+impl Oracle {
+    /// Returns the TWAP value of **amount** of **token** in each of the other assets of the pool
+    pub fn consult(
+        &self,
+        querier: &QuerierWrapper,
+        token: &AssetInfo,
+        amount: Uint128,
+    ) -> StdResult<Vec<(AssetInfo, Uint256)>> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Consult {
+            token: token.clone(),
+            amount,
+        })
+    }
+}
@@ -1,9 +1,9 @@
-use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, QuerierWrapper, StdResult, to_binary, WasmMsg};
+use cosmwasm_std::{Addr, Coin, CosmosMsg, Decimal, QuerierWrapper, StdResult, to_binary, Uint128, WasmMsg};
 use cw20::Cw20ExecuteMsg;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use astroport::asset::{Asset, AssetInfo, PairInfo};
-use astroport::pair::{Cw20HookMsg, ExecuteMsg, QueryMsg, SimulationResponse, ConfigResponse, PoolResponse};
+use astroport::pair::{Cw20HookMsg, ExecuteMsg, QueryMsg, SimulationResponse, ConfigResponse, PoolResponse, ReverseSimulationResponse};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Pair(pub Addr);
@@ -21,6 +21,10 @@ impl Pair {
         querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Config {})
     }
 
+    pub fn query_share(&self, querier: &QuerierWrapper, amount: Uint128) -> StdResult<Vec<Asset>> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::Share { amount })
+    }
+
     pub fn simulate(
         &self,
         querier: &QuerierWrapper,
@@ -33,10 +37,23 @@ impl Pair {
         })
     }
 
+    pub fn reverse_simulate(
+        &self,
+        querier: &QuerierWrapper,
+        ask_asset: &Asset,
+        offer_asset_info: Option<AssetInfo>
+    ) -> StdResult<ReverseSimulationResponse> {
+        querier.query_wasm_smart(self.0.to_string(), &QueryMsg::ReverseSimulation {
+            offer_asset_info,
+            ask_asset: ask_asset.clone(),
+        })
+    }
+
     /// Generate msg for swapping bazified asset
     pub fn swap_msg(
         &self,
         asset: &Asset,
+        ask_asset_info: Option<AssetInfo>,
         belief_price: Option<Decimal>,
         max_spread: Option<Decimal>,
         to: Option<String>,
@@ -48,7 +65,7 @@ impl Pair {
                     contract: self.0.to_string(),
                     amount: asset.amount,
                     msg: to_binary(&Cw20HookMsg::Swap {
-                        ask_asset_info: None,
+                        ask_asset_info,
                         belief_price,
                         max_spread,
                         to,
@@ -61,7 +78,7 @@ impl Pair {
                 contract_addr: self.0.to_string(),
                 msg: to_binary(&ExecuteMsg::Swap {
                     offer_asset: asset.clone(),
-                    ask_asset_info: None,
+                    ask_asset_info,
                     belief_price,
                     max_spread,
                     to: None,
@@ -95,4 +112,21 @@ impl Pair {
             funds,
         }))
     }
+
+    /// Generate msg for burning LP token to withdraw assets from the pair
+    pub fn withdraw_liquidity_msg(
+        &self,
+        liquidity_token: String,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: liquidity_token,
+            msg: to_binary(&Cw20ExecuteMsg::Send {
+                contract: self.0.to_string(),
+                amount,
+                msg: to_binary(&Cw20HookMsg::WithdrawLiquidity { assets: vec![] })?,
+            })?,
+            funds: vec![],
+        }))
+    }
 }
\ No newline at end of file
@@ -1,4 +1,4 @@
-use astroport::asset::Asset;
+use astroport::asset::{Asset, AssetInfo};
 use cosmwasm_std::{to_binary, Addr, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg, StdError, Binary};
 use cw20::{Cw20ReceiveMsg, Expiration, Logo};
 use schemars::JsonSchema;
@@ -48,16 +48,50 @@ pub enum ExecuteMsg {
         /// The fee collector contract address
         fee_collector: Option<String>,
     },
+    /// Update the permissionless compound settings
+    UpdateCompoundConfig {
+        /// Allow anyone to execute compound
+        permissionless: Option<bool>,
+        /// The minimum seconds between compounds executed by non-controller callers
+        min_interval: Option<u64>,
+        /// The share of rewards paid to non-controller callers
+        bounty: Option<Decimal>,
+    },
+    /// Update the fee recipients and the deposit and withdraw fees
+    UpdateFeeConfig {
+        /// The list of fee recipient and weight, empty to send all fees to the fee collector
+        fee_recipients: Option<Vec<(String, u64)>>,
+        /// The fee charged on bonded LP
+        deposit_fee: Option<Decimal>,
+        /// The fee charged on unbonded LP
+        withdraw_fee: Option<Decimal>,
+        /// Pay fees in vault shares instead of reward and LP tokens
+        fee_in_shares: Option<bool>,
+    },
+    /// Set the exit fee charged on LP unbonded soon after deposit, or disable it with `None`
+    UpdateExitFee {
+        /// The exit fee settings
+        exit_fee: Option<ExitFee>,
+    },
     /// Unbond LP token
     Unbond {
         /// The LP amount to unbond
         amount: Uint128,
     },
+    /// Unbond LP token and withdraw the underlying assets from the pair
+    UnbondAssets {
+        /// The LP amount to unbond
+        amount: Uint128,
+        /// The asset to swap all withdrawn assets into
+        target_asset: Option<AssetInfo>,
+        /// The minimum expected amount of target asset, required when target asset is set
+        minimum_receive: Option<Uint128>,
+    },
     /// Compound LP rewards
     Compound {
-        /// The minimum expected amount of LP token
+        /// The minimum expected amount of LP token, only applied for the controller
         minimum_receive: Option<Uint128>,
-        /// Slippage tolerance when providing LP
+        /// Slippage tolerance when providing LP, only applied for the controller
         slippage_tolerance: Option<Decimal>,
     },
     /// Bond asset with optimal swap
@@ -71,6 +105,13 @@ pub enum ExecuteMsg {
         /// Slippage tolerance when providing LP
         slippage_tolerance: Option<Decimal>,
     },
+    /// Adds or removes pair proxies used to swap withdrawn assets into a target asset
+    UpdatePairProxies {
+        /// The list of target asset and pair proxy address to add
+        add: Option<Vec<(AssetInfo, String)>>,
+        /// The list of target asset to remove
+        remove: Option<Vec<AssetInfo>>,
+    },
     /// Creates a request to change the contract's ownership
     ProposeNewOwner {
         /// The newly proposed owner
@@ -166,6 +207,32 @@ pub enum CallbackMsg {
         /// The minimum expected amount of LP token
         minimum_receive: Option<Uint128>,
     },
+    WithdrawLiquidity {
+        /// The address to receive withdrawn assets
+        to: Addr,
+        /// The LP amount to withdraw
+        amount: Uint128,
+        /// The asset to swap all withdrawn assets into
+        target_asset: Option<AssetInfo>,
+        /// The minimum expected amount of target asset
+        minimum_receive: Option<Uint128>,
+    },
+    SwapAssets {
+        /// The previous balances of the assets in the contract
+        prev_balances: Vec<Asset>,
+        /// The asset to swap into
+        target_asset: AssetInfo,
+    },
+    SendAssets {
+        /// The address to receive assets
+        to: Addr,
+        /// The previous balances of the assets in the contract
+        prev_balances: Vec<Asset>,
+        /// The asset to check the minimum receive
+        target_asset: Option<AssetInfo>,
+        /// The minimum expected amount of target asset
+        minimum_receive: Option<Uint128>,
+    },
 }
 
 // Modified from
@@ -198,6 +265,16 @@ pub enum QueryMsg {
     RewardInfo { staker_addr: String },
     /// Returns the global state
     State {},
+    /// Returns the assets received from unbonding LP with [`ExecuteMsg::UnbondAssets`]
+    SimulateUnbondAssets {
+        amount: Uint128,
+        target_asset: Option<AssetInfo>,
+    },
+    /// Returns the list of target asset and its pair proxy
+    PairProxies {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 
     /// cw20
     /// Returns the current balance of the given address, 0 if unset.
@@ -265,6 +342,50 @@ pub struct RewardInfoResponseItem {
     pub deposit_time: u64,
     /// The deposit cost
     pub deposit_costs: Vec<Uint128>,
+    /// The LP amount charged as withdraw and exit fees when unbonding the whole balance now
+    #[serde(default)]
+    pub exit_fee: Uint128,
+}
+
+/// This enum describes how the exit fee decays over the exit period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitFeeCurve {
+    /// The fee decreases linearly to zero
+    Linear {},
+    /// The fee decreases with the square of the remaining period
+    Quadratic {},
+    /// The max fee applies until the end of the period
+    Step {},
+}
+
+/// This structure describes the exit fee which decays with time since deposit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExitFee {
+    /// The fee charged when unbonding right after deposit
+    pub max_fee: Decimal,
+    /// The number of seconds after deposit until the fee is zero
+    pub period: u64,
+    /// The decay curve of the fee
+    pub curve: ExitFeeCurve,
+    /// Leave the fee to the remaining depositors instead of sending it to the fee recipients
+    pub to_depositors: bool,
+}
+
+impl ExitFee {
+    /// Returns the fee rate after **elapsed** seconds since deposit
+    pub fn rate(&self, elapsed: u64) -> Decimal {
+        if elapsed >= self.period {
+            return Decimal::zero();
+        }
+
+        let remaining = Decimal::from_ratio(self.period - elapsed, self.period);
+        match self.curve {
+            ExitFeeCurve::Linear {} => self.max_fee * remaining,
+            ExitFeeCurve::Quadratic {} => self.max_fee * remaining * remaining,
+            ExitFeeCurve::Step {} => self.max_fee,
+        }
+    }
 }
 
 /// This structure describes a migration message.
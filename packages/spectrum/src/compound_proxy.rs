@@ -2,12 +2,25 @@ use schemars::{JsonSchema};
 use serde::{Deserialize, Serialize};
 
 use astroport::asset::{Asset, AssetInfo};
+use crate::price_guard::PriceGuard;
 
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg, Decimal, Uint128, Coin};
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdError, StdResult, WasmMsg, Decimal, Uint128, Coin};
+
+/// ## Description
+/// Validates that commission bps is in the range 0 to 9999
+pub fn validate_commission(commission_bps: u64) -> StdResult<u64> {
+    if commission_bps >= 10000u64 {
+        Err(StdError::generic_err("commission rate must be 0 to 9999"))
+    } else {
+        Ok(commission_bps)
+    }
+}
 
 /// This structure describes the basic settings for creating a contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
+    /// The owner address
+    pub owner: String,
     /// The pair contract address
     pub pair_contract: String,
     /// The swap commission
@@ -16,6 +29,8 @@ pub struct InstantiateMsg {
     pub pair_proxies: Vec<(AssetInfo, String)>,
     /// The slippage tolerance when swapping
     pub slippage_tolerance: Decimal,
+    /// The price guard to bound swaps to a reference price
+    pub price_guard: Option<PriceGuard>,
 }
 
 /// This structure describes the execute messages of the contract.
@@ -35,6 +50,36 @@ pub enum ExecuteMsg {
     },
     /// The callback of type [`CallbackMsg`]
     Callback(CallbackMsg),
+    /// Updates contract config
+    UpdateConfig {
+        /// The swap commission
+        commission_bps: Option<u64>,
+        /// The slippage tolerance when providing liquidity
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Adds or removes pair proxies used to swap reward tokens to the asset in the pair
+    UpdatePairProxies {
+        /// List of reward asset and pair proxy address to be added
+        add: Option<Vec<(AssetInfo, String)>>,
+        /// List of reward asset to be removed
+        remove: Option<Vec<AssetInfo>>,
+    },
+    /// Sets or removes the price guard used to bound swaps to a reference price
+    UpdatePriceGuard {
+        /// The new price guard, `None` disables the price guard
+        price_guard: Option<PriceGuard>,
+    },
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
 }
 
 /// This structure describes the callback messages of the contract.
@@ -73,6 +118,11 @@ pub enum QueryMsg {
     CompoundSimulation {
         rewards: Vec<Asset>,
     },
+    /// Returns the list of reward asset and its pair proxy
+    PairProxies {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 /// This structure holds the parameters that are returned from a compound simulation response
@@ -80,20 +130,26 @@ pub enum QueryMsg {
 pub struct CompoundSimulationResponse {
     /// The amount of LP returned from compound
     pub lp_amount: Uint128,
-    /// The amount of asset A to be swapped
+    /// The amount of asset A to be swapped, which is the first pair asset
     pub swap_asset_a_amount: Uint128,
-    /// The amount of asset B to be swapped
+    /// The amount of asset B to be swapped, which is the second pair asset
     pub swap_asset_b_amount: Uint128,
     /// The amount of asset A returned from swap
     pub return_a_amount: Uint128,
     /// The amount of asset B returned from swap
     pub return_b_amount: Uint128,
+    /// The amount of each pair asset to be swapped, in the order of the pair assets
+    pub swap_asset_amounts: Vec<Uint128>,
+    /// The amount of each pair asset returned from swap, in the order of the pair assets
+    pub return_asset_amounts: Vec<Uint128>,
 }
 
 /// This structure describes a migration message.
-/// We currently take no arguments for migrations.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// The owner address, required when migrating from a contract without owner
+    pub owner: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Compounder(pub Addr);
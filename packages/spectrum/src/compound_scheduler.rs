@@ -0,0 +1,102 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{Addr, Uint128};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure describes the parameters for creating a contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The owner address
+    pub owner: String,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Adds, updates or removes the vaults compounded by the scheduler
+    UpdateVaults {
+        /// The list of vault schedules to add or update
+        add: Option<Vec<VaultSchedule>>,
+        /// The list of farm addresses to remove
+        remove: Option<Vec<String>>,
+    },
+    /// Compounds the farms which are due. Anyone can execute this message.
+    Compound {
+        /// The list of farm addresses to compound
+        farms: Vec<String>,
+    },
+    /// Sends the whole balance of the assets to the recipient, such as the compound bounty paid by
+    /// permissionless farms which the scheduler does not control. Only the owner can execute this message.
+    Sweep {
+        /// The assets to send
+        asset_infos: Vec<AssetInfo>,
+        /// The recipient address
+        recipient: String,
+    },
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This structure describes when a vault is due to compound
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultSchedule {
+    /// The astroport_farm contract address. The scheduler must be the controller of the farm,
+    /// or the farm must allow permissionless compound, in which case the bounty is kept by the scheduler.
+    pub farm: String,
+    /// The vault is due when the pending base reward reaches this amount
+    pub min_pending_reward: Uint128,
+    /// The vault is due when it is not compounded for this number of seconds
+    pub max_staleness: u64,
+}
+
+/// This structure describes query messages available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the schedule of the farm
+    Vault { farm: String },
+    /// Returns the list of vault schedules
+    Vaults {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the list of farms which are due to compound
+    DueVaults {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+/// This structure holds the schedule and compound state of a vault
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultInfo {
+    /// The astroport_farm contract address
+    pub farm: Addr,
+    /// The LP staking generator contract address of the farm
+    pub staking_contract: Addr,
+    /// The LP token contract address of the farm
+    pub liquidity_token: Addr,
+    /// The vault is due when the pending base reward reaches this amount
+    pub min_pending_reward: Uint128,
+    /// The vault is due when it is not compounded for this number of seconds
+    pub max_staleness: u64,
+    /// The timestamp of the last compound, in seconds
+    pub last_compound: u64,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
@@ -1,4 +1,5 @@
 use astroport::asset::{Asset, AssetInfo};
+use crate::price_guard::PriceGuard;
 use cosmwasm_std::{Uint128};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -16,6 +17,8 @@ pub struct InstantiateMsg {
     pub stablecoin: AssetInfo,
     /// The beneficiary addresses to received fees in stablecoin
     pub target_list: Vec<(String, u64)>,
+    /// The price guard to bound swaps to a reference price
+    pub price_guard: Option<PriceGuard>,
 }
 
 /// This structure describes the functions that can be executed in this contract.
@@ -45,6 +48,11 @@ pub enum ExecuteMsg {
         /// List of asset to be removed
         remove: Option<Vec<AssetInfo>>,
     },
+    /// Sets or removes the price guard used to bound swaps to a reference price
+    UpdatePriceGuard {
+        /// The new price guard, `None` disables the price guard
+        price_guard: Option<PriceGuard>,
+    },
     /// Swap fee tokens via bridge assets
     SwapBridgeAssets { assets: Vec<AssetInfo>, depth: u64 },
     /// Distribute stablecoin to beneficiary
@@ -1,7 +1,10 @@
 pub mod adapters;
 pub mod astroport_farm;
 pub mod compound_proxy;
+pub mod compound_scheduler;
 pub mod fees_collector;
 pub mod helper;
 pub mod pair_proxy;
+pub mod price_guard;
 pub mod lp_staking;
+pub mod vault_factory;
@@ -1,14 +1,17 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use astroport::asset::{Asset, AssetInfo};
 use cosmwasm_std::{Decimal, Uint128};
 use cw20::Cw20ReceiveMsg;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub owner: String,
-    pub reward_token: String,
-    pub staking_token: String,
+    /// The reward asset, cw20 or native
+    pub reward_token: AssetInfo,
+    /// The staking asset, cw20 or native
+    pub staking_token: AssetInfo,
     pub distribution_schedule: Vec<(u64, u64, Uint128)>,
 }
 
@@ -17,13 +20,34 @@ pub struct InstantiateMsg {
 pub enum ExecuteMsg {
     /// Receives a message of type [`Cw20ReceiveMsg`]
     Receive(Cw20ReceiveMsg),
+    /// Bond native staking token sent in funds
+    Bond {
+        staker_addr: Option<String>,
+        /// Locks the bonded amount for the duration of a lock tier to earn its multiplier
+        lock_duration: Option<u64>,
+    },
     /// Unbond token
     Unbond { amount: Uint128 },
     /// Withdraw pending rewards
     Withdraw { amount: Option<Uint128> },
+    /// Claim unbonded tokens whose unbonding period has passed
+    ClaimUnbonded {},
+    /// Creates a schedule funded by the native reward token sent in funds
+    FundSchedule { start: u64, end: u64 },
     UpdateConfig {
         distribution_schedule: Option<Vec<(u64, u64, Uint128)>>,
+        /// The seconds unbonded tokens wait before they can be claimed
+        unbonding_period: Option<u64>,
+        /// The minimum amount to fund a schedule with
+        min_fund_amount: Option<Uint128>,
     },
+    /// Adds a reward stream of an additional asset, or updates the schedule of an existing stream
+    UpdateRewardStream {
+        asset_info: AssetInfo,
+        distribution_schedule: Vec<(u64, u64, Uint128)>,
+    },
+    /// Replaces the lock tiers available for new locks
+    UpdateLockTiers { lock_tiers: Vec<LockTier> },
     /// Creates a request to change the contract's ownership
     ProposeNewOwner {
         /// The newly proposed owner
@@ -35,12 +59,21 @@ pub enum ExecuteMsg {
     DropOwnershipProposal {},
     /// Claims contract ownership
     ClaimOwnership {},
+    /// Sums the pending rewards of the next stakers into the totals after a migration,
+    /// other messages are rejected until all stakers are summed
+    BackfillPendingRewards { limit: Option<u32> },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
-    Bond { staker_addr: Option<String> },
+    Bond {
+        staker_addr: Option<String>,
+        /// Locks the bonded amount for the duration of a lock tier to earn its multiplier
+        lock_duration: Option<u64>,
+    },
+    /// Creates a schedule funded by the received reward token
+    FundSchedule { start: u64, end: u64 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -59,14 +92,102 @@ pub enum QueryMsg {
         limit: Option<u32>,
         time_seconds: Option<u64>,
     },
+    /// Returns the locks of a staker
+    Locks {
+        staker_addr: String,
+    },
+    /// Returns the pending and claimable unbondings of a staker
+    Unbondings {
+        staker_addr: String,
+    },
+    /// Returns the remaining and unfunded amounts of the reward schedules
+    Funding {},
+    /// Returns the bond amount of a staker at the beginning of the given block height
+    BondAmountAt { staker: String, height: u64 },
+    /// Returns the total bond amount at the beginning of the given block height
+    TotalBondAt { height: u64 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
     pub owner: String,
+    /// The reward token contract address or native denom
     pub reward_token: String,
+    /// The staking token contract address or native denom
     pub staking_token: String,
+    pub reward_token_info: AssetInfo,
+    pub staking_token_info: AssetInfo,
     pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+    pub reward_streams: Vec<RewardStream>,
+    pub lock_tiers: Vec<LockTier>,
+    pub unbonding_period: u64,
+    pub min_fund_amount: Uint128,
+}
+
+/// This structure describes an additional reward asset distributed to stakers
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardStream {
+    /// The reward asset, cw20 or native
+    pub asset_info: AssetInfo,
+    /// The list of (start time, end time, amount) to distribute
+    pub distribution_schedule: Vec<(u64, u64, Uint128)>,
+}
+
+/// This structure describes a lock duration and the reward multiplier it earns
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockTier {
+    /// The lock duration in seconds
+    pub duration: u64,
+    /// The reward multiplier of the locked amount, at least 1
+    pub multiplier: Decimal,
+}
+
+/// This structure describes a bonded amount that cannot be unbonded until unlock_time
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Lock {
+    pub amount: Uint128,
+    pub multiplier: Decimal,
+    pub unlock_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LocksResponse {
+    pub staker_addr: String,
+    /// The bond amount weighted by the lock multipliers
+    pub weighted_bond_amount: Uint128,
+    pub locks: Vec<Lock>,
+}
+
+/// This structure describes an unbonded amount waiting for its unbonding period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Unbonding {
+    pub amount: Uint128,
+    pub claimable_time: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondingsResponse {
+    pub staker_addr: String,
+    /// The unbondings which are not claimable yet
+    pub pending: Vec<Unbonding>,
+    /// The total amount which can be claimed now
+    pub claimable_amount: Uint128,
+}
+
+/// This structure describes how much of a reward asset is still to be distributed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RewardFunding {
+    pub asset_info: AssetInfo,
+    /// The amount of the schedules which is not distributed yet
+    pub remaining_amount: Uint128,
+    /// The part of the remaining amount which is not covered by the contract balance
+    pub unfunded_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FundingResponse {
+    pub reward: RewardFunding,
+    pub reward_streams: Vec<RewardFunding>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -74,6 +195,10 @@ pub struct StateResponse {
     pub last_distributed: u64,
     pub total_bond_amount: Uint128,
     pub global_reward_index: Decimal,
+    /// The global reward index of each reward stream
+    pub stream_reward_indexes: Vec<Decimal>,
+    /// The total bond amount weighted by the lock multipliers
+    pub total_weighted_bond_amount: Uint128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -88,6 +213,8 @@ pub struct RewardInfoResponseItem {
     pub bond_amount: Uint128,
     pub reward_index: Decimal,
     pub pending_reward: Uint128,
+    /// The pending rewards of each reward stream
+    pub stream_pending_rewards: Vec<Asset>,
 }
 
 /// This structure describes a migration message.
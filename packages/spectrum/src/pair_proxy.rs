@@ -3,22 +3,109 @@ use serde::{Deserialize, Serialize};
 
 use astroport::asset::{Asset, AssetInfo};
 
-use cosmwasm_std::{Decimal};
+use cosmwasm_std::{to_binary, Addr, CosmosMsg, Decimal, StdResult, Uint128, WasmMsg};
 use cw20::Cw20ReceiveMsg;
-use crate::adapters::router::RouterType;
+use crate::adapters::router::{RouterType, SwapOperation};
 
 /// Maximum assets in the swap route
 pub const MAX_ASSETS: usize = 50;
 
+/// Maximum candidate routes
+pub const MAX_ROUTES: usize = 5;
+
+/// This enum describes how a hop is swapped.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Dex {
+    /// Swaps through the route's router with an AstroSwap operation
+    AstroSwap,
+    /// Swaps through the route's router with a TerraSwap operation
+    TerraSwap,
+    /// Swaps through the route's router with a TokenSwap operation
+    TokenSwap,
+    /// Swaps by calling the pair contract directly
+    Pair { contract_addr: Addr },
+}
+
+impl From<&RouterType> for Dex {
+    fn from(router_type: &RouterType) -> Self {
+        match router_type {
+            RouterType::AstroSwap => Dex::AstroSwap,
+            RouterType::TerraSwap => Dex::TerraSwap,
+            RouterType::TokenSwap => Dex::TokenSwap,
+        }
+    }
+}
+
+/// This structure describes a single swap of a route.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Hop {
+    pub offer_asset_info: AssetInfo,
+    pub ask_asset_info: AssetInfo,
+    pub dex: Dex,
+    /// The pair the router swaps the hop on, used to simulate the spread and commission of the hop.
+    /// Without it, simulations report the spread of the route as unknown.
+    #[serde(default)]
+    pub pair_addr: Option<Addr>,
+}
+
+impl Hop {
+    /// Returns the hop in the opposite direction
+    pub fn reverse(&self) -> Hop {
+        Hop {
+            offer_asset_info: self.ask_asset_info.clone(),
+            ask_asset_info: self.offer_asset_info.clone(),
+            dex: self.dex.clone(),
+            pair_addr: self.pair_addr.clone(),
+        }
+    }
+
+    /// Returns the pair to simulate the hop on, or `None` when only the router can simulate it
+    pub fn simulation_pair(&self) -> Option<Addr> {
+        match &self.dex {
+            Dex::Pair { contract_addr } => Some(contract_addr.clone()),
+            _ => self.pair_addr.clone(),
+        }
+    }
+
+    /// Returns the router operation of the hop, or `None` when the hop calls the pair directly
+    pub fn swap_operation(&self) -> Option<SwapOperation> {
+        let offer_asset_info = self.offer_asset_info.clone();
+        let ask_asset_info = self.ask_asset_info.clone();
+        match self.dex {
+            Dex::AstroSwap => Some(SwapOperation::AstroSwap {
+                offer_asset_info,
+                ask_asset_info,
+            }),
+            Dex::TerraSwap => Some(SwapOperation::TerraSwap {
+                offer_asset_info,
+                ask_asset_info,
+            }),
+            Dex::TokenSwap => Some(SwapOperation::TokenSwap {
+                offer_asset_info,
+                ask_asset_info,
+            }),
+            Dex::Pair { .. } => None,
+        }
+    }
+}
+
+/// This structure describes a candidate swap route.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RouteInfo {
+    /// The router contract address, required when any hop swaps through the router
+    pub router: Option<String>,
+    /// The list of hops from the offer asset to the ask asset
+    pub hops: Vec<Hop>,
+}
+
 /// This structure describes the basic settings for creating a contract.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
-    /// The list of asset in the swap route
-    pub asset_infos: Vec<AssetInfo>,
-    /// The router contract address
-    pub router: String,
-    /// The type of router
-    pub router_type: RouterType,
+    /// The owner address
+    pub owner: String,
+    /// The candidate swap routes, all from the same offer asset to the same ask asset
+    pub routes: Vec<RouteInfo>,
     /// The decimal precision of the offer asset
     pub offer_precision: Option<u8>,
     /// The decimal precision of the ask asset
@@ -35,6 +122,8 @@ pub enum ExecuteMsg {
     Swap {
         /// Offer asset
         offer_asset: Asset,
+        /// Ask asset info, must be the other asset of the pair when set
+        ask_asset_info: Option<AssetInfo>,
         /// Belief price of the asset
         belief_price: Option<Decimal>,
         /// Maximum spread from the belief price
@@ -42,6 +131,55 @@ pub enum ExecuteMsg {
         /// Receiver address
         to: Option<String>,
     },
+    /// The callback of type [`CallbackMsg`]
+    Callback(CallbackMsg),
+    /// Replaces, appends or removes a route after simulating the new route through the router
+    UpdateRoute {
+        /// The index of the route, the number of routes appends a new route
+        index: u32,
+        /// The new route, `None` removes the route
+        route: Option<RouteInfo>,
+    },
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This structure describes the callback messages of the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CallbackMsg {
+    /// Swaps the amount received from the previous hop through the remaining hops
+    SwapHops {
+        router: Option<Addr>,
+        hops: Vec<Hop>,
+        prev_balance: Asset,
+        max_spread: Option<Decimal>,
+    },
+    /// Sends the ask asset received from the routes to the receiver
+    TransferOutput {
+        prev_balance: Asset,
+        minimum_receive: Option<Uint128>,
+        to: String,
+    },
+}
+
+impl CallbackMsg {
+    pub fn into_cosmos_msg(&self, contract_addr: &Addr) -> StdResult<CosmosMsg> {
+        Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: String::from(contract_addr),
+            msg: to_binary(&ExecuteMsg::Callback(self.clone()))?,
+            funds: vec![],
+        }))
+    }
 }
 
 /// ## Description
@@ -51,6 +189,8 @@ pub enum ExecuteMsg {
 pub enum Cw20HookMsg {
     /// Sell a given amount of asset
     Swap {
+        /// Ask asset info, must be the other asset of the pair when set
+        ask_asset_info: Option<AssetInfo>,
         /// Belief price of the asset
         belief_price: Option<Decimal>,
         /// Maximum spread from the belief price        
@@ -75,9 +215,69 @@ pub enum QueryMsg {
         /// Ask asset info when there are more than two assets in the pool
         ask_asset_info: Option<AssetInfo>,
     },
+    /// Returns the offer amount required to receive the ask asset in a [`ReverseSimulationResponse`] object.
+    ReverseSimulation {
+        /// Offer asset info when there are more than two assets in the pool
+        offer_asset_info: Option<AssetInfo>,
+        /// Ask asset
+        ask_asset: Asset,
+    },
+    /// Returns the versions of the routes, oldest first
+    RouteHistory {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+/// This structure holds the parameters that are returned from a swap simulation response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulationResponse {
+    /// The amount of ask assets returned by the swap
+    pub return_amount: Uint128,
+    /// The spread used in the swap operation
+    pub spread_amount: Uint128,
+    /// The amount of fees charged by the transaction
+    pub commission_amount: Uint128,
+    /// Whether a split swaps through router hops without `pair_addr`,
+    /// whose spread and commission are unknown and not included in the amounts
+    pub spread_unknown: bool,
+    /// The part of the offer amount swapped through each route
+    pub splits: Vec<RouteSplit>,
+}
+
+/// This structure holds the parameters that are returned from a reverse swap simulation response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReverseSimulationResponse {
+    /// The amount of offer assets required by the swap
+    pub offer_amount: Uint128,
+    /// The spread used in the swap operation
+    pub spread_amount: Uint128,
+    /// The amount of fees charged by the transaction
+    pub commission_amount: Uint128,
+    /// Whether the route swaps through router hops without `pair_addr`,
+    /// whose spread and commission are unknown and not included in the amounts
+    pub spread_unknown: bool,
+    /// The route chosen for the swap
+    pub splits: Vec<RouteSplit>,
+}
+
+/// This structure describes the part of a swap going through one route.
+/// Spread and commission of intermediate hops are converted to the ask asset.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RouteSplit {
+    /// The index of the route in the config
+    pub route: u32,
+    pub offer_amount: Uint128,
+    pub return_amount: Uint128,
+    pub spread_amount: Uint128,
+    pub commission_amount: Uint128,
+    /// Whether the route swaps through router hops without `pair_addr`
+    pub spread_unknown: bool,
 }
 
 /// This structure describes a migration message.
-/// We currently take no arguments for migrations.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct MigrateMsg {
+    /// The owner address, required when migrating from a contract without owner
+    pub owner: Option<String>,
+}
@@ -0,0 +1,217 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::{Addr, Api, Decimal, QuerierWrapper, StdError, StdResult, Uint128};
+
+use crate::adapters::oracle::Oracle;
+
+/// ## Description
+/// This enum describes where the reference price of an asset pair is read from.
+/// Only time-weighted sources are supported, a spot price can be moved within the same block as the swap.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceSource {
+    /// Astroport-style TWAP oracle of the asset pair
+    Oracle { contract_addr: Addr },
+}
+
+impl PriceSource {
+    /// ## Description
+    /// Validates the address of the price source.
+    pub fn check(&self, api: &dyn Api) -> StdResult<()> {
+        match self {
+            PriceSource::Oracle { contract_addr } => {
+                api.addr_validate(contract_addr.as_str())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// ## Description
+    /// Returns the amount of **ask_asset_info** that **offer_asset** is worth according to the price source.
+    pub fn query_return_amount(
+        &self,
+        querier: &QuerierWrapper,
+        offer_asset: &Asset,
+        ask_asset_info: &AssetInfo,
+    ) -> StdResult<Uint128> {
+        match self {
+            PriceSource::Oracle { contract_addr } => {
+                let prices =
+                    Oracle(contract_addr.clone()).consult(querier, &offer_asset.info, offer_asset.amount)?;
+                let (_, amount) = prices
+                    .into_iter()
+                    .find(|(asset_info, _)| asset_info.equal(ask_asset_info))
+                    .ok_or_else(|| {
+                        StdError::generic_err(format!("Oracle has no price for {}", ask_asset_info))
+                    })?;
+                amount
+                    .try_into()
+                    .map_err(|_| StdError::generic_err("overflow"))
+            }
+        }
+    }
+}
+
+/// ## Description
+/// This structure describes the settings to bound swaps to a reference price.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceGuard {
+    /// The maximum deviation allowed between the swap return and the reference price
+    pub max_deviation: Decimal,
+    /// The list of asset pairs and the source of their reference price
+    pub price_sources: Vec<(AssetInfo, AssetInfo, PriceSource)>,
+}
+
+impl PriceGuard {
+    /// ## Description
+    /// Validates the max deviation and the price sources.
+    pub fn check(&self, api: &dyn Api) -> StdResult<()> {
+        if self.max_deviation > Decimal::one() {
+            return Err(StdError::generic_err("max_deviation must be 0 to 1"));
+        }
+        for (asset_a, asset_b, price_source) in self.price_sources.iter() {
+            asset_a.check(api)?;
+            asset_b.check(api)?;
+            if asset_a.equal(asset_b) {
+                return Err(StdError::generic_err("price source assets must be different"));
+            }
+            price_source.check(api)?;
+        }
+        Ok(())
+    }
+
+    /// ## Description
+    /// Returns the price source registered for the asset pair in either direction.
+    pub fn find_price_source(
+        &self,
+        offer_asset_info: &AssetInfo,
+        ask_asset_info: &AssetInfo,
+    ) -> StdResult<&PriceSource> {
+        self.price_sources
+            .iter()
+            .find(|(asset_a, asset_b, _)| {
+                (asset_a.equal(offer_asset_info) && asset_b.equal(ask_asset_info))
+                    || (asset_a.equal(ask_asset_info) && asset_b.equal(offer_asset_info))
+            })
+            .map(|(_, _, price_source)| price_source)
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "No price source for {}-{}",
+                    offer_asset_info, ask_asset_info
+                ))
+            })
+    }
+
+    /// ## Description
+    /// Returns the belief price of the swap derived from the reference price, expressed as the amount
+    /// of offer asset per ask asset. When **precisions** (offer, ask) are given, the price is expressed
+    /// in whole tokens like `pair_proxy` expects, otherwise in the smallest unit like Astroport pairs expect.
+    pub fn query_belief_price(
+        &self,
+        querier: &QuerierWrapper,
+        offer_asset: &Asset,
+        ask_asset_info: &AssetInfo,
+        precisions: Option<(u8, u8)>,
+    ) -> StdResult<Decimal> {
+        let return_amount = self
+            .find_price_source(&offer_asset.info, ask_asset_info)?
+            .query_return_amount(querier, offer_asset, ask_asset_info)?;
+        if return_amount.is_zero() {
+            return Err(StdError::generic_err(format!(
+                "Zero reference price for {}-{}",
+                offer_asset.info, ask_asset_info
+            )));
+        }
+
+        let (offer_precision, ask_precision) = precisions.unwrap_or_default();
+        if ask_precision >= offer_precision {
+            let offer_amount = offer_asset
+                .amount
+                .checked_mul(Uint128::from(10u128.pow((ask_precision - offer_precision) as u32)))?;
+            Ok(Decimal::from_ratio(offer_amount, return_amount))
+        } else {
+            let return_amount = return_amount
+                .checked_mul(Uint128::from(10u128.pow((offer_precision - ask_precision) as u32)))?;
+            Ok(Decimal::from_ratio(offer_asset.amount, return_amount))
+        }
+    }
+}
+
+/// ## Description
+/// Returns the belief price and max spread to use for swapping **offer_asset** to **ask_asset_info**.
+/// When **price_guard** is set, the belief price is derived from the reference price so the swap fails
+/// when the pool deviates more than `max_deviation`, otherwise the swap is only bounded by **default_max_spread**.
+pub fn query_swap_limits(
+    querier: &QuerierWrapper,
+    price_guard: Option<&PriceGuard>,
+    offer_asset: &Asset,
+    ask_asset_info: &AssetInfo,
+    precisions: Option<(u8, u8)>,
+    default_max_spread: Decimal,
+) -> StdResult<(Option<Decimal>, Option<Decimal>)> {
+    match price_guard {
+        Some(price_guard) => Ok((
+            Some(price_guard.query_belief_price(querier, offer_asset, ask_asset_info, precisions)?),
+            Some(price_guard.max_deviation),
+        )),
+        None => Ok((Some(Decimal::MAX), Some(default_max_spread))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockApi;
+
+    fn price_guard() -> PriceGuard {
+        PriceGuard {
+            max_deviation: Decimal::percent(2),
+            price_sources: vec![(
+                AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                AssetInfo::Token {
+                    contract_addr: Addr::unchecked("astro"),
+                },
+                PriceSource::Oracle {
+                    contract_addr: Addr::unchecked("oracle"),
+                },
+            )],
+        }
+    }
+
+    #[test]
+    fn check() {
+        let api = MockApi::default();
+        assert!(price_guard().check(&api).is_ok());
+
+        let mut guard = price_guard();
+        guard.max_deviation = Decimal::percent(101);
+        assert!(guard.check(&api).is_err());
+
+        let mut guard = price_guard();
+        guard.price_sources[0].1 = guard.price_sources[0].0.clone();
+        assert!(guard.check(&api).is_err());
+    }
+
+    #[test]
+    fn find_price_source() {
+        let guard = price_guard();
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let astro = AssetInfo::Token {
+            contract_addr: Addr::unchecked("astro"),
+        };
+        let expected = PriceSource::Oracle {
+            contract_addr: Addr::unchecked("oracle"),
+        };
+        assert_eq!(guard.find_price_source(&uluna, &astro).unwrap(), &expected);
+        assert_eq!(guard.find_price_source(&astro, &uluna).unwrap(), &expected);
+        assert!(guard.find_price_source(&astro, &AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        }).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{Addr, Binary, Decimal};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// This structure describes the parameters for creating a contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The owner address
+    pub owner: String,
+    /// The code ID of the astroport_farm contract
+    pub farm_code_id: u64,
+    /// The code ID of the compound_proxy contract
+    pub compound_proxy_code_id: u64,
+    /// The LP staking generator contract address
+    pub staking_contract: String,
+    /// The base reward token contract address
+    pub base_reward_token: String,
+    /// The controller address to execute compound
+    pub controller: String,
+    /// The performance fee
+    pub fee: Decimal,
+    /// The fee collector contract address
+    pub fee_collector: String,
+    /// The default swap commission of the pairs, in bps
+    pub commission_bps: u64,
+    /// The default slippage tolerance when providing LP
+    pub slippage_tolerance: Decimal,
+}
+
+/// This structure describes the execute messages available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Update the template config used for new vaults
+    UpdateConfig {
+        /// The code ID of the astroport_farm contract
+        farm_code_id: Option<u64>,
+        /// The code ID of the compound_proxy contract
+        compound_proxy_code_id: Option<u64>,
+        /// The LP staking generator contract address
+        staking_contract: Option<String>,
+        /// The base reward token contract address
+        base_reward_token: Option<String>,
+        /// The controller address
+        controller: Option<String>,
+        /// The performance fee
+        fee: Option<Decimal>,
+        /// The fee collector contract address
+        fee_collector: Option<String>,
+        /// The default swap commission of the pairs, in bps
+        commission_bps: Option<u64>,
+        /// The default slippage tolerance when providing LP
+        slippage_tolerance: Option<Decimal>,
+    },
+    /// Instantiate farm and compound proxy for the Astroport pair
+    CreateVault {
+        /// The pair contract address
+        pair: String,
+        /// The name of the farm token
+        name: String,
+        /// The symbol of the farm token
+        symbol: String,
+        /// The swap commission of the pair in bps, defaults to the config
+        commission_bps: Option<u64>,
+        /// The list of reward asset and pair proxy to swap it to a pair asset
+        pair_proxies: Vec<(AssetInfo, String)>,
+    },
+    /// Push controller, fee and fee collector in the config to the vaults
+    PropagateConfig {
+        /// The LP token address to start after
+        start_after: Option<String>,
+        /// The number of vaults to update
+        limit: Option<u32>,
+    },
+    /// Execute an owner-only message on a contract of a vault, as the vaults are owned by this contract
+    ExecuteVault {
+        /// The LP token address of the vault
+        liquidity_token: String,
+        /// The vault contract to execute
+        target: VaultContract,
+        /// The message to execute
+        msg: Binary,
+    },
+    /// Creates a request to change the contract's ownership
+    ProposeNewOwner {
+        /// The newly proposed owner
+        owner: String,
+        /// The validity period of the proposal to change the owner
+        expires_in: u64,
+    },
+    /// Removes a request to change contract ownership
+    DropOwnershipProposal {},
+    /// Claims contract ownership
+    ClaimOwnership {},
+}
+
+/// This enum describes the contracts of a vault
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultContract {
+    Farm,
+    CompoundProxy,
+}
+
+/// This structure describes query messages available in the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the contract config
+    Config {},
+    /// Returns the vault of the LP token
+    Vault { liquidity_token: String },
+    /// Returns the list of vaults
+    Vaults {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+/// This structure holds the contracts of a vault
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultInfo {
+    /// The pair contract address
+    pub pair: Addr,
+    /// The LP token contract address
+    pub liquidity_token: Addr,
+    /// The astroport_farm contract address
+    pub farm: Addr,
+    /// The compound_proxy contract address
+    pub compound_proxy: Addr,
+}
+
+/// This structure describes a migration message.
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}